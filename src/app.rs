@@ -3,8 +3,24 @@
 //! This module implements the "Model" part of the Model-View-Update pattern.
 //! It holds all application state and provides methods to update it.
 
-use regex::Regex;
-use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::{Config, DEFAULT_SCAN_INTERVAL, DEFAULT_STATUS_DURATION};
+use crate::events::{diff_entries, EventLog, PortEvent};
+use crate::group::{self, Group, GroupBy, GroupKey, TableRow};
+use crate::history::ProcessHistory;
+use crate::inventory::{HostChoice, Inventory};
+use crate::keymap::KeyMap;
+use crate::palette;
+use crate::pins::{Pin, Pins};
+use crate::query::{Query, QueryError};
+use crate::rules::{HealthAlert, RuleEngine, Severity, RUNAWAY_RULE};
+use crate::scanner::Signal;
+use crate::theme::Theme;
+use crate::views::{View, Views};
 
 /// Represents a single port entry with associated process information
 #[derive(Debug, Clone)]
@@ -23,14 +39,53 @@ pub struct PortEntry {
     pub memory_usage: u64,
     /// Memory usage formatted as human-readable string
     pub memory_display: String,
-    /// Whether this process has a parent (used for zombie detection)
-    pub has_parent: bool,
-    /// Whether this entry is flagged as a "zombie" (high CPU + orphaned)
+    /// Local bind address (e.g. "0.0.0.0", "127.0.0.1", "::"), if known
+    pub address: Option<String>,
+    /// Owner of the process (user name, or UID when it can't be resolved)
+    pub user: Option<String>,
+    /// Parent process ID, if known
+    pub parent_pid: Option<u32>,
+    /// Parent process name, if known
+    pub parent_name: Option<String>,
+    /// Whether the leader of the process's session is still running, if known
+    pub session_leader_alive: Option<bool>,
+    /// Scheduler state of the process (running, sleeping, zombie, ...)
+    pub state: ProcessState,
+    /// Whether the process is defunct (exited but not yet reaped by its parent)
     pub is_zombie: bool,
+    /// Whether the process outlived its session and was re-parented to init or a subreaper
+    pub is_orphan: bool,
+    /// Health rules that currently match this entry
+    pub alerts: Vec<HealthAlert>,
+    /// Full command line (arguments joined by spaces), if known
+    pub command: Option<String>,
+    /// Path to the process executable, if known
+    pub exe_path: Option<String>,
+    /// Current working directory of the process, if known
+    pub cwd: Option<String>,
+    /// Process start time as seconds since the Unix epoch, if known
+    pub start_time: Option<u64>,
+    /// Processes above this one up to (not including) init, outermost first
+    pub ancestors: Vec<Ancestor>,
+    /// Established TCP connections on this port, if known (None for UDP)
+    pub connections: Option<usize>,
+    /// Host the entry was scanned on, in the fleet view (None on a single host's tab)
+    pub host: Option<String>,
+}
+
+/// Identifies a row across scans: port, protocol, PID and (in the fleet view) host
+pub type EntryKey = (u16, Protocol, u32, Option<String>);
+
+/// A process in a listener's ancestry, e.g. the shell that started a dev server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ancestor {
+    pub pid: u32,
+    pub name: String,
 }
 
 /// Network protocol type
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Protocol {
     Tcp,
     Udp,
@@ -45,6 +100,54 @@ impl std::fmt::Display for Protocol {
     }
 }
 
+/// Process scheduler state, normalized across platforms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProcessState {
+    Running,
+    Sleeping,
+    /// Uninterruptible sleep (usually waiting on disk I/O)
+    DiskSleep,
+    Idle,
+    Stopped,
+    /// Exited but not yet reaped by its parent (defunct)
+    Zombie,
+    Dead,
+    #[default]
+    Unknown,
+}
+
+impl ProcessState {
+    /// Parse the first character of a `ps -o stat` value (e.g. "Ss", "Z+", "R")
+    pub fn from_ps_stat(stat: &str) -> Self {
+        match stat.trim().chars().next() {
+            Some('R') => ProcessState::Running,
+            Some('S') => ProcessState::Sleeping,
+            Some('D' | 'U') => ProcessState::DiskSleep,
+            Some('I') => ProcessState::Idle,
+            Some('T' | 't') => ProcessState::Stopped,
+            Some('Z') => ProcessState::Zombie,
+            Some('X') => ProcessState::Dead,
+            _ => ProcessState::Unknown,
+        }
+    }
+}
+
+impl std::fmt::Display for ProcessState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ProcessState::Running => "Running",
+            ProcessState::Sleeping => "Sleeping",
+            ProcessState::DiskSleep => "Disk sleep",
+            ProcessState::Idle => "Idle",
+            ProcessState::Stopped => "Stopped",
+            ProcessState::Zombie => "Zombie (defunct)",
+            ProcessState::Dead => "Dead",
+            ProcessState::Unknown => "Unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 /// Status message types for the footer
 #[derive(Debug, Clone)]
 pub enum StatusMessage {
@@ -57,15 +160,20 @@ pub enum StatusMessage {
 }
 
 /// Column to sort by
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SortColumn {
     #[default]
     Port,
     Protocol,
     Pid,
+    #[serde(rename = "name")]
     ProcessName,
+    #[serde(rename = "cpu")]
     CpuUsage,
+    #[serde(rename = "memory")]
     MemoryUsage,
+    Uptime,
 }
 
 impl SortColumn {
@@ -77,16 +185,19 @@ impl SortColumn {
             SortColumn::Pid => SortColumn::ProcessName,
             SortColumn::ProcessName => SortColumn::CpuUsage,
             SortColumn::CpuUsage => SortColumn::MemoryUsage,
-            SortColumn::MemoryUsage => SortColumn::Port,
+            SortColumn::MemoryUsage => SortColumn::Uptime,
+            SortColumn::Uptime => SortColumn::Port,
         }
     }
 }
 
 /// Sort order
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum SortOrder {
     #[default]
+    #[serde(rename = "asc")]
     Ascending,
+    #[serde(rename = "desc")]
     Descending,
 }
 
@@ -100,40 +211,202 @@ impl SortOrder {
     }
 }
 
+/// Where the detail pane goes relative to the table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DetailsLayout {
+    /// Beside the table when the terminal is wide enough, otherwise below it
+    #[default]
+    Right,
+    /// Always below the table
+    Bottom,
+}
+
+/// What a confirmed bulk action does to each marked process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkKind {
+    Kill,
+    Signal(Signal),
+}
+
+/// A process a bulk action applies to, with its marked ports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkTarget {
+    /// Host the process runs on, in the fleet view
+    pub host: Option<String>,
+    pub pid: u32,
+    pub name: String,
+    pub ports: Vec<u16>,
+}
+
+/// A bulk action waiting for confirmation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingBulk {
+    pub kind: BulkKind,
+    /// One target per process, in table order
+    pub targets: Vec<BulkTarget>,
+}
+
+impl PendingBulk {
+    /// Question shown in the confirmation popup, e.g. "Kill 3 processes?"
+    pub fn question(&self) -> String {
+        let count = match self.targets.len() {
+            1 => "1 process".to_string(),
+            n => format!("{} processes", n),
+        };
+        match self.kind {
+            BulkKind::Kill => format!("Kill {}?", count),
+            BulkKind::Signal(signal) => format!("Send SIG{} to {}?", signal.name(), count),
+        }
+    }
+}
+
+/// Signal that toggles pausing: SIGCONT if every process is stopped, otherwise SIGSTOP
+pub fn pause_signal(entries: &[&PortEntry]) -> Signal {
+    if !entries.is_empty() && entries.iter().all(|e| e.state == ProcessState::Stopped) {
+        Signal::CONT
+    } else {
+        Signal::STOP
+    }
+}
+
+/// Tab bar label of the fleet view
+pub const FLEET_LABEL: &str = "all hosts";
+
+/// Tab label of the local machine
+pub const LOCAL_LABEL: &str = "localhost";
+
+/// A change to the open host tabs, carried out by the main loop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TabRequest {
+    Next,
+    Previous,
+    /// Switch to the tab at this index
+    Select(usize),
+    /// Open a tab for a remote host (or switch to it if it's already open)
+    Open {
+        host: String,
+        identity: Option<PathBuf>,
+    },
+    /// Close the current tab
+    Close,
+    /// Open the fleet view of every host, or close it if it's open
+    ToggleFleet,
+}
+
 /// Main application state
 pub struct App {
     /// List of port entries currently being displayed
     pub entries: Vec<PortEntry>,
-    /// Currently selected index in the table
+    /// Currently selected row in the table (an index into `rows` while grouped)
     pub selected_index: usize,
+    /// What entries are grouped by (None shows the flat table)
+    pub group_by: Option<GroupBy>,
+    /// Groups of `entries` while grouped
+    pub groups: Vec<Group>,
+    /// Rows of the grouped table
+    pub rows: Vec<TableRow>,
+    /// Nesting level of each of `rows`
+    pub row_depths: Vec<usize>,
+    /// Groups expanded or collapsed by the user (see [`group::is_expanded`])
+    pub toggled_groups: HashSet<GroupKey>,
     /// Status message to display in the footer
     pub status_message: StatusMessage,
     /// When the status message was set (for auto-clearing)
     pub status_timestamp: Instant,
     /// Duration to display status messages
     pub status_duration: Duration,
+    /// Time between scans
+    pub scan_interval: Duration,
+    /// Loaded configuration file (defaults, per-host options)
+    pub config: Config,
+    /// Key to action bindings
+    pub keymap: KeyMap,
+    /// Colors used by the UI
+    pub theme: Theme,
     /// Whether the application should quit
     pub should_quit: bool,
     /// Current sort column
     pub sort_column: SortColumn,
     /// Current sort order
     pub sort_order: SortOrder,
-    /// Filter string for process names
+    /// Filter query text (see `query` for the syntax)
     pub filter: String,
     /// Whether filter input mode is active
     pub filter_mode: bool,
     /// Whether to show the help popup
     pub show_help: bool,
-    /// Compiled regex for filtering (None if filter is plain text or invalid regex)
-    compiled_regex: Option<Regex>,
-    /// Whether the current filter is being treated as regex
-    pub filter_is_regex: bool,
+    /// Whether to show the detail panel for the selected entry
+    pub show_details: bool,
+    /// Where the detail panel goes relative to the table
+    pub details_layout: DetailsLayout,
+    /// Last successfully parsed filter query
+    query: Query,
+    /// Filter text that `query` was parsed from
+    query_source: String,
+    /// Why the current filter text doesn't parse (the previous query stays applied)
+    pub filter_error: Option<QueryError>,
     /// Remote host being monitored (None for localhost)
     pub remote_host: Option<String>,
+    /// Names of the open host tabs, for the tab bar (empty with a single host)
+    pub tab_labels: Vec<String>,
+    /// Index of this tab in `tab_labels`
+    pub active_tab: usize,
+    /// Tab change requested by a key or command, waiting for the main loop
+    pub tab_request: Option<TabRequest>,
+    /// Why the latest scan failed (e.g. the SSH connection dropped)
+    pub scan_error: Option<String>,
+    /// Whether this is the fleet view, merging the rows of every host tab
+    pub is_fleet: bool,
+    /// Hosts whose latest scan failed and why, shown as rows in the fleet view
+    pub host_errors: Vec<(String, String)>,
+    /// Health rules evaluated against every scan
+    pub rules: RuleEngine,
+    /// CPU and memory samples per PID across scans
+    pub history: ProcessHistory,
+    /// Unfiltered result of the previous scan (None before the first scan)
+    pub last_scan: Option<Vec<PortEntry>>,
+    /// Port open/close events derived from consecutive scans
+    pub events: EventLog,
+    /// Whether to show the event log pane
+    pub show_events: bool,
+    /// How many of the newest events are scrolled past in the log pane
+    pub events_scroll: usize,
+    /// Ports pinned to the top of the table
+    pub pins: Pins,
+    /// Events on pinned ports waiting to be delivered as notifications
+    pub notifications: Vec<PortEvent>,
+    /// Rows marked for a bulk action, by [`PortEntry::key`]
+    pub marked: HashSet<EntryKey>,
+    /// Bulk action waiting for the user to confirm it
+    pub pending_bulk: Option<PendingBulk>,
+    /// Saved filter + sort combinations
+    pub views: Views,
+    /// Name of the most recently applied or saved view
+    pub active_view: Option<String>,
+    /// Whether to show the view picker popup
+    pub show_view_picker: bool,
+    /// Highlighted row in the view picker
+    pub view_picker_index: usize,
+    /// Whether the "save view as" name prompt is active
+    pub view_name_mode: bool,
+    /// Name typed into the "save view as" prompt
+    pub view_name_input: String,
+    /// Whether the `:` command palette is open
+    pub command_mode: bool,
+    /// Command typed into the palette (without the ':')
+    pub command_input: String,
+    /// Completions offered by the last Tab press (cleared when typing)
+    pub command_completions: Vec<String>,
+    /// Which completion is currently shown
+    pub command_completion_index: usize,
+    /// Inventory hosts and recently connected hosts, offered by the connect picker
+    pub inventory: Inventory,
     /// Whether connect input mode is active
     pub connect_mode: bool,
-    /// Current connection input string
+    /// Current connection input string (searches the host picker)
     pub connect_input: String,
+    /// Highlighted row in the host picker
+    pub connect_picker_index: usize,
     /// SSH key path input (optional, for connect mode)
     pub connect_key_input: String,
     /// Whether entering SSH key path (second step of connect)
@@ -152,28 +425,125 @@ impl App {
         Self {
             entries: Vec::new(),
             selected_index: 0,
+            group_by: None,
+            groups: Vec::new(),
+            rows: Vec::new(),
+            row_depths: Vec::new(),
+            toggled_groups: HashSet::new(),
             status_message: StatusMessage::Info("Ready".into()),
             status_timestamp: Instant::now(),
-            status_duration: Duration::from_secs(5),
+            status_duration: Duration::from_secs(DEFAULT_STATUS_DURATION),
+            scan_interval: Duration::from_secs(DEFAULT_SCAN_INTERVAL),
+            config: Config::default(),
+            keymap: KeyMap::default(),
+            theme: Theme::default(),
             should_quit: false,
             sort_column: SortColumn::default(),
             sort_order: SortOrder::default(),
             filter: String::new(),
             filter_mode: false,
             show_help: false,
-            compiled_regex: None,
-            filter_is_regex: false,
+            show_details: false,
+            details_layout: DetailsLayout::default(),
+            query: Query::default(),
+            query_source: String::new(),
+            filter_error: None,
             remote_host: None,
+            tab_labels: Vec::new(),
+            active_tab: 0,
+            tab_request: None,
+            scan_error: None,
+            is_fleet: false,
+            host_errors: Vec::new(),
+            rules: RuleEngine::default(),
+            history: ProcessHistory::default(),
+            last_scan: None,
+            events: EventLog::default(),
+            show_events: false,
+            events_scroll: 0,
+            pins: Pins::default(),
+            notifications: Vec::new(),
+            marked: HashSet::new(),
+            pending_bulk: None,
+            views: Views::default(),
+            active_view: None,
+            show_view_picker: false,
+            view_picker_index: 0,
+            view_name_mode: false,
+            view_name_input: String::new(),
+            command_mode: false,
+            command_input: String::new(),
+            command_completions: Vec::new(),
+            command_completion_index: 0,
+            inventory: Inventory::default(),
             connect_mode: false,
             connect_input: String::new(),
+            connect_picker_index: 0,
             connect_key_input: String::new(),
             connect_key_mode: false,
         }
     }
 
+    /// Take the startup sort, filter and timings from the config file's `[defaults]`
+    pub fn apply_config(&mut self, config: Config) {
+        let defaults = &config.defaults;
+        self.sort_column = defaults.sort;
+        self.sort_order = defaults.order;
+        self.filter = defaults.filter.clone();
+        self.status_duration = Duration::from_secs(defaults.status_duration);
+        self.scan_interval = Duration::from_secs(defaults.scan_interval);
+        self.config = config;
+    }
+
+    /// A fresh view for another host tab, sharing settings, keys, pins and views
+    /// but starting with the configured filter and sort and no history
+    pub fn new_tab(&self) -> App {
+        let mut app = App::new();
+        // The rules were validated when the first tab loaded them
+        app.rules = self.config.rule_engine().unwrap_or_default();
+        app.keymap = self.keymap.clone();
+        app.theme = self.theme;
+        app.pins = self.pins.clone();
+        app.views = self.views.clone();
+        app.inventory = self.inventory.clone();
+        app.show_details = self.show_details;
+        app.details_layout = self.details_layout;
+        app.show_events = self.show_events;
+        app.apply_config(self.config.clone());
+        app
+    }
+
+    /// Label of this host in the tab bar
+    pub fn tab_label(&self) -> String {
+        if self.is_fleet {
+            return FLEET_LABEL.to_string();
+        }
+        self.remote_host
+            .clone()
+            .unwrap_or_else(|| LOCAL_LABEL.to_string())
+    }
+
+    /// Host whose pins apply to a row: the row's own host in the fleet view, otherwise
+    /// this tab's (None for the local machine)
+    pub fn pin_host<'a>(&'a self, entry: &'a PortEntry) -> Option<&'a str> {
+        match entry.host.as_deref() {
+            Some(LOCAL_LABEL) => None,
+            Some(host) => Some(host),
+            None => self.remote_host.as_deref(),
+        }
+    }
+
+    /// Whether a row's port is pinned on its host
+    pub fn is_pinned(&self, entry: &PortEntry) -> bool {
+        self.pins.contains(self.pin_host(entry), entry.port)
+    }
+
     /// Set the remote host being monitored
     pub fn set_remote_host(&mut self, host: Option<String>) {
         self.remote_host = host;
+        // PIDs from another host are unrelated to the ones we sampled
+        self.history.clear();
+        self.last_scan = None;
     }
 
     /// Toggle the help popup
@@ -181,63 +551,287 @@ impl App {
         self.show_help = !self.show_help;
     }
 
+    /// Toggle the detail panel for the selected entry
+    pub fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+    }
+
+    /// Move the detail panel between the right of the table and below it
+    pub fn toggle_details_layout(&mut self) {
+        self.details_layout = match self.details_layout {
+            DetailsLayout::Right => DetailsLayout::Bottom,
+            DetailsLayout::Bottom => DetailsLayout::Right,
+        };
+        self.show_details = true;
+    }
+
+    /// Every socket the entry's process listens on, from the latest unfiltered scan
+    pub fn sockets_of(&self, entry: &PortEntry) -> Vec<&PortEntry> {
+        let scan = self.last_scan.as_deref().unwrap_or(&self.entries);
+        let mut sockets: Vec<&PortEntry> = scan
+            .iter()
+            .filter(|e| e.pid == entry.pid && e.host == entry.host)
+            .collect();
+        sockets.sort_by_key(|e| (e.port, e.protocol == Protocol::Udp));
+        sockets
+    }
+
+    /// Toggle the event log pane
+    pub fn toggle_events(&mut self) {
+        self.show_events = !self.show_events;
+        self.events_scroll = 0;
+    }
+
+    /// Scroll the event log towards older events
+    pub fn scroll_events_older(&mut self) {
+        if self.events_scroll + 1 < self.events.len() {
+            self.events_scroll += 1;
+        }
+    }
+
+    /// Scroll the event log towards newer events
+    pub fn scroll_events_newer(&mut self) {
+        self.events_scroll = self.events_scroll.saturating_sub(1);
+    }
+
     /// Update the list of port entries, applying current sort and filter
     pub fn update_entries(&mut self, mut entries: Vec<PortEntry>) {
-        // Apply filter
-        if !self.filter.is_empty() {
-            if let Some(ref regex) = self.compiled_regex {
-                // Use regex filtering
-                entries.retain(|e| {
-                    regex.is_match(&e.process_name)
-                        || regex.is_match(&e.port.to_string())
-                        || regex.is_match(&e.pid.to_string())
-                });
-            } else {
-                // Use simple substring matching (case-insensitive)
-                let filter_lower = self.filter.to_lowercase();
-                entries.retain(|e| {
-                    e.process_name.to_lowercase().contains(&filter_lower)
-                        || e.port.to_string().contains(&filter_lower)
-                        || e.pid.to_string().contains(&filter_lower)
-                });
+        // Evaluate health rules before filtering so sustained conditions keep their timers
+        self.rules.evaluate(&mut entries);
+        self.history.record(&entries);
+
+        // Diff against the previous scan to log ports opening and closing
+        if let Some(previous) = &self.last_scan {
+            let events = diff_entries(previous, &entries, unix_now());
+            if self.events_scroll > 0 {
+                // Keep the view anchored on the same events while scrolled back
+                self.events_scroll += events.len();
+            }
+            let pinned: Vec<PortEvent> = events
+                .iter()
+                .filter(|event| self.pins.contains(self.remote_host.as_deref(), event.port))
+                .cloned()
+                .collect();
+            if let Some(latest) = pinned.last() {
+                self.set_info(format!("📌 {}", latest));
             }
+            self.notifications.extend(pinned);
+            self.events.extend(events);
+            // The log drops its oldest events once full
+            self.events_scroll = self.events_scroll.min(self.events.len().saturating_sub(1));
         }
+        self.last_scan = Some(entries.clone());
+
+        // Forget marks on rows that went away
+        self.marked
+            .retain(|key| entries.iter().any(|entry| entry.key() == *key));
+
+        self.show_entries(entries);
+    }
+
+    /// Show the merged rows of every host in the fleet view. Health rules, history
+    /// and events were already handled by each host's own tab.
+    pub fn update_fleet(&mut self, entries: Vec<PortEntry>, host_errors: Vec<(String, String)>) {
+        self.host_errors = host_errors;
+        self.last_scan = Some(entries.clone());
+        self.marked
+            .retain(|key| entries.iter().any(|entry| entry.key() == *key));
+        self.show_entries(entries);
+    }
+
+    /// Re-apply filter and sort to the latest scan (e.g. while typing a filter)
+    pub fn refresh_view(&mut self) {
+        let entries = self.last_scan.clone().unwrap_or_default();
+        self.show_entries(entries);
+    }
+
+    /// Filter and sort scan results into the table
+    fn show_entries(&mut self, mut entries: Vec<PortEntry>) {
+        // Apply filter
+        self.compile_filter();
+        entries.retain(|e| self.query.matches(e));
 
         // Apply sort
         self.sort_entries(&mut entries);
 
         self.entries = entries;
+        self.regroup();
         // Ensure selected index is within bounds
-        if !self.entries.is_empty() && self.selected_index >= self.entries.len() {
-            self.selected_index = self.entries.len() - 1;
+        if self.row_count() > 0 && self.selected_index >= self.row_count() {
+            self.selected_index = self.row_count() - 1;
         }
     }
 
-    /// Try to compile the current filter as a regex
-    fn try_compile_filter_regex(&mut self) {
-        if self.filter.is_empty() {
-            self.compiled_regex = None;
-            self.filter_is_regex = false;
+    /// Rebuild the groups and rows of the grouped table from `entries`
+    fn regroup(&mut self) {
+        let Some(by) = self.group_by else {
+            self.groups.clear();
+            self.rows.clear();
+            self.row_depths.clear();
             return;
+        };
+        let (groups, top) = group::group_entries(
+            &self.entries,
+            by,
+            &|entry| self.is_pinned(entry),
+            self.sort_column,
+            self.sort_order,
+        );
+        self.groups = groups;
+        (self.rows, self.row_depths) = group::table_rows(&self.groups, &top, &self.toggled_groups)
+            .into_iter()
+            .unzip();
+    }
+
+    /// Whether a group's children are shown
+    pub fn is_expanded(&self, key: &GroupKey) -> bool {
+        group::is_expanded(key, &self.toggled_groups)
+    }
+
+    fn set_expanded(&mut self, key: GroupKey, expanded: bool) {
+        if self.is_expanded(&key) != expanded {
+            if !self.toggled_groups.remove(&key) {
+                self.toggled_groups.insert(key);
+            }
+            self.regroup();
+        }
+    }
+
+    /// Number of selectable rows in the table
+    pub fn row_count(&self) -> usize {
+        match self.group_by {
+            Some(_) => self.rows.len(),
+            None => self.entries.len(),
         }
+    }
+
+    /// Row the selection is on
+    pub fn selected_row(&self) -> Option<TableRow> {
+        match self.group_by {
+            Some(_) => self.rows.get(self.selected_index).copied(),
+            None => (self.selected_index < self.entries.len())
+                .then_some(TableRow::Entry(self.selected_index)),
+        }
+    }
 
-        // Try to compile as case-insensitive regex
-        match Regex::new(&format!("(?i){}", &self.filter)) {
-            Ok(regex) => {
-                self.compiled_regex = Some(regex);
-                self.filter_is_regex = true;
+    /// Group whose header row is selected
+    pub fn selected_group(&self) -> Option<&Group> {
+        match self.selected_row()? {
+            TableRow::Group(index) => self.groups.get(index),
+            TableRow::Entry(_) => None,
+        }
+    }
+
+    /// Entries of the selected row: a single entry, or every entry of a group
+    pub fn selected_entries(&self) -> Vec<&PortEntry> {
+        match self.selected_row() {
+            Some(TableRow::Entry(index)) => self.entries.get(index).into_iter().collect(),
+            Some(TableRow::Group(index)) => self.groups[index]
+                .entries
+                .iter()
+                .map(|&i| &self.entries[i])
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Table row showing an entry (the innermost group containing it while collapsed)
+    fn row_of_entry(&self, index: usize) -> Option<usize> {
+        if self.group_by.is_none() {
+            return Some(index);
+        }
+        self.rows.iter().rposition(|row| match *row {
+            TableRow::Entry(entry) => entry == index,
+            TableRow::Group(group) => self.groups[group].entries.contains(&index),
+        })
+    }
+
+    /// Group the table by PID, process name or ancestry, or show it flat again
+    pub fn set_group_by(&mut self, group_by: Option<GroupBy>) {
+        if self.is_fleet && group_by == Some(GroupBy::Tree) {
+            self.set_error("The process tree is per host; open the host's tab to see it");
+            return;
+        }
+        self.group_by = group_by;
+        self.selected_index = 0;
+        self.regroup();
+        match group_by {
+            Some(GroupBy::Tree) => self.set_info("Showing the process tree"),
+            Some(by) => self.set_info(format!("Grouped by {}", by.label())),
+            None => self.set_info("Ungrouped"),
+        }
+    }
+
+    /// Cycle between no grouping, grouping by PID, by name and the process tree
+    pub fn cycle_group_by(&mut self) {
+        let mut next = GroupBy::cycle(self.group_by);
+        if self.is_fleet && next == Some(GroupBy::Tree) {
+            next = GroupBy::cycle(next);
+        }
+        self.set_group_by(next);
+    }
+
+    /// Expand or collapse the selected group
+    pub fn toggle_group_selected(&mut self) {
+        let Some(key) = self.selected_group().map(|g| g.key.clone()) else {
+            return;
+        };
+        let expanded = self.is_expanded(&key);
+        self.set_expanded(key, !expanded);
+    }
+
+    /// Expand the selected group
+    pub fn expand_selected(&mut self) {
+        if let Some(key) = self.selected_group().map(|g| g.key.clone()) {
+            self.set_expanded(key, true);
+        }
+    }
+
+    /// Collapse the selected group, or else the group containing the selected row
+    /// (selecting its header)
+    pub fn collapse_selected(&mut self) {
+        if let Some(key) = self.selected_group().map(|g| g.key.clone()) {
+            if self.is_expanded(&key) {
+                self.set_expanded(key, false);
+                return;
             }
-            Err(_) => {
-                // Invalid regex, fall back to literal matching
-                self.compiled_regex = None;
-                self.filter_is_regex = false;
+        }
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        let Some(parent) = self.groups.iter().position(|g| g.children.contains(&row)) else {
+            return;
+        };
+        self.set_expanded(self.groups[parent].key.clone(), false);
+        if let Some(index) = self.rows.iter().position(|r| *r == TableRow::Group(parent)) {
+            self.selected_index = index;
+        }
+    }
+
+    /// Parse the filter text if it changed; on error keep the previous query
+    pub fn compile_filter(&mut self) {
+        if self.filter == self.query_source {
+            return;
+        }
+        self.query_source = self.filter.clone();
+
+        match Query::parse(&self.filter) {
+            Ok(query) => {
+                self.query = query;
+                self.filter_error = None;
             }
+            Err(e) => self.filter_error = Some(e),
         }
     }
 
-    /// Sort entries by current sort column and order
+    /// Sort entries by current sort column and order (pinned ports first)
     fn sort_entries(&self, entries: &mut [PortEntry]) {
         entries.sort_by(|a, b| {
+            let pinned = self.is_pinned(b).cmp(&self.is_pinned(a));
+            if pinned.is_ne() {
+                return pinned;
+            }
+
             let cmp = match self.sort_column {
                 SortColumn::Port => a.port.cmp(&b.port),
                 SortColumn::Protocol => {
@@ -253,6 +847,7 @@ impl App {
                     .partial_cmp(&b.cpu_usage)
                     .unwrap_or(std::cmp::Ordering::Equal),
                 SortColumn::MemoryUsage => a.memory_usage.cmp(&b.memory_usage),
+                SortColumn::Uptime => a.uptime_secs().cmp(&b.uptime_secs()),
             };
 
             match self.sort_order {
@@ -262,6 +857,87 @@ impl App {
         });
     }
 
+    /// Pin or unpin the selected entry's port on its host
+    pub fn toggle_pin_selected(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let (host, port) = (self.pin_host(entry).map(str::to_string), entry.port);
+        let pinned = self.pins.contains(host.as_deref(), port);
+        self.set_pinned(host.as_deref(), port, !pinned);
+    }
+
+    /// Host that `:pin`/`:unpin` apply to: this tab's, or in the fleet view the selected row's
+    pub fn command_pin_host(&self) -> Option<String> {
+        match self.selected_entry() {
+            Some(entry) if self.is_fleet => self.pin_host(entry).map(str::to_string),
+            _ => self.remote_host.clone(),
+        }
+    }
+
+    /// Pin or unpin a port on `host` (the port doesn't need to be in the table)
+    pub fn set_pinned(&mut self, host: Option<&str>, port: u16, pinned: bool) {
+        let label = match host {
+            Some(host) => format!("{} on {}", port, host),
+            None => port.to_string(),
+        };
+        if self.pins.contains(host, port) == pinned {
+            let state = if pinned { "already" } else { "not" };
+            self.set_info(format!("Port {} is {} pinned", label, state));
+            return;
+        }
+        match self.pins.toggle(host, port) {
+            Ok(true) => self.set_success(format!("📌 Pinned port {}", label)),
+            Ok(false) => self.set_info(format!("Unpinned port {}", label)),
+            Err(e) => self.set_error(format!("Failed to save pins: {:#}", e)),
+        }
+
+        // Move the entry into (or out of) the pinned group right away, keeping it selected
+        let selected = self.selected_entry().map(PortEntry::key);
+        let mut entries = std::mem::take(&mut self.entries);
+        self.sort_entries(&mut entries);
+        self.entries = entries;
+        self.regroup();
+        if let Some(row) = self
+            .entries
+            .iter()
+            .position(|e| Some(e.key()) == selected)
+            .and_then(|index| self.row_of_entry(index))
+        {
+            self.selected_index = row;
+        }
+    }
+
+    /// Rows above the entries that can't be selected: failed hosts and pinned ports that are down
+    pub fn placeholder_count(&self) -> usize {
+        self.host_errors.len() + self.pinned_down().len()
+    }
+
+    /// Pinned ports nobody is listening on in the latest scan, with their host
+    ///
+    /// The fleet view covers the pins of every open host it could reach.
+    pub fn pinned_down(&self) -> Vec<Pin> {
+        let scan = self.last_scan.as_deref().unwrap_or_default();
+        let shown = |host: Option<&str>| {
+            if !self.is_fleet {
+                return host == self.remote_host.as_deref();
+            }
+            let label = host.unwrap_or(LOCAL_LABEL);
+            self.tab_labels.iter().any(|tab| tab == label)
+                && !self.host_errors.iter().any(|(failed, _)| failed == label)
+        };
+        self.pins
+            .iter()
+            .filter(|(host, _)| shown(host.as_deref()))
+            .filter(|(host, port)| {
+                !scan
+                    .iter()
+                    .any(|e| e.port == *port && self.pin_host(e) == host.as_deref())
+            })
+            .cloned()
+            .collect()
+    }
+
     /// Cycle to the next sort column
     pub fn cycle_sort_column(&mut self) {
         self.sort_column = self.sort_column.next();
@@ -299,6 +975,7 @@ impl App {
             SortColumn::ProcessName => "Name",
             SortColumn::CpuUsage => "CPU",
             SortColumn::MemoryUsage => "Memory",
+            SortColumn::Uptime => "Uptime",
         };
         self.set_info(format!("Sort: {}{}", col_str, order_str));
     }
@@ -309,14 +986,17 @@ impl App {
         self.set_info("Filter: Type to search, Enter to confirm, Esc to cancel");
     }
 
-    /// Exit filter mode
+    /// Exit filter mode (stays in it if the query doesn't parse)
     pub fn exit_filter_mode(&mut self) {
+        self.refresh_view();
+        if let Some(err) = &self.filter_error {
+            self.set_error(format!("Invalid filter: {}", err));
+            return;
+        }
+
         self.filter_mode = false;
-        self.try_compile_filter_regex();
         if self.filter.is_empty() {
             self.set_info("Filter cleared");
-        } else if self.filter_is_regex {
-            self.set_info(format!("Regex filter: {}", self.filter));
         } else {
             self.set_info(format!("Filter: {}", self.filter));
         }
@@ -325,20 +1005,207 @@ impl App {
     /// Clear filter
     pub fn clear_filter(&mut self) {
         self.filter.clear();
-        self.compiled_regex = None;
-        self.filter_is_regex = false;
         self.filter_mode = false;
+        self.refresh_view();
         self.set_info("Filter cleared");
     }
 
-    /// Add character to filter
+    /// Add character to filter (the table updates as you type)
     pub fn filter_push(&mut self, c: char) {
         self.filter.push(c);
+        self.refresh_view();
     }
 
     /// Remove last character from filter
     pub fn filter_pop(&mut self) {
         self.filter.pop();
+        self.refresh_view();
+    }
+
+    /// Apply a saved view's filter and sort
+    pub fn apply_view(&mut self, view: &View) {
+        self.filter = view.filter.clone();
+        self.filter_mode = false;
+        self.sort_column = view.sort;
+        self.sort_order = view.order;
+        self.refresh_view();
+        self.selected_index = 0;
+        self.active_view = Some(view.name.clone());
+        match &self.filter_error {
+            Some(err) => self.set_error(format!("View '{}': invalid filter: {}", view.name, err)),
+            None => self.set_info(format!("View: {}", view.name)),
+        }
+    }
+
+    /// Whether the filter or sort has changed since the active view was applied
+    pub fn active_view_modified(&self) -> bool {
+        let Some(view) = self.active_view.as_deref().and_then(|n| self.views.get(n)) else {
+            return false;
+        };
+        view.filter != self.filter || view.sort != self.sort_column || view.order != self.sort_order
+    }
+
+    /// Switch to the view after the active one (wrapping around)
+    pub fn cycle_view(&mut self) {
+        if self.views.is_empty() {
+            self.set_info("No saved views (press V to save one)");
+            return;
+        }
+        let next = self
+            .active_view
+            .as_deref()
+            .and_then(|name| self.views.iter().position(|v| v.name == name))
+            .map_or(0, |i| (i + 1) % self.views.len());
+        if let Some(view) = self.views.at(next).cloned() {
+            self.apply_view(&view);
+        }
+    }
+
+    /// Open the view picker with the active view highlighted
+    pub fn open_view_picker(&mut self) {
+        if self.views.is_empty() {
+            self.set_info("No saved views (press V to save one)");
+            return;
+        }
+        self.view_picker_index = self
+            .active_view
+            .as_deref()
+            .and_then(|name| self.views.iter().position(|v| v.name == name))
+            .unwrap_or(0);
+        self.show_view_picker = true;
+    }
+
+    /// Move the view picker highlight by `delta` rows (wrapping around)
+    pub fn move_view_picker(&mut self, delta: isize) {
+        let len = self.views.len() as isize;
+        if len == 0 {
+            return;
+        }
+        self.view_picker_index = (self.view_picker_index as isize + delta).rem_euclid(len) as usize;
+    }
+
+    /// Apply the view highlighted in the picker and close it
+    pub fn pick_view(&mut self) {
+        self.show_view_picker = false;
+        if let Some(view) = self.views.at(self.view_picker_index).cloned() {
+            self.apply_view(&view);
+        }
+    }
+
+    /// Delete the view highlighted in the picker
+    pub fn delete_picked_view(&mut self) {
+        let Some(name) = self
+            .views
+            .at(self.view_picker_index)
+            .map(|v| v.name.clone())
+        else {
+            return;
+        };
+        if let Err(e) = self.views.remove(&name) {
+            self.set_error(format!("Failed to save views: {:#}", e));
+            return;
+        }
+        if self.active_view.as_deref() == Some(name.as_str()) {
+            self.active_view = None;
+        }
+        self.set_info(format!("Deleted view '{}'", name));
+        if self.views.is_empty() {
+            self.show_view_picker = false;
+        } else {
+            self.view_picker_index = self.view_picker_index.min(self.views.len() - 1);
+        }
+    }
+
+    /// Start prompting for a name to save the current filter and sort under
+    pub fn enter_view_name_mode(&mut self) {
+        self.view_name_mode = true;
+        self.view_name_input = self.active_view.clone().unwrap_or_default();
+        self.set_info("Save view: Enter a name, Enter to save, Esc to cancel");
+    }
+
+    /// Cancel the "save view as" prompt
+    pub fn exit_view_name_mode(&mut self) {
+        self.view_name_mode = false;
+        self.view_name_input.clear();
+        self.set_info("Save view cancelled");
+    }
+
+    /// Save the current filter and sort under the name typed into the prompt
+    pub fn save_view_from_input(&mut self) {
+        let name = self.view_name_input.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        self.view_name_mode = false;
+        self.view_name_input.clear();
+
+        let view = View {
+            name: name.clone(),
+            filter: self.filter.clone(),
+            sort: self.sort_column,
+            order: self.sort_order,
+        };
+        match self.views.save_view(view) {
+            Ok(()) => {
+                self.active_view = Some(name.clone());
+                self.set_success(format!("Saved view '{}'", name));
+            }
+            Err(e) => self.set_error(format!("Failed to save views: {:#}", e)),
+        }
+    }
+
+    /// Open the command palette
+    pub fn enter_command_mode(&mut self) {
+        self.command_mode = true;
+        self.command_input.clear();
+        self.command_completions.clear();
+    }
+
+    /// Close the command palette
+    pub fn exit_command_mode(&mut self) {
+        self.command_mode = false;
+        self.command_input.clear();
+        self.command_completions.clear();
+    }
+
+    /// Add a character to the palette command
+    pub fn command_push(&mut self, c: char) {
+        self.command_input.push(c);
+        self.command_completions.clear();
+    }
+
+    /// Remove the last character of the palette command
+    pub fn command_pop(&mut self) {
+        self.command_input.pop();
+        self.command_completions.clear();
+    }
+
+    /// Complete the palette command; pressing again cycles through the candidates
+    pub fn complete_command(&mut self) {
+        if self.command_completions.is_empty() {
+            let completions = palette::complete(&self.command_input, self);
+            if let [only] = completions.as_slice() {
+                // Nothing to cycle through: accept it and move on to the next argument
+                self.command_input = format!("{} ", only);
+                return;
+            }
+            self.command_completions = completions;
+            self.command_completion_index = 0;
+        } else {
+            self.command_completion_index =
+                (self.command_completion_index + 1) % self.command_completions.len();
+        }
+        if let Some(completion) = self.command_completions.get(self.command_completion_index) {
+            self.command_input = completion.clone();
+        }
+    }
+
+    /// The entry on a port, or else the process with a PID, in the latest scan
+    pub fn find_port_or_pid(&self, target: u32) -> Option<&PortEntry> {
+        let scan = self.last_scan.as_deref().unwrap_or(&self.entries);
+        scan.iter()
+            .find(|entry| u32::from(entry.port) == target)
+            .or_else(|| scan.iter().find(|entry| entry.pid == target))
     }
 
     /// Enter connect mode
@@ -347,7 +1214,41 @@ impl App {
         self.connect_key_mode = false;
         self.connect_input.clear();
         self.connect_key_input.clear();
-        self.set_info("Connect: Enter host (user@host:port or user@host or host), Enter to connect, Esc to cancel");
+        self.connect_picker_index = 0;
+        self.set_info("Connect: type to search hosts or enter user@host:port, Enter to connect, Esc to cancel");
+    }
+
+    /// Host picker rows for the typed text: matching recent and inventory hosts,
+    /// then the text itself as a new host
+    pub fn connect_choices(&self) -> Vec<HostChoice> {
+        let query = self.connect_input.trim();
+        let mut choices = self.inventory.choices(query);
+        if !query.is_empty() && !choices.iter().any(|choice| choice.name == query) {
+            choices.push(HostChoice {
+                name: query.to_string(),
+                address: String::new(),
+                groups: Vec::new(),
+                recent: false,
+            });
+        }
+        choices
+    }
+
+    /// The host picker row that Enter connects to
+    pub fn picked_host(&self) -> Option<HostChoice> {
+        self.connect_choices()
+            .into_iter()
+            .nth(self.connect_picker_index)
+    }
+
+    /// Move the host picker highlight by `delta` rows (wrapping around)
+    pub fn move_connect_picker(&mut self, delta: isize) {
+        let len = self.connect_choices().len() as isize;
+        if len == 0 {
+            return;
+        }
+        self.connect_picker_index =
+            (self.connect_picker_index as isize + delta).rem_euclid(len) as usize;
     }
 
     /// Exit connect mode
@@ -373,6 +1274,7 @@ impl App {
             self.connect_key_input.push(c);
         } else {
             self.connect_input.push(c);
+            self.connect_picker_index = 0;
         }
     }
 
@@ -382,34 +1284,35 @@ impl App {
             self.connect_key_input.pop();
         } else {
             self.connect_input.pop();
+            self.connect_picker_index = 0;
         }
     }
 
     /// Disconnect from remote and return to local mode
     pub fn disconnect(&mut self) {
-        self.remote_host = None;
+        self.set_remote_host(None);
         self.set_info("Disconnected from remote host");
     }
 
     /// Move selection up
     pub fn select_previous(&mut self) {
-        if self.entries.is_empty() {
+        if self.row_count() == 0 {
             return;
         }
         if self.selected_index > 0 {
             self.selected_index -= 1;
         } else {
             // Wrap around to bottom
-            self.selected_index = self.entries.len() - 1;
+            self.selected_index = self.row_count() - 1;
         }
     }
 
     /// Move selection down
     pub fn select_next(&mut self) {
-        if self.entries.is_empty() {
+        if self.row_count() == 0 {
             return;
         }
-        if self.selected_index < self.entries.len() - 1 {
+        if self.selected_index < self.row_count() - 1 {
             self.selected_index += 1;
         } else {
             // Wrap around to top
@@ -417,9 +1320,29 @@ impl App {
         }
     }
 
-    /// Get the currently selected entry
+    /// Move selection by `delta` rows, stopping at the first and last entry
+    pub fn scroll_selection(&mut self, delta: isize) {
+        if self.row_count() == 0 {
+            return;
+        }
+        self.selected_index = self
+            .selected_index
+            .saturating_add_signed(delta)
+            .min(self.row_count() - 1);
+    }
+
+    /// Get the currently selected entry (the process of a selected PID group)
     pub fn selected_entry(&self) -> Option<&PortEntry> {
-        self.entries.get(self.selected_index)
+        match self.selected_row()? {
+            TableRow::Entry(index) => self.entries.get(index),
+            TableRow::Group(index) => {
+                let group = &self.groups[index];
+                match group.key {
+                    GroupKey::Pid(..) => self.entries.get(group.entries[0]),
+                    GroupKey::Name(_) | GroupKey::Process(..) => None,
+                }
+            }
+        }
     }
 
     /// Set an info status message
@@ -448,6 +1371,73 @@ impl App {
         }
     }
 
+    /// Mark or unmark the selected row (every entry of a group) and move down to the next one
+    pub fn toggle_mark_selected(&mut self) {
+        let keys: Vec<_> = self
+            .selected_entries()
+            .into_iter()
+            .map(PortEntry::key)
+            .collect();
+        if keys.is_empty() {
+            return;
+        }
+        if keys.iter().all(|key| self.marked.contains(key)) {
+            for key in &keys {
+                self.marked.remove(key);
+            }
+        } else {
+            self.marked.extend(keys);
+        }
+        self.scroll_selection(1);
+    }
+
+    /// Mark every row in the table, or unmark them all if they already are
+    pub fn toggle_mark_all(&mut self) {
+        let keys: Vec<_> = self.entries.iter().map(PortEntry::key).collect();
+        if keys.iter().all(|key| self.marked.contains(key)) {
+            for key in &keys {
+                self.marked.remove(key);
+            }
+            self.set_info("Unmarked all rows");
+        } else {
+            self.marked.extend(keys);
+            self.set_info(format!("Marked {} rows", self.entries.len()));
+        }
+    }
+
+    pub fn is_marked(&self, entry: &PortEntry) -> bool {
+        self.marked.contains(&entry.key())
+    }
+
+    /// Marked rows currently shown in the table
+    pub fn marked_entries(&self) -> Vec<&PortEntry> {
+        self.entries.iter().filter(|e| self.is_marked(e)).collect()
+    }
+
+    /// Ask for confirmation before applying an action to every marked process
+    pub fn request_bulk(&mut self, kind: BulkKind) {
+        let mut targets: Vec<BulkTarget> = Vec::new();
+        for entry in self.marked_entries() {
+            match targets
+                .iter_mut()
+                .find(|t| t.pid == entry.pid && t.host == entry.host)
+            {
+                Some(target) => target.ports.push(entry.port),
+                None => targets.push(BulkTarget {
+                    host: entry.host.clone(),
+                    pid: entry.pid,
+                    name: entry.process_name.clone(),
+                    ports: vec![entry.port],
+                }),
+            }
+        }
+        if targets.is_empty() {
+            self.set_info("No marked rows in the table");
+            return;
+        }
+        self.pending_bulk = Some(PendingBulk { kind, targets });
+    }
+
     /// Request application quit
     pub fn quit(&mut self) {
         self.should_quit = true;
@@ -601,280 +1591,894 @@ mod connect_tests {
         let mut app = App::new();
         assert!(app.remote_host.is_none());
 
-        app.disconnect(); // Should not panic
+        app.disconnect(); // Should not panic
+
+        assert!(app.remote_host.is_none());
+    }
+
+    #[test]
+    fn test_connect_mode_initialization() {
+        let app = App::new();
+        assert!(!app.connect_mode);
+        assert!(!app.connect_key_mode);
+        assert!(app.connect_input.is_empty());
+        assert!(app.connect_key_input.is_empty());
+    }
+
+    #[test]
+    fn test_connect_mode_clears_on_enter() {
+        let mut app = App::new();
+        app.connect_input.push_str("old");
+        app.connect_key_input.push_str("old_key");
+
+        app.enter_connect_mode();
+
+        assert!(app.connect_input.is_empty());
+        assert!(app.connect_key_input.is_empty());
+    }
+
+    #[test]
+    fn test_connect_key_mode_goes_back_to_host() {
+        let mut app = App::new();
+        app.enter_connect_mode();
+        app.connect_input.push_str("user@host");
+        app.enter_connect_key_mode();
+        app.connect_key_input.push_str("/key");
+
+        // Simulate Esc in key mode
+        app.connect_key_mode = false;
+        app.connect_key_input.clear();
+
+        assert!(!app.connect_key_mode);
+        assert!(app.connect_key_input.is_empty());
+        assert_eq!(app.connect_input, "user@host");
+    }
+}
+
+/// Process names that adopt orphaned children (init systems and container subreapers)
+const SUBREAPER_NAMES: &[&str] = &[
+    "init",
+    "systemd",
+    "launchd",
+    "tini",
+    "dumb-init",
+    "catatonit",
+    "docker-init",
+];
+
+impl PortEntry {
+    /// A TCP listener with no other details, for tests to fill in the fields they check
+    #[cfg(test)]
+    pub fn test(port: u16, pid: u32, process_name: &str) -> Self {
+        Self {
+            port,
+            protocol: Protocol::Tcp,
+            pid,
+            process_name: process_name.to_string(),
+            cpu_usage: 0.0,
+            memory_usage: 0,
+            memory_display: "0 B".to_string(),
+            address: None,
+            user: None,
+            parent_pid: None,
+            parent_name: None,
+            session_leader_alive: None,
+            state: ProcessState::Unknown,
+            is_zombie: false,
+            is_orphan: false,
+            alerts: Vec::new(),
+            command: None,
+            exe_path: None,
+            cwd: None,
+            start_time: None,
+            ancestors: Vec::new(),
+            connections: None,
+            host: None,
+        }
+    }
+
+    /// Identifies the row across scans (port, protocol, PID and host)
+    pub fn key(&self) -> EntryKey {
+        (self.port, self.protocol, self.pid, self.host.clone())
+    }
+
+    /// Classify the entry as defunct and/or orphaned
+    ///
+    /// - Zombie: the process is in the `Z` state (exited, not yet reaped)
+    /// - Orphan: the process was re-parented to PID 1 or a known subreaper, and the
+    ///   session it was started in has lost its leader. Services that init starts
+    ///   directly lead their own session, so they aren't flagged.
+    pub fn detect_health_flags(&mut self) {
+        self.is_zombie = self.state == ProcessState::Zombie;
+        self.is_orphan = self.is_adopted() && self.session_leader_alive == Some(false);
+    }
+
+    /// Whether the parent is PID 1 or a known subreaper, as for a service or an orphan
+    pub fn is_adopted(&self) -> bool {
+        self.parent_pid == Some(1)
+            || self
+                .parent_name
+                .as_deref()
+                .is_some_and(|name| SUBREAPER_NAMES.contains(&name))
+    }
+
+    /// Whether the built-in runaway CPU rule (or the config's override of it) matches
+    pub fn is_runaway(&self) -> bool {
+        self.alerts.iter().any(|alert| alert.rule == RUNAWAY_RULE)
+    }
+
+    /// Highest severity among the entry's health alerts
+    pub fn worst_severity(&self) -> Option<Severity> {
+        self.alerts.iter().map(|alert| alert.severity).max()
+    }
+
+    /// Seconds since the process started (None if the start time is unknown)
+    pub fn uptime_secs(&self) -> Option<u64> {
+        let start = self.start_time?;
+        Some(unix_now().saturating_sub(start))
+    }
+
+    /// Human-readable uptime, e.g. "3d 4h" ("-" if unknown)
+    pub fn uptime_display(&self) -> String {
+        self.uptime_secs()
+            .map(format_uptime)
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Process start time formatted as "YYYY-MM-DD HH:MM:SS UTC" ("-" if unknown)
+    pub fn start_time_display(&self) -> String {
+        self.start_time
+            .map(format_unix_time)
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Parent chain like "tmux (812) › zsh (813) › npm (2201)" ("-" if unknown)
+    pub fn ancestry_display(&self) -> String {
+        if self.ancestors.is_empty() {
+            return "-".to_string();
+        }
+        self.ancestors
+            .iter()
+            .map(|ancestor| format!("{} ({})", ancestor.name, ancestor.pid))
+            .collect::<Vec<_>>()
+            .join(" › ")
+    }
+
+    /// Protocol and bind address, e.g. "TCP 0.0.0.0:3000" or "UDP [::1]:5353"
+    pub fn socket_display(&self) -> String {
+        let host = match self.address.as_deref() {
+            Some(addr) if addr.contains(':') => format!("[{}]", addr),
+            Some(addr) => addr.to_string(),
+            None => "*".to_string(),
+        };
+        format!("{} {}:{}", self.protocol, host, self.port)
+    }
+}
+
+/// Format a duration in seconds as a compact uptime with the two largest units
+pub fn format_uptime(secs: u64) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = MINUTE * 60;
+    const DAY: u64 = HOUR * 24;
+
+    if secs >= DAY {
+        format!("{}d {}h", secs / DAY, (secs % DAY) / HOUR)
+    } else if secs >= HOUR {
+        format!("{}h {}m", secs / HOUR, (secs % HOUR) / MINUTE)
+    } else if secs >= MINUTE {
+        format!("{}m {}s", secs / MINUTE, secs % MINUTE)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Current time in seconds since the Unix epoch
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Format seconds since the Unix epoch as a UTC date and time
+pub fn format_unix_time(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let time_of_day = secs % 86_400;
+
+    // Convert days since epoch to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        time_of_day / 3_600,
+        (time_of_day % 3_600) / 60,
+        time_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rule;
+    use std::thread;
+
+    // ==================== Helper Functions ====================
+
+    fn create_test_entry(port: u16, protocol: Protocol, pid: u32) -> PortEntry {
+        PortEntry {
+            protocol,
+            ..PortEntry::test(port, pid, &format!("process_{}", pid))
+        }
+    }
+
+    fn create_entries(count: usize) -> Vec<PortEntry> {
+        (0..count)
+            .map(|i| create_test_entry(3000 + i as u16, Protocol::Tcp, i as u32 + 1))
+            .collect()
+    }
+
+    // ==================== Protocol Tests ====================
+
+    #[test]
+    fn test_protocol_display_tcp() {
+        assert_eq!(format!("{}", Protocol::Tcp), "TCP");
+    }
+
+    #[test]
+    fn test_protocol_display_udp() {
+        assert_eq!(format!("{}", Protocol::Udp), "UDP");
+    }
+
+    #[test]
+    fn test_protocol_equality() {
+        assert_eq!(Protocol::Tcp, Protocol::Tcp);
+        assert_eq!(Protocol::Udp, Protocol::Udp);
+        assert_ne!(Protocol::Tcp, Protocol::Udp);
+    }
+
+    #[test]
+    fn test_protocol_clone() {
+        let proto = Protocol::Tcp;
+        let cloned = proto;
+        assert_eq!(proto, cloned);
+    }
+
+    #[test]
+    fn test_protocol_debug() {
+        assert_eq!(format!("{:?}", Protocol::Tcp), "Tcp");
+        assert_eq!(format!("{:?}", Protocol::Udp), "Udp");
+    }
+
+    // ==================== PortEntry Tests ====================
+
+    #[test]
+    fn test_port_entry_clone() {
+        let entry = create_test_entry(8080, Protocol::Tcp, 1234);
+        let cloned = entry.clone();
+        assert_eq!(entry.port, cloned.port);
+        assert_eq!(entry.pid, cloned.pid);
+        assert_eq!(entry.process_name, cloned.process_name);
+    }
+
+    #[test]
+    fn test_port_entry_debug() {
+        let entry = create_test_entry(8080, Protocol::Tcp, 1234);
+        let debug_str = format!("{:?}", entry);
+        assert!(debug_str.contains("8080"));
+        assert!(debug_str.contains("1234"));
+        assert!(debug_str.contains("Tcp"));
+    }
+
+    // ==================== Health Flag Tests ====================
+
+    #[test]
+    fn test_zombie_detection_defunct_state() {
+        let mut entry = create_test_entry(3000, Protocol::Tcp, 1234);
+        entry.state = ProcessState::Zombie;
+
+        entry.detect_health_flags();
+        assert!(entry.is_zombie, "Z state should be flagged as zombie");
+    }
+
+    #[test]
+    fn test_zombie_detection_ignores_cpu() {
+        let mut entry = create_test_entry(3000, Protocol::Tcp, 1234);
+        entry.state = ProcessState::Running;
+        entry.cpu_usage = 100.0;
+
+        entry.detect_health_flags();
+        assert!(!entry.is_zombie, "High CPU alone should NOT be a zombie");
+    }
+
+    #[test]
+    fn test_orphan_detection_reparented_to_init() {
+        let mut entry = create_test_entry(3000, Protocol::Tcp, 1234);
+        entry.parent_pid = Some(1);
+        entry.session_leader_alive = Some(false);
+
+        entry.detect_health_flags();
+        assert!(entry.is_orphan, "Re-parented to PID 1 should be orphan");
+    }
+
+    #[test]
+    fn test_orphan_detection_ignores_init_services() {
+        // systemd starts a service in its own session, which it leads
+        let mut entry = create_test_entry(5432, Protocol::Tcp, 812);
+        entry.parent_pid = Some(1);
+        entry.parent_name = Some("systemd".into());
+        entry.session_leader_alive = Some(true);
+
+        entry.detect_health_flags();
+        assert!(
+            !entry.is_orphan,
+            "A service started by systemd is not an orphan"
+        );
+
+        // Without session information there's nothing to tell them apart
+        entry.session_leader_alive = None;
+        entry.detect_health_flags();
+        assert!(!entry.is_orphan);
+    }
+
+    #[test]
+    fn test_orphan_detection_subreaper() {
+        let mut entry = create_test_entry(3000, Protocol::Tcp, 1234);
+        entry.parent_pid = Some(812);
+        entry.parent_name = Some("tini".into());
+        entry.session_leader_alive = Some(false);
+
+        entry.detect_health_flags();
+        assert!(entry.is_orphan, "Child of a subreaper should be orphan");
+    }
+
+    #[test]
+    fn test_orphan_detection_regular_parent() {
+        let mut entry = create_test_entry(3000, Protocol::Tcp, 1234);
+        entry.parent_pid = Some(4120);
+        entry.parent_name = Some("zsh".into());
+
+        entry.detect_health_flags();
+        assert!(!entry.is_orphan, "Child of a shell should NOT be orphan");
+    }
+
+    #[test]
+    fn test_orphan_detection_no_parent() {
+        let mut entry = create_test_entry(1, Protocol::Tcp, 1);
+        entry.parent_pid = None;
+
+        entry.detect_health_flags();
+        assert!(!entry.is_orphan, "PID 1 itself is not an orphan");
+    }
+
+    #[test]
+    fn test_health_flags_healthy_process() {
+        let mut entry = create_test_entry(3000, Protocol::Tcp, 1234);
+        entry.state = ProcessState::Sleeping;
+        entry.parent_pid = Some(4120);
+
+        entry.detect_health_flags();
+        assert!(!entry.is_zombie);
+        assert!(!entry.is_orphan);
+    }
+
+    #[test]
+    fn test_update_entries_applies_default_rules() {
+        let mut app = App::new();
+        let mut entries = create_entries(2);
+        entries[0].cpu_usage = 40.0; // Exactly at the default threshold
+        entries[1].cpu_usage = 40.1;
+
+        app.update_entries(entries);
+        assert!(app.entries[0].alerts.is_empty());
+        assert_eq!(app.entries[1].alerts[0].rule, "runaway");
+        assert!(app.entries[1].is_runaway());
+        assert!(!app.entries[0].is_runaway());
+        assert_eq!(app.entries[1].worst_severity(), Some(Severity::Warning));
+    }
+
+    #[test]
+    fn test_update_entries_uses_configured_rules() {
+        let mut app = App::new();
+        app.rules = RuleEngine::new(vec![
+            Rule::parse("ssh", "port = 22", Severity::Info).unwrap(),
+            Rule::parse("root-port", "port < 1024", Severity::Critical).unwrap(),
+        ]);
+
+        app.update_entries(vec![
+            create_test_entry(22, Protocol::Tcp, 1),
+            create_test_entry(8080, Protocol::Tcp, 2),
+        ]);
+
+        assert_eq!(app.entries[0].alerts.len(), 2);
+        assert_eq!(app.entries[0].worst_severity(), Some(Severity::Critical));
+        assert_eq!(app.entries[1].worst_severity(), None);
+    }
+
+    #[test]
+    fn test_process_state_from_ps_stat() {
+        assert_eq!(ProcessState::from_ps_stat("R+"), ProcessState::Running);
+        assert_eq!(ProcessState::from_ps_stat("Ss"), ProcessState::Sleeping);
+        assert_eq!(ProcessState::from_ps_stat("D"), ProcessState::DiskSleep);
+        assert_eq!(ProcessState::from_ps_stat("I<"), ProcessState::Idle);
+        assert_eq!(ProcessState::from_ps_stat("T"), ProcessState::Stopped);
+        assert_eq!(ProcessState::from_ps_stat("Z"), ProcessState::Zombie);
+        assert_eq!(ProcessState::from_ps_stat(""), ProcessState::Unknown);
+    }
+
+    // ==================== Detail Panel Tests ====================
+
+    #[test]
+    fn test_toggle_details() {
+        let mut app = App::new();
+        assert!(!app.show_details);
+
+        app.toggle_details();
+        assert!(app.show_details);
+
+        app.toggle_details();
+        assert!(!app.show_details);
+    }
+
+    #[test]
+    fn test_toggle_details_layout_shows_panel() {
+        let mut app = App::new();
+        assert_eq!(app.details_layout, DetailsLayout::Right);
+
+        app.toggle_details_layout();
+        assert_eq!(app.details_layout, DetailsLayout::Bottom);
+        assert!(app.show_details);
+
+        app.toggle_details_layout();
+        assert_eq!(app.details_layout, DetailsLayout::Right);
+    }
+
+    #[test]
+    fn test_sockets_of_ignores_filter() {
+        let mut app = App::new();
+        app.update_entries(vec![
+            create_test_entry(9229, Protocol::Tcp, 7),
+            create_test_entry(22, Protocol::Tcp, 1),
+            create_test_entry(5353, Protocol::Udp, 7),
+            create_test_entry(3000, Protocol::Tcp, 7),
+        ]);
+        app.filter = "9229".to_string();
+        app.refresh_view();
+        assert_eq!(app.entries.len(), 1);
+
+        let ports: Vec<u16> = app
+            .sockets_of(&create_test_entry(3000, Protocol::Tcp, 7))
+            .iter()
+            .map(|e| e.port)
+            .collect();
+        assert_eq!(ports, vec![3000, 5353, 9229]);
+    }
+
+    #[test]
+    fn test_detail_displays() {
+        let mut entry = create_test_entry(3000, Protocol::Tcp, 7);
+        assert_eq!(entry.ancestry_display(), "-");
+        assert_eq!(entry.socket_display(), "TCP *:3000");
+
+        entry.ancestors = vec![
+            Ancestor {
+                pid: 812,
+                name: "tmux".to_string(),
+            },
+            Ancestor {
+                pid: 813,
+                name: "zsh".to_string(),
+            },
+        ];
+        entry.address = Some("::1".to_string());
+        assert_eq!(entry.ancestry_display(), "tmux (812) › zsh (813)");
+        assert_eq!(entry.socket_display(), "TCP [::1]:3000");
+    }
+
+    #[test]
+    fn test_update_entries_records_history_before_filter() {
+        let mut app = App::new();
+        app.filter = "process_1".into();
+
+        app.update_entries(create_entries(2));
+        app.update_entries(create_entries(2));
+
+        // Filtered-out processes keep accumulating samples
+        assert_eq!(app.history.get(None, 1).unwrap().cpu.len(), 2);
+        assert_eq!(app.history.get(None, 2).unwrap().cpu.len(), 2);
+    }
+
+    #[test]
+    fn test_set_remote_host_clears_history() {
+        let mut app = App::new();
+        app.update_entries(create_entries(1));
+        assert!(app.history.get(None, 1).is_some());
+
+        app.set_remote_host(Some("user@example.com".into()));
+        assert!(app.history.get(None, 1).is_none());
+    }
+
+    // ==================== Event Log Tests ====================
+
+    #[test]
+    fn test_first_scan_produces_no_events() {
+        let mut app = App::new();
+        app.update_entries(create_entries(3));
+        assert!(app.events.is_empty());
+    }
+
+    #[test]
+    fn test_update_entries_logs_open_and_close() {
+        let mut app = App::new();
+        app.update_entries(create_entries(2));
+        app.update_entries(create_entries(3));
+        app.update_entries(create_entries(1));
+
+        let events: Vec<String> = app.events.newest_first().map(|e| e.to_string()).collect();
+        assert_eq!(
+            events,
+            vec![
+                "3002/TCP closed (was process_3 (3))",
+                "3001/TCP closed (was process_2 (2))",
+                "3002/TCP opened by process_3 (3)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_ignore_filter() {
+        let mut app = App::new();
+        app.filter = "process_1".into();
+        app.update_entries(create_entries(1));
+        app.update_entries(create_entries(2));
 
-        assert!(app.remote_host.is_none());
+        // process_2 is filtered out of the table but its port still opened
+        assert_eq!(app.entries.len(), 1);
+        assert_eq!(app.events.len(), 1);
     }
 
     #[test]
-    fn test_connect_mode_initialization() {
-        let app = App::new();
-        assert!(!app.connect_mode);
-        assert!(!app.connect_key_mode);
-        assert!(app.connect_input.is_empty());
-        assert!(app.connect_key_input.is_empty());
+    fn test_switching_host_does_not_log_events() {
+        let mut app = App::new();
+        app.update_entries(create_entries(2));
+        app.set_remote_host(Some("user@example.com".into()));
+        app.update_entries(Vec::new());
+        assert!(app.events.is_empty());
     }
 
     #[test]
-    fn test_connect_mode_clears_on_enter() {
+    fn test_toggle_and_scroll_events() {
         let mut app = App::new();
-        app.connect_input.push_str("old");
-        app.connect_key_input.push_str("old_key");
+        app.toggle_events();
+        assert!(app.show_events);
 
-        app.enter_connect_mode();
+        app.update_entries(Vec::new());
+        app.update_entries(create_entries(3));
+        app.scroll_events_older();
+        app.scroll_events_older();
+        app.scroll_events_older(); // Can't scroll past the oldest event
+        assert_eq!(app.events_scroll, 2);
 
-        assert!(app.connect_input.is_empty());
-        assert!(app.connect_key_input.is_empty());
+        // New events keep the scrolled view in place
+        app.update_entries(Vec::new());
+        assert_eq!(app.events_scroll, 5);
+
+        app.scroll_events_newer();
+        assert_eq!(app.events_scroll, 4);
+
+        app.toggle_events();
+        assert!(!app.show_events);
+        assert_eq!(app.events_scroll, 0);
     }
 
     #[test]
-    fn test_connect_key_mode_goes_back_to_host() {
+    fn test_events_scroll_stays_within_full_log() {
         let mut app = App::new();
-        app.enter_connect_mode();
-        app.connect_input.push_str("user@host");
-        app.enter_connect_key_mode();
-        app.connect_key_input.push_str("/key");
-
-        // Simulate Esc in key mode
-        app.connect_key_mode = false;
-        app.connect_key_input.clear();
+        app.events = EventLog::new(4);
+        app.update_entries(Vec::new());
+        app.update_entries(create_entries(3));
+        app.scroll_events_older();
+        app.scroll_events_older();
 
-        assert!(!app.connect_key_mode);
-        assert!(app.connect_key_input.is_empty());
-        assert_eq!(app.connect_input, "user@host");
+        // Three more events push the oldest ones out of the log
+        app.update_entries(Vec::new());
+        assert_eq!(app.events.len(), 4);
+        assert_eq!(app.events_scroll, 3);
     }
-}
 
-/// CPU threshold for zombie detection (40%)
-pub const ZOMBIE_CPU_THRESHOLD: f32 = 40.0;
+    // ==================== Group Tests ====================
 
-impl PortEntry {
-    /// Check if this entry should be flagged as a zombie
-    /// A zombie is defined as: CPU > 40% AND no parent process (orphaned)
-    pub fn detect_zombie(&mut self) {
-        self.is_zombie = self.cpu_usage > ZOMBIE_CPU_THRESHOLD && !self.has_parent;
+    /// Ports 3000-3002 owned by PID 1 and 3003 by PID 2
+    fn create_grouped_app() -> App {
+        let mut app = App::new();
+        let mut entries = create_entries(4);
+        for entry in &mut entries[..3] {
+            entry.pid = 1;
+        }
+        app.update_entries(entries);
+        app.set_group_by(Some(GroupBy::Pid));
+        app
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::thread;
 
-    // ==================== Helper Functions ====================
+    #[test]
+    fn test_grouped_selection() {
+        let mut app = create_grouped_app();
+        assert_eq!(app.row_count(), 2);
+        assert_eq!(app.selected_group().unwrap().ports, vec![3000, 3001, 3002]);
+        // A PID group stands for its process
+        assert_eq!(app.selected_entry().unwrap().pid, 1);
+        assert_eq!(app.selected_entries().len(), 3);
 
-    fn create_test_entry(port: u16, protocol: Protocol, pid: u32) -> PortEntry {
-        PortEntry {
-            port,
-            protocol,
-            pid,
-            process_name: format!("process_{}", pid),
-            cpu_usage: 0.0,
-            memory_usage: 0,
-            memory_display: "0 B".into(),
-            has_parent: true,
-            is_zombie: false,
-        }
+        app.select_next();
+        assert_eq!(app.selected_entry().unwrap().port, 3003);
+        app.select_next();
+        assert_eq!(app.selected_index, 0);
     }
 
-    fn create_entries(count: usize) -> Vec<PortEntry> {
-        (0..count)
-            .map(|i| create_test_entry(3000 + i as u16, Protocol::Tcp, i as u32 + 1))
-            .collect()
-    }
+    #[test]
+    fn test_expand_and_collapse_group() {
+        let mut app = create_grouped_app();
+        app.expand_selected();
+        assert_eq!(app.row_count(), 5);
 
-    // ==================== Protocol Tests ====================
+        // Collapsing from an entry selects its group again
+        app.selected_index = 2;
+        app.collapse_selected();
+        assert_eq!(app.row_count(), 2);
+        assert_eq!(app.selected_index, 0);
 
-    #[test]
-    fn test_protocol_display_tcp() {
-        assert_eq!(format!("{}", Protocol::Tcp), "TCP");
+        // Groups stay expanded across scans
+        app.toggle_group_selected();
+        app.update_entries(app.last_scan.clone().unwrap());
+        assert_eq!(app.row_count(), 5);
     }
 
     #[test]
-    fn test_protocol_display_udp() {
-        assert_eq!(format!("{}", Protocol::Udp), "UDP");
+    fn test_tree_collapse_walks_up() {
+        let mut app = App::new();
+        let mut entries = create_entries(2);
+        for entry in &mut entries {
+            entry.ancestors = vec![
+                Ancestor {
+                    pid: 100,
+                    name: "tmux".into(),
+                },
+                Ancestor {
+                    pid: 200,
+                    name: "zsh".into(),
+                },
+            ];
+        }
+        app.update_entries(entries);
+        app.set_group_by(Some(GroupBy::Tree));
+        // Tree nodes start expanded: tmux, zsh, :3000, :3001
+        assert_eq!(app.row_count(), 4);
+        assert_eq!(app.row_depths, vec![0, 1, 2, 2]);
+        assert!(app.selected_entry().is_none());
+
+        // From an entry, collapse its parent node and select it
+        app.selected_index = 3;
+        app.collapse_selected();
+        assert_eq!((app.row_count(), app.selected_index), (2, 1));
+        // A collapsed node collapses its own parent next
+        app.collapse_selected();
+        assert_eq!((app.row_count(), app.selected_index), (1, 0));
+
+        app.expand_selected();
+        assert_eq!(app.row_count(), 2);
     }
 
     #[test]
-    fn test_protocol_equality() {
-        assert_eq!(Protocol::Tcp, Protocol::Tcp);
-        assert_eq!(Protocol::Udp, Protocol::Udp);
-        assert_ne!(Protocol::Tcp, Protocol::Udp);
+    fn test_mark_group_marks_its_entries() {
+        let mut app = create_grouped_app();
+        app.toggle_mark_selected();
+        assert_eq!(app.marked.len(), 3);
+        assert_eq!(app.selected_index, 1);
     }
 
     #[test]
-    fn test_protocol_clone() {
-        let proto = Protocol::Tcp;
-        let cloned = proto;
-        assert_eq!(proto, cloned);
+    fn test_fleet_skips_process_tree() {
+        let mut app = App::new();
+        app.is_fleet = true;
+        app.set_group_by(Some(GroupBy::Tree));
+        assert_eq!(app.group_by, None);
+        app.set_group_by(Some(GroupBy::Name));
+        app.cycle_group_by();
+        assert_eq!(app.group_by, None);
     }
 
     #[test]
-    fn test_protocol_debug() {
-        assert_eq!(format!("{:?}", Protocol::Tcp), "Tcp");
-        assert_eq!(format!("{:?}", Protocol::Udp), "Udp");
+    fn test_ungroup() {
+        let mut app = create_grouped_app();
+        app.cycle_group_by();
+        assert_eq!(app.group_by, Some(GroupBy::Name));
+        app.cycle_group_by();
+        assert_eq!(app.group_by, Some(GroupBy::Tree));
+        app.cycle_group_by();
+        assert_eq!(app.group_by, None);
+        assert_eq!(app.row_count(), 4);
+        assert!(app.rows.is_empty());
     }
 
-    // ==================== PortEntry Tests ====================
+    // ==================== Mark Tests ====================
 
     #[test]
-    fn test_port_entry_clone() {
-        let entry = create_test_entry(8080, Protocol::Tcp, 1234);
-        let cloned = entry.clone();
-        assert_eq!(entry.port, cloned.port);
-        assert_eq!(entry.pid, cloned.pid);
-        assert_eq!(entry.process_name, cloned.process_name);
+    fn test_marks_survive_rescans_of_same_rows() {
+        let mut app = App::new();
+        app.update_entries(create_entries(3));
+        app.toggle_mark_all();
+        assert_eq!(app.marked.len(), 3);
+
+        app.update_entries(create_entries(2));
+        assert_eq!(app.marked.len(), 2);
+
+        // A restarted process (new PID on the same port) isn't marked
+        let mut entries = create_entries(2);
+        entries[0].pid = 99;
+        app.update_entries(entries);
+        assert_eq!(app.marked_entries().len(), 1);
     }
 
     #[test]
-    fn test_port_entry_debug() {
-        let entry = create_test_entry(8080, Protocol::Tcp, 1234);
-        let debug_str = format!("{:?}", entry);
-        assert!(debug_str.contains("8080"));
-        assert!(debug_str.contains("1234"));
-        assert!(debug_str.contains("Tcp"));
+    fn test_request_bulk_groups_by_pid() {
+        let mut app = App::new();
+        let mut entries = create_entries(3);
+        entries[2].pid = entries[0].pid;
+        app.update_entries(entries);
+        app.toggle_mark_all();
+
+        app.request_bulk(BulkKind::Kill);
+        let bulk = app.pending_bulk.take().unwrap();
+        assert_eq!(bulk.targets.len(), 2);
+        assert_eq!(bulk.targets[0].ports, vec![3000, 3002]);
+        assert_eq!(bulk.question(), "Kill 2 processes?");
+
+        app.marked.clear();
+        app.request_bulk(BulkKind::Kill);
+        assert!(app.pending_bulk.is_none());
     }
 
-    // ==================== Zombie Detection Tests ====================
+    // ==================== Pin Tests ====================
 
     #[test]
-    fn test_zombie_detection_high_cpu_no_parent() {
-        let mut entry = PortEntry {
-            port: 3000,
-            protocol: Protocol::Tcp,
-            pid: 1234,
-            process_name: "test".into(),
-            cpu_usage: 50.0,
-            memory_usage: 1024,
-            memory_display: "1 KB".into(),
-            has_parent: false,
-            is_zombie: false,
-        };
+    fn test_toggle_pin_moves_entry_to_top() {
+        let mut app = App::new();
+        app.update_entries(create_entries(3));
+        app.selected_index = 2;
 
-        entry.detect_zombie();
-        assert!(entry.is_zombie, "High CPU + no parent should be zombie");
-    }
+        app.toggle_pin_selected();
+        assert!(app.pins.contains(None, 3002));
+        assert_eq!(app.entries[0].port, 3002);
+        assert_eq!(app.selected_index, 0);
 
-    #[test]
-    fn test_zombie_detection_high_cpu_has_parent() {
-        let mut entry = PortEntry {
-            port: 3000,
-            protocol: Protocol::Tcp,
-            pid: 1234,
-            process_name: "test".into(),
-            cpu_usage: 50.0,
-            memory_usage: 1024,
-            memory_display: "1 KB".into(),
-            has_parent: true,
-            is_zombie: false,
-        };
+        // Pinned entries stay on top whatever the sort order
+        app.sort_order = SortOrder::Descending;
+        app.update_entries(create_entries(3));
+        let ports: Vec<u16> = app.entries.iter().map(|e| e.port).collect();
+        assert_eq!(ports, vec![3002, 3001, 3000]);
 
-        entry.detect_zombie();
-        assert!(
-            !entry.is_zombie,
-            "High CPU but has parent should NOT be zombie"
-        );
+        app.toggle_pin_selected();
+        assert!(!app.pins.contains(None, 3002));
     }
 
     #[test]
-    fn test_zombie_detection_low_cpu_no_parent() {
-        let mut entry = PortEntry {
-            port: 3000,
-            protocol: Protocol::Tcp,
-            pid: 1234,
-            process_name: "test".into(),
-            cpu_usage: 20.0,
-            memory_usage: 1024,
-            memory_display: "1 KB".into(),
-            has_parent: false,
-            is_zombie: false,
-        };
+    fn test_pins_stay_on_their_host() {
+        let mut app = App::new();
+        app.set_remote_host(Some("staging".into()));
+        app.update_entries(create_entries(1));
+        app.toggle_pin_selected();
+        assert!(app.pins.contains(Some("staging"), 3000));
+        app.pins.toggle(Some("staging"), 5432).unwrap();
+
+        // Another host doesn't see staging's pins
+        let mut other = app.new_tab();
+        other.set_remote_host(Some("prod".into()));
+        other.update_entries(create_entries(1));
+        assert!(!other.is_pinned(&other.entries[0]));
+        assert!(other.pinned_down().is_empty());
+
+        // The fleet view shows them on staging's rows only
+        let mut fleet = app.new_tab();
+        fleet.is_fleet = true;
+        fleet.tab_labels = vec![FLEET_LABEL.into(), "staging".into(), "prod".into()];
+        let rows: Vec<PortEntry> = ["staging", "prod"]
+            .iter()
+            .map(|host| PortEntry {
+                host: Some(host.to_string()),
+                ..create_entries(1).remove(0)
+            })
+            .collect();
+        fleet.update_fleet(rows, Vec::new());
+        let pinned: Vec<_> = fleet
+            .entries
+            .iter()
+            .map(|e| (e.host.clone().unwrap(), fleet.is_pinned(e)))
+            .collect();
+        assert!(pinned.contains(&("staging".into(), true)));
+        assert!(pinned.contains(&("prod".into(), false)));
+        assert_eq!(fleet.pinned_down(), vec![(Some("staging".into()), 5432)]);
+    }
+
+    #[test]
+    fn test_pinned_down() {
+        let mut app = App::new();
+        app.pins.toggle(None, 3001).unwrap();
+        app.pins.toggle(None, 9999).unwrap();
+        app.update_entries(create_entries(3));
+        assert_eq!(app.pinned_down(), vec![(None, 9999)]);
 
-        entry.detect_zombie();
-        assert!(
-            !entry.is_zombie,
-            "Low CPU even without parent should NOT be zombie"
-        );
+        app.update_entries(create_entries(1));
+        assert_eq!(app.pinned_down(), vec![(None, 3001), (None, 9999)]);
     }
 
     #[test]
-    fn test_zombie_detection_exactly_at_threshold() {
-        let mut entry = PortEntry {
-            port: 3000,
-            protocol: Protocol::Tcp,
-            pid: 1234,
-            process_name: "test".into(),
-            cpu_usage: ZOMBIE_CPU_THRESHOLD, // Exactly 40%
-            memory_usage: 1024,
-            memory_display: "1 KB".into(),
-            has_parent: false,
-            is_zombie: false,
-        };
-
-        entry.detect_zombie();
-        assert!(
-            !entry.is_zombie,
-            "Exactly at threshold (40%) should NOT be zombie"
-        );
+    fn test_pinned_events_queue_notifications() {
+        let mut app = App::new();
+        app.pins.toggle(None, 3001).unwrap();
+        app.update_entries(create_entries(3));
+        app.update_entries(create_entries(1));
+
+        // Only the pinned port notifies, though both closed
+        assert_eq!(app.events.len(), 2);
+        assert_eq!(app.notifications.len(), 1);
+        assert_eq!(app.notifications[0].port, 3001);
+        match &app.status_message {
+            StatusMessage::Info(msg) => assert_eq!(msg, "📌 3001/TCP closed (was process_2 (2))"),
+            _ => panic!("Expected Info status"),
+        }
     }
 
-    #[test]
-    fn test_zombie_detection_just_above_threshold() {
-        let mut entry = PortEntry {
-            port: 3000,
-            protocol: Protocol::Tcp,
-            pid: 1234,
-            process_name: "test".into(),
-            cpu_usage: ZOMBIE_CPU_THRESHOLD + 0.1, // 40.1%
-            memory_usage: 1024,
-            memory_display: "1 KB".into(),
-            has_parent: false,
-            is_zombie: false,
-        };
+    // ==================== Uptime Tests ====================
 
-        entry.detect_zombie();
-        assert!(entry.is_zombie, "Just above threshold should be zombie");
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(0), "0s");
+        assert_eq!(format_uptime(59), "59s");
+        assert_eq!(format_uptime(60), "1m 0s");
+        assert_eq!(format_uptime(3_599), "59m 59s");
+        assert_eq!(format_uptime(3_600), "1h 0m");
+        assert_eq!(format_uptime(86_399), "23h 59m");
+        assert_eq!(format_uptime(86_400), "1d 0h");
+        assert_eq!(format_uptime(14 * 86_400 + 5 * 3_600 + 30), "14d 5h");
     }
 
     #[test]
-    fn test_zombie_detection_max_cpu() {
-        let mut entry = PortEntry {
-            port: 3000,
-            protocol: Protocol::Tcp,
-            pid: 1234,
-            process_name: "test".into(),
-            cpu_usage: 100.0,
-            memory_usage: 1024,
-            memory_display: "1 KB".into(),
-            has_parent: false,
-            is_zombie: false,
-        };
-
-        entry.detect_zombie();
-        assert!(entry.is_zombie, "100% CPU + no parent should be zombie");
+    fn test_format_unix_time() {
+        assert_eq!(format_unix_time(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_unix_time(951_782_400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(format_unix_time(1_700_000_000), "2023-11-14 22:13:20 UTC");
     }
 
     #[test]
-    fn test_zombie_detection_zero_cpu() {
-        let mut entry = PortEntry {
-            port: 3000,
-            protocol: Protocol::Tcp,
-            pid: 1234,
-            process_name: "test".into(),
-            cpu_usage: 0.0,
-            memory_usage: 1024,
-            memory_display: "1 KB".into(),
-            has_parent: false,
-            is_zombie: false,
-        };
-
-        entry.detect_zombie();
-        assert!(!entry.is_zombie, "0% CPU should NOT be zombie");
+    fn test_uptime_unknown_start_time() {
+        let entry = create_test_entry(3000, Protocol::Tcp, 1);
+        assert_eq!(entry.uptime_secs(), None);
+        assert_eq!(entry.uptime_display(), "-");
+        assert_eq!(entry.start_time_display(), "-");
     }
 
     #[test]
-    fn test_zombie_threshold_constant() {
-        assert_eq!(ZOMBIE_CPU_THRESHOLD, 40.0);
+    fn test_uptime_from_start_time() {
+        let mut entry = create_test_entry(3000, Protocol::Tcp, 1);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        entry.start_time = Some(now - 2 * 86_400 - 3_600);
+
+        let uptime = entry.uptime_secs().unwrap();
+        assert!(uptime >= 2 * 86_400 + 3_600);
+        assert_eq!(entry.uptime_display(), "2d 1h");
     }
 
     // ==================== App Creation Tests ====================
@@ -1262,13 +2866,14 @@ mod tests {
         assert_eq!(SortColumn::Pid.next(), SortColumn::ProcessName);
         assert_eq!(SortColumn::ProcessName.next(), SortColumn::CpuUsage);
         assert_eq!(SortColumn::CpuUsage.next(), SortColumn::MemoryUsage);
-        assert_eq!(SortColumn::MemoryUsage.next(), SortColumn::Port); // Wraps
+        assert_eq!(SortColumn::MemoryUsage.next(), SortColumn::Uptime);
+        assert_eq!(SortColumn::Uptime.next(), SortColumn::Port); // Wraps
     }
 
     #[test]
     fn test_sort_column_full_cycle() {
         let mut col = SortColumn::Port;
-        for _ in 0..6 {
+        for _ in 0..7 {
             col = col.next();
         }
         assert_eq!(col, SortColumn::Port); // Back to start
@@ -1420,6 +3025,28 @@ mod tests {
         assert_eq!(app.entries[2].cpu_usage, 10.0);
     }
 
+    #[test]
+    fn test_sorting_by_uptime() {
+        let mut app = App::new();
+        app.sort_column = SortColumn::Uptime;
+        app.sort_order = SortOrder::Descending;
+
+        let mut entries = vec![
+            create_test_entry(3000, Protocol::Tcp, 1),
+            create_test_entry(3001, Protocol::Tcp, 2),
+            create_test_entry(3002, Protocol::Tcp, 3),
+        ];
+        entries[0].start_time = Some(2_000_000_000);
+        entries[1].start_time = Some(1_000_000_000); // Oldest process
+        entries[2].start_time = None; // Unknown sorts as shortest uptime
+
+        app.update_entries(entries);
+
+        assert_eq!(app.entries[0].pid, 2);
+        assert_eq!(app.entries[1].pid, 1);
+        assert_eq!(app.entries[2].pid, 3);
+    }
+
     // ==================== Filter Tests ====================
 
     #[test]
@@ -1603,10 +3230,9 @@ mod tests {
     fn test_regex_filter_basic() {
         let mut app = App::new();
         app.filter = "process_[12]".into();
-        app.try_compile_filter_regex();
+        app.compile_filter();
 
-        assert!(app.filter_is_regex);
-        assert!(app.compiled_regex.is_some());
+        assert!(app.filter_error.is_none());
 
         let entries = vec![
             create_test_entry(3000, Protocol::Tcp, 1), // process_1
@@ -1624,9 +3250,6 @@ mod tests {
     fn test_regex_filter_case_insensitive() {
         let mut app = App::new();
         app.filter = "PROCESS".into();
-        app.try_compile_filter_regex();
-
-        assert!(app.filter_is_regex);
 
         let entries = create_entries(3);
         app.update_entries(entries);
@@ -1639,9 +3262,6 @@ mod tests {
     fn test_regex_filter_port_pattern() {
         let mut app = App::new();
         app.filter = "^300[01]$".into(); // Match ports 3000 or 3001
-        app.try_compile_filter_regex();
-
-        assert!(app.filter_is_regex);
 
         let entries = vec![
             create_test_entry(3000, Protocol::Tcp, 1),
@@ -1658,24 +3278,198 @@ mod tests {
     fn test_regex_filter_invalid_falls_back() {
         let mut app = App::new();
         app.filter = "[invalid".into(); // Invalid regex (unclosed bracket)
-        app.try_compile_filter_regex();
+        app.compile_filter();
+
+        // Should fall back to literal matching rather than report an error
+        assert!(app.filter_error.is_none());
 
-        // Should fall back to literal matching
-        assert!(!app.filter_is_regex);
-        assert!(app.compiled_regex.is_none());
+        let mut entry = create_test_entry(3000, Protocol::Tcp, 1);
+        entry.process_name = "[invalid]".into();
+        app.update_entries(vec![entry, create_test_entry(3001, Protocol::Tcp, 2)]);
+        assert_eq!(app.entries.len(), 1);
     }
 
     #[test]
     fn test_regex_filter_cleared() {
         let mut app = App::new();
-        app.filter = "test".into();
-        app.try_compile_filter_regex();
-        assert!(app.filter_is_regex);
+        app.filter = "process_1".into();
+        app.update_entries(create_entries(3));
+        assert_eq!(app.entries.len(), 1);
 
         app.clear_filter();
 
         assert!(app.filter.is_empty());
-        assert!(!app.filter_is_regex);
-        assert!(app.compiled_regex.is_none());
+        assert_eq!(app.entries.len(), 3);
+    }
+
+    // ==================== Filter Query Tests ====================
+
+    #[test]
+    fn test_query_filter_fields() {
+        let mut app = App::new();
+        let mut entries = create_entries(4);
+        entries[3].protocol = Protocol::Udp;
+        entries[1].cpu_usage = 50.0;
+
+        app.filter = "proto:tcp port:3000-3002 !pid:1".into();
+        app.update_entries(entries.clone());
+        let ports: Vec<u16> = app.entries.iter().map(|e| e.port).collect();
+        assert_eq!(ports, vec![3001, 3002]);
+
+        app.filter = "cpu>20".into();
+        app.refresh_view();
+        assert_eq!(app.entries.len(), 1);
+        assert_eq!(app.entries[0].port, 3001);
+    }
+
+    #[test]
+    fn test_filter_updates_while_typing() {
+        let mut app = App::new();
+        app.update_entries(create_entries(3));
+        app.enter_filter_mode();
+
+        for c in "port:3001".chars() {
+            app.filter_push(c);
+        }
+        assert_eq!(app.entries.len(), 1);
+
+        app.filter_pop();
+        app.filter_pop();
+        assert_eq!(app.filter, "port:30");
+        assert_eq!(app.entries.len(), 0);
+    }
+
+    #[test]
+    fn test_invalid_filter_keeps_previous_query() {
+        let mut app = App::new();
+        app.update_entries(create_entries(3));
+        app.enter_filter_mode();
+
+        for c in "port:3001 cpu>".chars() {
+            app.filter_push(c);
+        }
+        let err = app.filter_error.clone().unwrap();
+        assert_eq!(err.message, "Expected a value after 'cpu>'");
+        assert_eq!(err.column, 15);
+        // The last valid query ("port:3001 cpu") is still applied
+        assert_eq!(app.entries.len(), 0);
+
+        // Enter doesn't leave filter mode while the query is invalid
+        app.exit_filter_mode();
+        assert!(app.filter_mode);
+        assert!(matches!(app.status_message, StatusMessage::Error(_)));
+
+        app.filter_push('5');
+        app.exit_filter_mode();
+        assert!(!app.filter_mode);
+        assert!(app.filter_error.is_none());
+    }
+
+    #[test]
+    fn test_apply_config_defaults() {
+        let config = Config::parse(
+            r#"
+            [defaults]
+            scan_interval = 10
+            status_duration = 2
+            sort = "cpu"
+            order = "desc"
+            filter = "proto:tcp"
+            "#,
+        )
+        .unwrap();
+
+        let mut app = App::new();
+        app.apply_config(config);
+        assert_eq!(app.sort_column, SortColumn::CpuUsage);
+        assert_eq!(app.sort_order, SortOrder::Descending);
+        assert_eq!(app.filter, "proto:tcp");
+        assert_eq!(app.status_duration, Duration::from_secs(2));
+        assert_eq!(app.scan_interval, Duration::from_secs(10));
+    }
+
+    fn save_view_as(app: &mut App, name: &str) {
+        app.enter_view_name_mode();
+        app.view_name_input = name.to_string();
+        app.save_view_from_input();
+    }
+
+    #[test]
+    fn test_save_and_apply_view() {
+        let mut app = App::new();
+        app.update_entries(create_entries(5));
+
+        app.filter = "port:3001-3002".into();
+        app.sort_by_column(SortColumn::Port);
+        save_view_as(&mut app, "pair");
+        assert!(!app.view_name_mode);
+        assert_eq!(app.active_view.as_deref(), Some("pair"));
+        assert!(!app.active_view_modified());
+
+        app.clear_filter();
+        assert!(app.active_view_modified());
+        assert_eq!(app.entries.len(), 5);
+
+        let view = app.views.get("pair").cloned().unwrap();
+        app.apply_view(&view);
+        assert_eq!(app.filter, "port:3001-3002");
+        assert_eq!(app.sort_order, SortOrder::Descending);
+        let ports: Vec<u16> = app.entries.iter().map(|e| e.port).collect();
+        assert_eq!(ports, vec![3002, 3001]);
+    }
+
+    #[test]
+    fn test_save_view_requires_name() {
+        let mut app = App::new();
+        save_view_as(&mut app, "  ");
+        assert!(app.view_name_mode);
+        assert!(app.views.is_empty());
+    }
+
+    #[test]
+    fn test_cycle_views() {
+        let mut app = App::new();
+        app.update_entries(create_entries(5));
+        app.cycle_view();
+        assert!(app.active_view.is_none());
+
+        app.filter = "port:3000".into();
+        save_view_as(&mut app, "one");
+        app.filter = "port:3001,3002".into();
+        save_view_as(&mut app, "two");
+
+        app.cycle_view();
+        assert_eq!(app.active_view.as_deref(), Some("one"));
+        assert_eq!(app.entries.len(), 1);
+        app.cycle_view();
+        assert_eq!(app.active_view.as_deref(), Some("two"));
+        assert_eq!(app.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_view_picker() {
+        let mut app = App::new();
+        app.open_view_picker();
+        assert!(!app.show_view_picker);
+
+        save_view_as(&mut app, "one");
+        save_view_as(&mut app, "two");
+        app.open_view_picker();
+        assert!(app.show_view_picker);
+        assert_eq!(app.view_picker_index, 1);
+
+        app.move_view_picker(1);
+        assert_eq!(app.view_picker_index, 0);
+        app.move_view_picker(-1);
+        assert_eq!(app.view_picker_index, 1);
+
+        app.delete_picked_view();
+        assert!(app.views.get("two").is_none());
+        assert!(app.active_view.is_none());
+        assert_eq!(app.view_picker_index, 0);
+
+        app.pick_view();
+        assert!(!app.show_view_picker);
+        assert_eq!(app.active_view.as_deref(), Some("one"));
     }
 }
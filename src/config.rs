@@ -0,0 +1,500 @@
+//! User configuration
+//!
+//! Loaded from `$XDG_CONFIG_HOME/lsport/config.toml` (falling back to
+//! `~/.config/lsport/config.toml`). A missing file means "use the defaults".
+//!
+//! ```toml
+//! [defaults]
+//! scan_interval = 5
+//! sort = "cpu"
+//! order = "desc"
+//! host = "deploy@prod"
+//!
+//! theme = "light"
+//!
+//! [hosts."deploy@prod"]
+//! identity = "~/.ssh/prod_ed25519"
+//! scan_interval = 10
+//!
+//! [[rules]]
+//! name = "hog"
+//! when = "cpu > 80% for 30s"
+//! severity = "critical"
+//!
+//! [notify]
+//! bell = true
+//! osc = "777"
+//! command = "notify-send lsport \"$LSPORT_MESSAGE\""
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{SortColumn, SortOrder};
+use crate::keymap::{KeyMap, KeysConfig};
+use crate::notify::NotifyConfig;
+use crate::query::Query;
+use crate::remote::RemoteConfig;
+use crate::rules::{self, Rule, RuleEngine, Severity};
+use crate::theme::{self, Theme, ThemeConfig};
+
+/// Default scan interval for refreshing port data (2 seconds)
+pub const DEFAULT_SCAN_INTERVAL: u64 = 2;
+
+/// Default time a status message stays in the footer (5 seconds)
+pub const DEFAULT_STATUS_DURATION: u64 = 5;
+
+/// Top-level configuration file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Startup values for command-line flags and TUI state
+    pub defaults: Defaults,
+    /// Options for individual remote hosts, keyed by host as passed to `-H`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub hosts: BTreeMap<String, HostConfig>,
+    /// Health rules, checked along with every built-in rule they don't override by name
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<RuleConfig>,
+    /// How to notify about pinned ports
+    pub notify: NotifyConfig,
+    /// Keybinding overrides
+    pub keys: KeysConfig,
+    /// User-defined color themes, selected with `defaults.theme`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub themes: BTreeMap<String, ThemeConfig>,
+}
+
+/// The `[defaults]` table (command-line flags take precedence)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Defaults {
+    /// Seconds between scans (`--scan-interval`)
+    pub scan_interval: u64,
+    /// Seconds a status message stays in the footer
+    pub status_duration: u64,
+    /// Remote host to monitor when `-H` isn't given
+    pub host: Option<String>,
+    /// SSH private key when `-i` isn't given
+    pub identity: Option<PathBuf>,
+    /// Initial sort column
+    pub sort: SortColumn,
+    /// Initial sort order
+    pub order: SortOrder,
+    /// Initial filter query (`--filter`)
+    pub filter: String,
+    /// Color theme (`--theme`)
+    pub theme: String,
+}
+
+impl Default for Defaults {
+    fn default() -> Self {
+        Self {
+            scan_interval: DEFAULT_SCAN_INTERVAL,
+            status_duration: DEFAULT_STATUS_DURATION,
+            host: None,
+            identity: None,
+            sort: SortColumn::default(),
+            order: SortOrder::default(),
+            filter: String::new(),
+            theme: "dark".to_string(),
+        }
+    }
+}
+
+/// A `[hosts."user@host"]` table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HostConfig {
+    /// SSH private key for this host
+    pub identity: Option<PathBuf>,
+    /// Seconds between scans of this host
+    pub scan_interval: Option<u64>,
+}
+
+/// A `[[rules]]` table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConfig {
+    /// Name shown in badges and `lsport health`
+    pub name: String,
+    /// Condition, e.g. "mem > 2GB" or "cpu > 80% for 30s"
+    pub when: String,
+    /// info, warning or critical (default: warning)
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+impl Config {
+    /// Parse configuration from TOML text
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Load the config file, returning defaults if it doesn't exist
+    pub fn load() -> Result<Self> {
+        match config_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load a config file, returning defaults if it doesn't exist
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Check everything that is only parsed when used (rules, keys, filter, hosts)
+    pub fn validate(&self) -> Result<()> {
+        self.rule_engine()?;
+        self.keymap()?;
+        self.theme(&self.defaults.theme)
+            .context("Invalid defaults.theme")?;
+        if self.defaults.scan_interval == 0 {
+            bail!("defaults.scan_interval must be at least 1 second");
+        }
+        Query::parse(&self.defaults.filter).context("Invalid defaults.filter")?;
+        if let Some(host) = &self.defaults.host {
+            RemoteConfig::parse(host).context("Invalid defaults.host")?;
+        }
+        for (host, options) in &self.hosts {
+            RemoteConfig::parse(host).with_context(|| format!("Invalid host \"{}\"", host))?;
+            if options.scan_interval == Some(0) {
+                bail!("hosts.\"{}\".scan_interval must be at least 1 second", host);
+            }
+        }
+        Ok(())
+    }
+
+    /// Options for a host, matched by the `-H` string or by user@host:port
+    pub fn host(&self, host: &str) -> Option<&HostConfig> {
+        if let Some(options) = self.hosts.get(host) {
+            return Some(options);
+        }
+        let target = RemoteConfig::parse(host).ok()?.display();
+        self.hosts.iter().find_map(|(key, options)| {
+            RemoteConfig::parse(key)
+                .is_ok_and(|config| config.display() == target)
+                .then_some(options)
+        })
+    }
+
+    /// SSH key for a host: its `[hosts]` entry, then `defaults.identity`
+    pub fn identity_for(&self, host: &str) -> Option<PathBuf> {
+        self.host(host)
+            .and_then(|options| options.identity.as_ref())
+            .or(self.defaults.identity.as_ref())
+            .map(|path| expand_home(path))
+    }
+
+    /// Scan interval in seconds for a host (None for localhost)
+    pub fn scan_interval_for(&self, host: Option<&str>) -> u64 {
+        host.and_then(|host| self.host(host))
+            .and_then(|options| options.scan_interval)
+            .unwrap_or(self.defaults.scan_interval)
+    }
+
+    /// The configuration as TOML, with every default filled in
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Build the keymap (defaults with the `[keys]` overrides applied)
+    pub fn keymap(&self) -> Result<KeyMap> {
+        KeyMap::new(&self.keys).context("Invalid [keys]")
+    }
+
+    /// Look up a built-in or `[themes]` theme by name
+    pub fn theme(&self, name: &str) -> Result<Theme> {
+        theme::resolve(name, &self.themes)
+    }
+
+    /// Build the health rules engine: the configured rules plus the built-in `runaway`
+    /// rule, unless a configured rule of the same name overrides it
+    pub fn rule_engine(&self) -> Result<RuleEngine> {
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| {
+                Rule::parse(&rule.name, &rule.when, rule.severity)
+                    .with_context(|| format!("Invalid rule '{}': \"{}\"", rule.name, rule.when))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(RuleEngine::new(rules::with_default_rules(rules)))
+    }
+}
+
+/// Path of the config file (`$XDG_CONFIG_HOME/lsport/config.toml`)
+pub fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs_next::home_dir().map(|home| home.join(".config")))?;
+    Some(base.join("lsport").join("config.toml"))
+}
+
+/// Expand a leading `~/` to the home directory
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs_next::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Load the config and build the rules engine in one step
+pub fn load_rule_engine() -> Result<RuleEngine> {
+    Config::load()?.rule_engine()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::OscStyle;
+
+    #[test]
+    fn test_parse_rules() {
+        let config = Config::parse(
+            r#"
+            [[rules]]
+            name = "hog"
+            when = "cpu > 80% for 30s"
+            severity = "critical"
+
+            [[rules]]
+            name = "big"
+            when = "mem > 2GB"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.rules.len(), 2);
+        assert_eq!(config.rules[0].severity, Severity::Critical);
+        assert_eq!(config.rules[1].severity, Severity::Warning);
+
+        let engine = config.rule_engine().unwrap();
+        // The built-in runaway rule still applies
+        assert_eq!(engine.rules().len(), 3);
+        assert_eq!(engine.rules()[0].name, "hog");
+        assert_eq!(engine.rules()[2].name, rules::RUNAWAY_RULE);
+    }
+
+    #[test]
+    fn test_empty_config_uses_default_rules() {
+        let config = Config::parse("").unwrap();
+        let engine = config.rule_engine().unwrap();
+        let defaults = rules::default_rules();
+        assert_eq!(engine.rules().len(), defaults.len());
+        assert_eq!(engine.rules()[0].name, defaults[0].name);
+    }
+
+    #[test]
+    fn test_invalid_rule_reports_name() {
+        let config = Config::parse(
+            r#"
+            [[rules]]
+            name = "broken"
+            when = "cpu >"
+            "#,
+        )
+        .unwrap();
+
+        let err = config.rule_engine().unwrap_err();
+        assert!(format!("{:#}", err).contains("broken"));
+    }
+
+    #[test]
+    fn test_parse_notify() {
+        let config = Config::parse(
+            r#"
+            [notify]
+            bell = false
+            osc = "9"
+            command = "say port changed"
+            "#,
+        )
+        .unwrap();
+
+        assert!(!config.notify.bell);
+        assert_eq!(config.notify.osc, Some(OscStyle::Osc9));
+        assert_eq!(config.notify.command.as_deref(), Some("say port changed"));
+
+        // Only the bell is on by default
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.notify, NotifyConfig::default());
+        assert!(config.notify.bell);
+
+        assert!(Config::parse("[notify]\nosc = \"42\"").is_err());
+    }
+
+    #[test]
+    fn test_unknown_keys_rejected() {
+        assert!(Config::parse("colour = \"blue\"").is_err());
+        assert!(Config::parse("[[rules]]\nname = \"x\"\nwhen = \"cpu > 1\"\nlevel = 3").is_err());
+        assert!(
+            Config::parse("[[rules]]\nname = \"x\"\nwhen = \"cpu > 1\"\nseverity = \"loud\"")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_defaults_and_hosts() {
+        let config = Config::parse(
+            r#"
+            [defaults]
+            scan_interval = 5
+            identity = "/keys/default"
+            sort = "memory"
+
+            [hosts."deploy@prod"]
+            identity = "/keys/prod"
+            scan_interval = 30
+
+            [hosts.staging]
+            scan_interval = 10
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(config.defaults.sort, SortColumn::MemoryUsage);
+        assert_eq!(config.defaults.status_duration, DEFAULT_STATUS_DURATION);
+
+        assert_eq!(
+            config.identity_for("deploy@prod"),
+            Some(PathBuf::from("/keys/prod"))
+        );
+        // Matched by user@host:port as well as the exact key
+        assert_eq!(
+            config.identity_for("deploy@prod:22"),
+            Some(PathBuf::from("/keys/prod"))
+        );
+        assert_eq!(
+            config.identity_for("staging"),
+            Some(PathBuf::from("/keys/default"))
+        );
+
+        assert_eq!(config.scan_interval_for(None), 5);
+        assert_eq!(config.scan_interval_for(Some("deploy@prod")), 30);
+        assert_eq!(config.scan_interval_for(Some("elsewhere")), 5);
+    }
+
+    #[test]
+    fn test_validate() {
+        let config = Config::parse("[defaults]\nscan_interval = 0").unwrap();
+        assert!(config.validate().is_err());
+
+        let config = Config::parse("[defaults]\nfilter = \"cpu>\"").unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{:#}", err).contains("defaults.filter"));
+
+        let config = Config::parse("[hosts.\"h\"]\nscan_interval = 0").unwrap();
+        assert!(config.validate().is_err());
+
+        assert!(Config::parse("[defaults]\ncolour = \"dark\"").is_err());
+
+        let config = Config::parse("[defaults]\ntheme = \"neon\"").unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{:#}", err).contains("Unknown theme 'neon'"));
+    }
+
+    #[test]
+    fn test_parse_themes() {
+        let config = Config::parse(
+            r##"
+            [defaults]
+            theme = "mine"
+
+            [themes.mine]
+            base = "high-contrast"
+            accent = "#00ff00"
+            "##,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let theme = config.theme(&config.defaults.theme).unwrap();
+        assert_eq!(theme.accent, ratatui::style::Color::Rgb(0, 255, 0));
+        assert_eq!(theme.bg, Theme::high_contrast().bg);
+        assert_eq!(Config::default().theme("light").unwrap(), Theme::light());
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        use crate::keymap::{Action, KeyContext};
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let config = Config::parse(
+            r#"
+            [keys]
+            kill = ["ctrl+k", "delete"]
+            connect = "C"
+
+            [keys.prompt]
+            cancel = ["esc", "ctrl+g"]
+            "#,
+        )
+        .unwrap();
+
+        let keymap = config.keymap().unwrap();
+        assert_eq!(
+            keymap.action(KeyContext::Table, KeyCode::Delete, KeyModifiers::NONE),
+            Some(Action::Kill)
+        );
+        assert_eq!(
+            keymap.action(KeyContext::Table, KeyCode::Char('C'), KeyModifiers::SHIFT),
+            Some(Action::Connect)
+        );
+        assert_eq!(
+            keymap.action(
+                KeyContext::Prompt,
+                KeyCode::Char('g'),
+                KeyModifiers::CONTROL
+            ),
+            Some(Action::Cancel)
+        );
+
+        let text = config.to_toml().unwrap();
+        assert_eq!(Config::parse(&text).unwrap().keys, config.keys);
+
+        let config = Config::parse("[keys]\nkill = \"hyper+k\"").unwrap();
+        assert!(format!("{:#}", config.validate().unwrap_err()).contains("hyper+k"));
+    }
+
+    #[test]
+    fn test_to_toml_round_trips() {
+        let config = Config::parse(
+            r#"
+            [defaults]
+            order = "desc"
+
+            [[rules]]
+            name = "big"
+            when = "mem > 2GB"
+            "#,
+        )
+        .unwrap();
+
+        let text = config.to_toml().unwrap();
+        let reparsed = Config::parse(&text).unwrap();
+        assert_eq!(reparsed.defaults, config.defaults);
+        assert_eq!(reparsed.rules[0].when, "mem > 2GB");
+        assert_eq!(reparsed.notify, config.notify);
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = dirs_next::home_dir().unwrap();
+        assert_eq!(expand_home(Path::new("~/.ssh/id")), home.join(".ssh/id"));
+        assert_eq!(expand_home(Path::new("/abs/id")), PathBuf::from("/abs/id"));
+    }
+}
@@ -0,0 +1,414 @@
+//! Port open/close events
+//!
+//! Consecutive scans are diffed to produce events such as "8080/TCP opened by
+//! node (4123)" or "5432/TCP closed", so a crash-looping service shows up as a
+//! history instead of a flicker in the table.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::app::{format_unix_time, PortEntry, Protocol};
+
+/// Number of events kept in memory for the log pane
+pub const EVENT_LOG_CAPACITY: usize = 500;
+
+/// What happened to a port between two scans
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum PortEventKind {
+    /// A process started listening on the port
+    Opened,
+    /// The process stopped listening on the port
+    Closed,
+    /// A different process now owns the port
+    OwnerChanged {
+        old_pid: u32,
+        #[serde(rename = "old_process")]
+        old_name: String,
+    },
+}
+
+/// A single change to a listening port
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PortEvent {
+    /// When the change was observed (seconds since the Unix epoch)
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub kind: PortEventKind,
+    pub port: u16,
+    pub protocol: Protocol,
+    /// Owning process (the new owner for `OwnerChanged`, the old one for `Closed`)
+    pub pid: u32,
+    #[serde(rename = "process")]
+    pub process_name: String,
+}
+
+impl PortEvent {
+    /// Single-line JSON object, as printed by `lsport watch`
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("port events always serialize")
+    }
+}
+
+impl fmt::Display for PortEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} ", self.port, self.protocol)?;
+        match &self.kind {
+            PortEventKind::Opened => {
+                write!(f, "opened by {} ({})", self.process_name, self.pid)
+            }
+            PortEventKind::Closed => {
+                write!(f, "closed (was {} ({}))", self.process_name, self.pid)
+            }
+            PortEventKind::OwnerChanged { old_pid, old_name } => write!(
+                f,
+                "owner changed from {} ({}) to {} ({})",
+                old_name, old_pid, self.process_name, self.pid
+            ),
+        }
+    }
+}
+
+/// Processes owning one (port, protocol), keyed by PID
+type PortOwners<'a> = BTreeMap<u32, (Protocol, &'a str)>;
+
+/// Owners of each (port, protocol) in a scan, deduplicated by PID
+fn owners(entries: &[PortEntry]) -> BTreeMap<(u16, u8), PortOwners<'_>> {
+    let mut map: BTreeMap<(u16, u8), PortOwners> = BTreeMap::new();
+    for entry in entries {
+        // IPv4 and IPv6 listeners of the same process collapse into one owner
+        map.entry((entry.port, protocol_key(entry.protocol)))
+            .or_default()
+            .entry(entry.pid)
+            .or_insert((entry.protocol, entry.process_name.as_str()));
+    }
+    map
+}
+
+/// Stable ordering key so events come out sorted by port, TCP before UDP
+fn protocol_key(protocol: Protocol) -> u8 {
+    match protocol {
+        Protocol::Tcp => 0,
+        Protocol::Udp => 1,
+    }
+}
+
+/// Compare two scans and describe what changed, keyed by (port, protocol, pid)
+pub fn diff_entries(old: &[PortEntry], new: &[PortEntry], timestamp: u64) -> Vec<PortEvent> {
+    let old_owners = owners(old);
+    let new_owners = owners(new);
+    let keys: BTreeSet<_> = old_owners.keys().chain(new_owners.keys()).collect();
+
+    let empty = BTreeMap::new();
+    let mut events = Vec::new();
+
+    for key in keys {
+        let before = old_owners.get(key).unwrap_or(&empty);
+        let after = new_owners.get(key).unwrap_or(&empty);
+        let event = |pid: u32, (protocol, name): (Protocol, &str), kind| PortEvent {
+            timestamp,
+            port: key.0,
+            protocol,
+            kind,
+            pid,
+            process_name: name.to_string(),
+        };
+
+        let gone: Vec<_> = before
+            .iter()
+            .filter(|(pid, _)| !after.contains_key(pid))
+            .collect();
+        let came: Vec<_> = after
+            .iter()
+            .filter(|(pid, _)| !before.contains_key(pid))
+            .collect();
+
+        // A single owner replaced by another single owner is a hand-over, not close + open
+        if let ([(&old_pid, &(_, old_name))], [(&pid, &owner)]) = (gone.as_slice(), came.as_slice())
+        {
+            events.push(event(
+                pid,
+                owner,
+                PortEventKind::OwnerChanged {
+                    old_pid,
+                    old_name: old_name.to_string(),
+                },
+            ));
+            continue;
+        }
+
+        for (&pid, &owner) in gone {
+            events.push(event(pid, owner, PortEventKind::Closed));
+        }
+        for (&pid, &owner) in came {
+            events.push(event(pid, owner, PortEventKind::Opened));
+        }
+    }
+
+    events
+}
+
+/// Bounded in-memory event history, optionally mirrored to a file
+#[derive(Debug)]
+pub struct EventLog {
+    events: VecDeque<PortEvent>,
+    capacity: usize,
+    file: Option<File>,
+    /// Host written before each event in the file, when several hosts share it
+    host: Option<String>,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(EVENT_LOG_CAPACITY)
+    }
+}
+
+impl EventLog {
+    /// Create an empty log keeping at most `capacity` events in memory
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity: capacity.max(1),
+            file: None,
+            host: None,
+        }
+    }
+
+    /// Append every future event to `path` (one line per event)
+    pub fn write_to(&mut self, path: &Path) -> Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open event log {}", path.display()))?;
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Prefix events written to the file with the host they happened on
+    pub fn label_host(&mut self, host: impl Into<String>) {
+        self.host = Some(host.into());
+    }
+
+    /// Record new events; file write errors stop mirroring rather than failing the scan
+    pub fn extend(&mut self, events: Vec<PortEvent>) {
+        for event in events {
+            if let Some(file) = &mut self.file {
+                let host = self.host.as_deref().map(|h| format!("{}  ", h));
+                let line = format!(
+                    "{}  {}{}",
+                    format_unix_time(event.timestamp),
+                    host.unwrap_or_default(),
+                    event
+                );
+                if writeln!(file, "{}", line).is_err() {
+                    self.file = None;
+                }
+            }
+            if self.events.len() == self.capacity {
+                self.events.pop_front();
+            }
+            self.events.push_back(event);
+        }
+    }
+
+    /// Events from newest to oldest
+    pub fn newest_first(&self) -> impl Iterator<Item = &PortEvent> {
+        self.events.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// Format seconds since the Unix epoch as "HH:MM:SS" (UTC)
+pub fn format_clock(secs: u64) -> String {
+    let time_of_day = secs % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        time_of_day / 3_600,
+        (time_of_day % 3_600) / 60,
+        time_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(port: u16, protocol: Protocol, pid: u32, name: &str) -> PortEntry {
+        PortEntry {
+            protocol,
+            ..PortEntry::test(port, pid, name)
+        }
+    }
+
+    #[test]
+    fn test_diff_opened_and_closed() {
+        let old = vec![entry(5432, Protocol::Tcp, 812, "postgres")];
+        let new = vec![entry(8080, Protocol::Tcp, 4123, "node")];
+
+        let events = diff_entries(&old, &new, 100);
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0].to_string(),
+            "5432/TCP closed (was postgres (812))"
+        );
+        assert_eq!(events[1].to_string(), "8080/TCP opened by node (4123)");
+        assert_eq!(events[1].timestamp, 100);
+    }
+
+    #[test]
+    fn test_diff_owner_changed() {
+        let old = vec![entry(3000, Protocol::Tcp, 41, "node")];
+        let new = vec![entry(3000, Protocol::Tcp, 99, "node")];
+
+        let events = diff_entries(&old, &new, 0);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].kind,
+            PortEventKind::OwnerChanged {
+                old_pid: 41,
+                old_name: "node".into()
+            }
+        );
+        assert_eq!(events[0].pid, 99);
+    }
+
+    #[test]
+    fn test_diff_unchanged_and_duplicates() {
+        // Same process on IPv4 and IPv6 counts once, and nothing changed
+        let old = vec![
+            entry(80, Protocol::Tcp, 1, "nginx"),
+            entry(80, Protocol::Tcp, 1, "nginx"),
+        ];
+        let new = vec![entry(80, Protocol::Tcp, 1, "nginx")];
+        assert!(diff_entries(&old, &new, 0).is_empty());
+    }
+
+    #[test]
+    fn test_diff_protocols_are_separate() {
+        let old = vec![entry(53, Protocol::Udp, 7, "dnsmasq")];
+        let new = vec![
+            entry(53, Protocol::Udp, 7, "dnsmasq"),
+            entry(53, Protocol::Tcp, 7, "dnsmasq"),
+        ];
+
+        let events = diff_entries(&old, &new, 0);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].protocol, Protocol::Tcp);
+        assert_eq!(events[0].kind, PortEventKind::Opened);
+    }
+
+    #[test]
+    fn test_diff_extra_owner_is_opened() {
+        // SO_REUSEPORT: a second worker joins instead of replacing the first
+        let old = vec![entry(8000, Protocol::Tcp, 10, "gunicorn")];
+        let new = vec![
+            entry(8000, Protocol::Tcp, 10, "gunicorn"),
+            entry(8000, Protocol::Tcp, 11, "gunicorn"),
+        ];
+
+        let events = diff_entries(&old, &new, 0);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, PortEventKind::Opened);
+        assert_eq!(events[0].pid, 11);
+    }
+
+    #[test]
+    fn test_event_json() {
+        let old = vec![entry(3000, Protocol::Tcp, 41, "node")];
+        let new = vec![
+            entry(3000, Protocol::Tcp, 99, "node"),
+            entry(53, Protocol::Udp, 7, "dnsmasq"),
+        ];
+
+        let events = diff_entries(&old, &new, 1_700_000_000);
+        let json: Vec<String> = events.iter().map(PortEvent::to_json).collect();
+        assert_eq!(
+            json,
+            vec![
+                r#"{"timestamp":1700000000,"event":"opened","port":53,"protocol":"udp","pid":7,"process":"dnsmasq"}"#,
+                r#"{"timestamp":1700000000,"event":"owner_changed","old_pid":41,"old_process":"node","port":3000,"protocol":"tcp","pid":99,"process":"node"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_log_is_bounded() {
+        let mut log = EventLog::new(2);
+        let events = diff_entries(
+            &[],
+            &[
+                entry(1, Protocol::Tcp, 1, "a"),
+                entry(2, Protocol::Tcp, 2, "b"),
+                entry(3, Protocol::Tcp, 3, "c"),
+            ],
+            0,
+        );
+        log.extend(events);
+
+        assert_eq!(log.len(), 2);
+        let ports: Vec<u16> = log.newest_first().map(|e| e.port).collect();
+        assert_eq!(ports, vec![3, 2]);
+    }
+
+    #[test]
+    fn test_event_log_writes_file() {
+        let path = std::env::temp_dir().join(format!("lsport-events-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut log = EventLog::default();
+        log.write_to(&path).unwrap();
+        log.extend(diff_entries(
+            &[],
+            &[entry(8080, Protocol::Tcp, 4123, "node")],
+            0,
+        ));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            contents,
+            "1970-01-01 00:00:00 UTC  8080/TCP opened by node (4123)\n"
+        );
+    }
+
+    #[test]
+    fn test_event_log_labels_host() {
+        let path =
+            std::env::temp_dir().join(format!("lsport-events-host-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut log = EventLog::default();
+        log.write_to(&path).unwrap();
+        log.label_host("deploy@web1");
+        log.extend(diff_entries(
+            &[entry(8080, Protocol::Tcp, 4123, "node")],
+            &[],
+            0,
+        ));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(contents.starts_with("1970-01-01 00:00:00 UTC  deploy@web1  8080/TCP closed"));
+    }
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock(0), "00:00:00");
+        assert_eq!(format_clock(86_400 + 3_661), "01:01:01");
+    }
+}
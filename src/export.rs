@@ -0,0 +1,206 @@
+//! Exporting the port table
+//!
+//! `:export json out.json` and `:export csv out.csv` write the rows currently
+//! shown in the table (after filtering and sorting) to a file, or only the
+//! marked rows when any are marked.
+
+use std::io::Write;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::app::PortEntry;
+
+/// File format for exported rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// A JSON array of objects
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+impl ExportFormat {
+    /// Names accepted by [`ExportFormat::from_str`]
+    pub const NAMES: &[&str] = &["json", "csv"];
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "csv" => Ok(ExportFormat::Csv),
+            _ => bail!("Unknown export format '{}' (expected json or csv)", s),
+        }
+    }
+}
+
+/// One exported row
+#[derive(Debug, Serialize)]
+struct ExportRow<'a> {
+    port: u16,
+    protocol: String,
+    pid: u32,
+    name: &'a str,
+    address: Option<&'a str>,
+    user: Option<&'a str>,
+    cpu: f32,
+    memory: u64,
+    uptime_secs: Option<u64>,
+    command: Option<&'a str>,
+    /// Only rows from the fleet view have a host
+    #[serde(skip_serializing_if = "Option::is_none")]
+    host: Option<&'a str>,
+}
+
+impl<'a> From<&'a PortEntry> for ExportRow<'a> {
+    fn from(entry: &'a PortEntry) -> Self {
+        Self {
+            port: entry.port,
+            protocol: entry.protocol.to_string(),
+            pid: entry.pid,
+            name: &entry.process_name,
+            address: entry.address.as_deref(),
+            user: entry.user.as_deref(),
+            cpu: entry.cpu_usage,
+            memory: entry.memory_usage,
+            uptime_secs: entry.uptime_secs(),
+            command: entry.command.as_deref(),
+            host: entry.host.as_deref(),
+        }
+    }
+}
+
+/// Column names of the CSV header, in the order of [`ExportRow`]'s fields
+/// (followed by `host` when exporting from the fleet view)
+const CSV_HEADER: &str = "port,protocol,pid,name,address,user,cpu,memory,uptime_secs,command";
+
+/// Write entries in the given format
+pub fn write_entries(
+    entries: &[PortEntry],
+    format: ExportFormat,
+    out: &mut impl Write,
+) -> Result<()> {
+    let rows: Vec<ExportRow> = entries.iter().map(ExportRow::from).collect();
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, &rows)?;
+            writeln!(out)?;
+        }
+        ExportFormat::Csv => {
+            let with_host = rows.iter().any(|row| row.host.is_some());
+            if with_host {
+                writeln!(out, "{},host", CSV_HEADER)?;
+            } else {
+                writeln!(out, "{}", CSV_HEADER)?;
+            }
+            for row in rows {
+                let mut fields = vec![
+                    row.port.to_string(),
+                    row.protocol,
+                    row.pid.to_string(),
+                    csv_field(row.name),
+                    csv_field(row.address.unwrap_or_default()),
+                    csv_field(row.user.unwrap_or_default()),
+                    format!("{:.1}", row.cpu),
+                    row.memory.to_string(),
+                    row.uptime_secs.map(|s| s.to_string()).unwrap_or_default(),
+                    csv_field(row.command.unwrap_or_default()),
+                ];
+                if with_host {
+                    fields.push(csv_field(row.host.unwrap_or_default()));
+                }
+                writeln!(out, "{}", fields.join(","))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::ProcessState;
+
+    fn entry(port: u16, command: &str) -> PortEntry {
+        PortEntry {
+            cpu_usage: 12.34,
+            memory_usage: 2048,
+            memory_display: "2.0 KB".to_string(),
+            address: Some("127.0.0.1".to_string()),
+            state: ProcessState::Running,
+            command: Some(command.to_string()),
+            ..PortEntry::test(port, 42, "node")
+        }
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("json".parse::<ExportFormat>().unwrap(), ExportFormat::Json);
+        assert_eq!("CSV".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+        assert!("xml".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_write_json() {
+        let mut out = Vec::new();
+        write_entries(
+            &[entry(3000, "node server.js")],
+            ExportFormat::Json,
+            &mut out,
+        )
+        .unwrap();
+
+        let rows: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(rows[0]["port"], 3000);
+        assert_eq!(rows[0]["protocol"], "TCP");
+        assert_eq!(rows[0]["address"], "127.0.0.1");
+        assert_eq!(rows[0]["user"], serde_json::Value::Null);
+        assert_eq!(rows[0]["command"], "node server.js");
+        assert!(rows[0].get("host").is_none());
+    }
+
+    #[test]
+    fn test_write_csv_with_host() {
+        let mut out = Vec::new();
+        let mut fleet_entry = entry(3000, "node server.js");
+        fleet_entry.host = Some("web1".to_string());
+        write_entries(&[fleet_entry], ExportFormat::Csv, &mut out).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], format!("{},host", CSV_HEADER));
+        assert!(lines[1].ends_with(",node server.js,web1"));
+    }
+
+    #[test]
+    fn test_write_csv_quotes_fields() {
+        let mut out = Vec::new();
+        let entries = [entry(3000, "node server.js"), entry(3001, "sh -c \"a, b\"")];
+        write_entries(&entries, ExportFormat::Csv, &mut out).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "3000,TCP,42,node,127.0.0.1,,12.3,2048,,node server.js"
+        );
+        assert_eq!(
+            lines[2],
+            "3001,TCP,42,node,127.0.0.1,,12.3,2048,,\"sh -c \"\"a, b\"\"\""
+        );
+    }
+}
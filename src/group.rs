@@ -0,0 +1,569 @@
+//! Grouped table view
+//!
+//! A single process like `nginx` or `docker-proxy` can own dozens of ports.
+//! Grouping collapses the table into one row per PID (or per process name)
+//! with the group's ports and summed CPU and memory; groups expand to show
+//! their entries. Groups are sorted by the same column as the flat table,
+//! using the aggregate value (lowest port and PID, total CPU and memory,
+//! longest uptime).
+//!
+//! The tree grouping nests listeners under their ancestor processes instead
+//! (`tmux > zsh > npm > node :3000`), showing which terminal or supervisor
+//! started a server. Tree nodes start expanded.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+
+use crate::app::{PortEntry, Protocol, SortColumn, SortOrder};
+
+/// What the grouped view collapses entries by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Pid,
+    Name,
+    /// Nest entries under their ancestor processes
+    Tree,
+}
+
+impl GroupBy {
+    /// Names accepted by [`GroupBy::parse`]
+    pub const NAMES: &[&str] = &["pid", "name", "tree", "off"];
+
+    /// Parse "pid", "name", "tree" or "off" (no grouping)
+    pub fn parse(text: &str) -> Result<Option<Self>> {
+        match text.to_ascii_lowercase().as_str() {
+            "pid" => Ok(Some(GroupBy::Pid)),
+            "name" => Ok(Some(GroupBy::Name)),
+            "tree" => Ok(Some(GroupBy::Tree)),
+            "off" | "none" => Ok(None),
+            _ => bail!(
+                "Unknown grouping '{}' (expected pid, name, tree or off)",
+                text
+            ),
+        }
+    }
+
+    /// Next grouping when cycling: off → PID → name → tree → off
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(GroupBy::Pid),
+            Some(GroupBy::Pid) => Some(GroupBy::Name),
+            Some(GroupBy::Name) => Some(GroupBy::Tree),
+            Some(GroupBy::Tree) => None,
+        }
+    }
+
+    /// Label shown in the context bar
+    pub fn label(self) -> &'static str {
+        match self {
+            GroupBy::Pid => "PID",
+            GroupBy::Name => "Name",
+            GroupBy::Tree => "Tree",
+        }
+    }
+}
+
+/// What the entries of a group have in common (remembers which groups are expanded)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GroupKey {
+    Pid(Option<String>, u32),
+    Name(String),
+    /// A process in the tree on a host, with the listeners below it
+    Process(Option<String>, u32),
+}
+
+impl GroupKey {
+    /// Tree nodes start expanded so the ancestry is visible; groups start collapsed
+    fn expanded_by_default(&self) -> bool {
+        matches!(self, GroupKey::Process(..))
+    }
+}
+
+/// Whether a group shows its children (`toggled` holds the groups the user
+/// expanded or collapsed)
+pub fn is_expanded(key: &GroupKey, toggled: &HashSet<GroupKey>) -> bool {
+    toggled.contains(key) != key.expanded_by_default()
+}
+
+/// Entries collapsed into one row of the grouped view
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub key: GroupKey,
+    pub name: String,
+    /// Rows shown below the header when expanded, in order
+    pub children: Vec<TableRow>,
+    /// Indices into the table's entries of every entry below the group
+    pub entries: Vec<usize>,
+    /// Distinct PIDs, in table order
+    pub pids: Vec<u32>,
+    /// Distinct protocols, in table order
+    pub protocols: Vec<Protocol>,
+    pub ports: Vec<u16>,
+    /// Summed over the distinct processes
+    pub cpu_usage: f32,
+    /// Summed over the distinct processes
+    pub memory_usage: u64,
+    /// Uptime of the longest-running process
+    pub uptime_secs: Option<u64>,
+    /// Whether any of the group's ports is pinned
+    pub pinned: bool,
+}
+
+impl Group {
+    fn new(key: GroupKey, name: String) -> Self {
+        Self {
+            key,
+            name,
+            children: Vec::new(),
+            entries: Vec::new(),
+            pids: Vec::new(),
+            protocols: Vec::new(),
+            ports: Vec::new(),
+            cpu_usage: 0.0,
+            memory_usage: 0,
+            uptime_secs: None,
+            pinned: false,
+        }
+    }
+
+    fn push(&mut self, index: usize, entry: &PortEntry, is_pinned: &dyn Fn(&PortEntry) -> bool) {
+        self.entries.push(index);
+        self.ports.push(entry.port);
+        self.pinned |= is_pinned(entry);
+        if !self.protocols.contains(&entry.protocol) {
+            self.protocols.push(entry.protocol);
+        }
+        // A process with several ports only counts once
+        if !self.pids.contains(&entry.pid) {
+            self.pids.push(entry.pid);
+            self.cpu_usage += entry.cpu_usage;
+            self.memory_usage += entry.memory_usage;
+            self.uptime_secs = self.uptime_secs.max(entry.uptime_secs());
+        }
+    }
+
+    /// Protocols joined for display, e.g. "TCP+UDP"
+    pub fn protocol_label(&self) -> String {
+        self.protocols
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+
+    fn compare(&self, other: &Self, column: SortColumn) -> Ordering {
+        match column {
+            SortColumn::Port => self.ports.iter().min().cmp(&other.ports.iter().min()),
+            SortColumn::Protocol => self.protocol_label().cmp(&other.protocol_label()),
+            SortColumn::Pid => self.pids.iter().min().cmp(&other.pids.iter().min()),
+            SortColumn::ProcessName => self.name.to_lowercase().cmp(&other.name.to_lowercase()),
+            SortColumn::CpuUsage => self
+                .cpu_usage
+                .partial_cmp(&other.cpu_usage)
+                .unwrap_or(Ordering::Equal),
+            SortColumn::MemoryUsage => self.memory_usage.cmp(&other.memory_usage),
+            SortColumn::Uptime => self.uptime_secs.cmp(&other.uptime_secs),
+        }
+    }
+}
+
+/// A row of the grouped table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableRow {
+    /// A group header (index into the groups)
+    Group(usize),
+    /// A single entry (index into the entries)
+    Entry(usize),
+}
+
+/// Group already sorted entries. Returns the groups and the top-level rows,
+/// with groups sorted by `column` (pinned ones first, per `is_pinned`).
+pub fn group_entries(
+    entries: &[PortEntry],
+    by: GroupBy,
+    is_pinned: &dyn Fn(&PortEntry) -> bool,
+    column: SortColumn,
+    order: SortOrder,
+) -> (Vec<Group>, Vec<TableRow>) {
+    let compare = |a: &Group, b: &Group| {
+        b.pinned.cmp(&a.pinned).then_with(|| {
+            let cmp = a.compare(b, column);
+            match order {
+                SortOrder::Ascending => cmp,
+                SortOrder::Descending => cmp.reverse(),
+            }
+        })
+    };
+
+    match by {
+        GroupBy::Pid => flat_groups(entries, is_pinned, compare, |e| {
+            GroupKey::Pid(e.host.clone(), e.pid)
+        }),
+        GroupBy::Name => flat_groups(entries, is_pinned, compare, |e| {
+            GroupKey::Name(e.process_name.clone())
+        }),
+        GroupBy::Tree => {
+            let (mut groups, mut top) = tree_groups(entries, is_pinned);
+            // Own ports first (in table order), then child processes
+            let sort = |rows: &mut Vec<TableRow>, groups: &[Group]| {
+                rows.sort_by(|a, b| match (a, b) {
+                    (TableRow::Entry(_), TableRow::Entry(_)) => Ordering::Equal,
+                    (TableRow::Entry(_), TableRow::Group(_)) => Ordering::Less,
+                    (TableRow::Group(_), TableRow::Entry(_)) => Ordering::Greater,
+                    (TableRow::Group(a), TableRow::Group(b)) => compare(&groups[*a], &groups[*b]),
+                })
+            };
+            for index in 0..groups.len() {
+                let mut children = std::mem::take(&mut groups[index].children);
+                sort(&mut children, &groups);
+                groups[index].children = children;
+            }
+            sort(&mut top, &groups);
+            (groups, top)
+        }
+    }
+}
+
+/// One group per key; groups of a single entry are shown as that entry
+fn flat_groups(
+    entries: &[PortEntry],
+    is_pinned: &dyn Fn(&PortEntry) -> bool,
+    compare: impl Fn(&Group, &Group) -> Ordering,
+    key: impl Fn(&PortEntry) -> GroupKey,
+) -> (Vec<Group>, Vec<TableRow>) {
+    let mut all: Vec<Group> = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let key = key(entry);
+        let group = match all.iter().position(|g| g.key == key) {
+            Some(position) => &mut all[position],
+            None => {
+                all.push(Group::new(key, entry.process_name.clone()));
+                all.last_mut().unwrap()
+            }
+        };
+        group.push(index, entry, is_pinned);
+        group.children.push(TableRow::Entry(index));
+    }
+    all.sort_by(&compare);
+
+    let mut groups = Vec::new();
+    let mut top = Vec::new();
+    for group in all {
+        if let [entry] = group.entries.as_slice() {
+            top.push(TableRow::Entry(*entry));
+        } else {
+            top.push(TableRow::Group(groups.len()));
+            groups.push(group);
+        }
+    }
+    (groups, top)
+}
+
+/// A node per ancestor process, nested by parent, with listeners below their
+/// parent's node. A listener that is itself an ancestor of other listeners
+/// gets a node too, listing its own ports first.
+fn tree_groups(
+    entries: &[PortEntry],
+    is_pinned: &dyn Fn(&PortEntry) -> bool,
+) -> (Vec<Group>, Vec<TableRow>) {
+    // PIDs repeat across hosts in the fleet view, so nodes are per host
+    let ancestor_pids: HashSet<(Option<&str>, u32)> = entries
+        .iter()
+        .flat_map(|e| e.ancestors.iter().map(|a| (e.host.as_deref(), a.pid)))
+        .collect();
+
+    let mut groups: Vec<Group> = Vec::new();
+    let mut top: Vec<TableRow> = Vec::new();
+    let mut nodes: HashMap<GroupKey, usize> = HashMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let own_node = ancestor_pids
+            .contains(&(entry.host.as_deref(), entry.pid))
+            .then_some((entry.pid, &entry.process_name));
+        let chain = entry
+            .ancestors
+            .iter()
+            .map(|a| (a.pid, &a.name))
+            .chain(own_node);
+
+        let mut parent: Option<usize> = None;
+        for (pid, name) in chain {
+            let key = GroupKey::Process(entry.host.clone(), pid);
+            let node = match nodes.get(&key) {
+                Some(&node) => node,
+                None => {
+                    let node = groups.len();
+                    groups.push(Group::new(key.clone(), name.clone()));
+                    nodes.insert(key, node);
+                    match parent {
+                        Some(parent) => groups[parent].children.push(TableRow::Group(node)),
+                        None => top.push(TableRow::Group(node)),
+                    }
+                    node
+                }
+            };
+            groups[node].push(index, entry, is_pinned);
+            parent = Some(node);
+        }
+        match parent {
+            Some(parent) => groups[parent].children.push(TableRow::Entry(index)),
+            None => top.push(TableRow::Entry(index)),
+        }
+    }
+    (groups, top)
+}
+
+/// Rows to show, with their depth: each group's children follow its header
+/// while it is expanded
+pub fn table_rows(
+    groups: &[Group],
+    top: &[TableRow],
+    toggled: &HashSet<GroupKey>,
+) -> Vec<(TableRow, usize)> {
+    fn walk(
+        row: TableRow,
+        depth: usize,
+        groups: &[Group],
+        toggled: &HashSet<GroupKey>,
+        rows: &mut Vec<(TableRow, usize)>,
+    ) {
+        rows.push((row, depth));
+        if let TableRow::Group(index) = row {
+            let group = &groups[index];
+            if is_expanded(&group.key, toggled) {
+                for &child in &group.children {
+                    walk(child, depth + 1, groups, toggled, rows);
+                }
+            }
+        }
+    }
+
+    let mut rows = Vec::new();
+    for &row in top {
+        walk(row, 0, groups, toggled, &mut rows);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{Ancestor, ProcessState};
+
+    fn entry(port: u16, pid: u32, name: &str, cpu: f32) -> PortEntry {
+        PortEntry {
+            cpu_usage: cpu,
+            memory_usage: 1024,
+            memory_display: "1.0 KB".to_string(),
+            state: ProcessState::Running,
+            ..PortEntry::test(port, pid, name)
+        }
+    }
+
+    fn entries() -> Vec<PortEntry> {
+        vec![
+            entry(80, 10, "nginx", 1.0),
+            entry(443, 10, "nginx", 1.0),
+            entry(3000, 20, "node", 30.0),
+            entry(8080, 11, "nginx", 2.0),
+        ]
+    }
+
+    #[test]
+    fn test_parse_and_cycle() {
+        assert_eq!(GroupBy::parse("PID").unwrap(), Some(GroupBy::Pid));
+        assert_eq!(GroupBy::parse("tree").unwrap(), Some(GroupBy::Tree));
+        assert_eq!(GroupBy::parse("off").unwrap(), None);
+        assert!(GroupBy::parse("user").is_err());
+
+        assert_eq!(GroupBy::cycle(None), Some(GroupBy::Pid));
+        assert_eq!(GroupBy::cycle(Some(GroupBy::Name)), Some(GroupBy::Tree));
+        assert_eq!(GroupBy::cycle(Some(GroupBy::Tree)), None);
+    }
+
+    #[test]
+    fn test_group_by_pid() {
+        let (groups, top) = group_entries(
+            &entries(),
+            GroupBy::Pid,
+            &|_| false,
+            SortColumn::Port,
+            SortOrder::Ascending,
+        );
+        // Processes with a single port aren't grouped
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            top,
+            vec![TableRow::Group(0), TableRow::Entry(2), TableRow::Entry(3)]
+        );
+        assert_eq!(groups[0].key, GroupKey::Pid(None, 10));
+        assert_eq!(groups[0].ports, vec![80, 443]);
+        assert_eq!(groups[0].entries, vec![0, 1]);
+        // The process is only counted once
+        assert_eq!(groups[0].cpu_usage, 1.0);
+        assert_eq!(groups[0].memory_usage, 1024);
+    }
+
+    #[test]
+    fn test_group_by_pid_keeps_hosts_apart() {
+        let mut entries = entries();
+        entries[1].host = Some("web1".to_string());
+        let (groups, _) = group_entries(
+            &entries,
+            GroupBy::Pid,
+            &|_| false,
+            SortColumn::Port,
+            SortOrder::Ascending,
+        );
+        // The same PID on another host is another process
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_group_by_name_sums_processes() {
+        let (groups, top) = group_entries(
+            &entries(),
+            GroupBy::Name,
+            &|_| false,
+            SortColumn::CpuUsage,
+            SortOrder::Descending,
+        );
+        assert_eq!(top, vec![TableRow::Entry(2), TableRow::Group(0)]);
+        assert_eq!(groups[0].name, "nginx");
+        assert_eq!(groups[0].pids, vec![10, 11]);
+        assert_eq!(groups[0].cpu_usage, 3.0);
+        assert_eq!(groups[0].memory_usage, 2048);
+        assert_eq!(groups[0].protocol_label(), "TCP");
+    }
+
+    #[test]
+    fn test_table_rows() {
+        let (groups, top) = group_entries(
+            &entries(),
+            GroupBy::Pid,
+            &|_| false,
+            SortColumn::Port,
+            SortOrder::Ascending,
+        );
+        let rows = |toggled: &HashSet<GroupKey>| -> Vec<TableRow> {
+            table_rows(&groups, &top, toggled)
+                .into_iter()
+                .map(|(row, _)| row)
+                .collect()
+        };
+        let mut toggled = HashSet::new();
+        assert_eq!(
+            rows(&toggled),
+            vec![TableRow::Group(0), TableRow::Entry(2), TableRow::Entry(3)]
+        );
+
+        toggled.insert(GroupKey::Pid(None, 10));
+        assert_eq!(
+            rows(&toggled),
+            vec![
+                TableRow::Group(0),
+                TableRow::Entry(0),
+                TableRow::Entry(1),
+                TableRow::Entry(2),
+                TableRow::Entry(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tree() {
+        let ancestors = |chain: &[(u32, &str)]| -> Vec<Ancestor> {
+            chain
+                .iter()
+                .map(|&(pid, name)| Ancestor {
+                    pid,
+                    name: name.to_string(),
+                })
+                .collect()
+        };
+        let mut entries = entries();
+        // tmux > zsh > npm > node :3000
+        entries[2].ancestors = ancestors(&[(2, "tmux"), (3, "zsh"), (4, "npm")]);
+        // nginx master 10 listens itself and runs worker 11
+        entries[3].ancestors = ancestors(&[(10, "nginx")]);
+
+        let (groups, top) = group_entries(
+            &entries,
+            GroupBy::Tree,
+            &|_| false,
+            SortColumn::Port,
+            SortOrder::Ascending,
+        );
+        let rows: Vec<(String, usize)> = table_rows(&groups, &top, &HashSet::new())
+            .into_iter()
+            .map(|(row, depth)| {
+                let label = match row {
+                    TableRow::Group(index) => groups[index].name.clone(),
+                    TableRow::Entry(index) => format!(":{}", entries[index].port),
+                };
+                (label, depth)
+            })
+            .collect();
+        let expected = [
+            ("nginx", 0),
+            (":80", 1),
+            (":443", 1),
+            (":8080", 1),
+            ("tmux", 0),
+            ("zsh", 1),
+            ("npm", 2),
+            (":3000", 3),
+        ];
+        let expected: Vec<(String, usize)> = expected
+            .iter()
+            .map(|&(label, depth)| (label.to_string(), depth))
+            .collect();
+        assert_eq!(rows, expected);
+
+        // Collapsing a node hides everything below it
+        let toggled = HashSet::from([GroupKey::Process(None, 3)]);
+        assert_eq!(table_rows(&groups, &top, &toggled).len(), 6);
+    }
+
+    #[test]
+    fn test_tree_keeps_hosts_apart() {
+        let ancestors = |chain: &[(u32, &str)]| -> Vec<Ancestor> {
+            chain
+                .iter()
+                .map(|&(pid, name)| Ancestor {
+                    pid,
+                    name: name.to_string(),
+                })
+                .collect()
+        };
+        let mut entries = entries();
+        for (entry, host) in entries.iter_mut().zip(["web1", "web1", "web2", "web2"]) {
+            entry.host = Some(host.to_string());
+            entry.ancestors = ancestors(&[(1, "systemd")]);
+        }
+        // PID 10 is nginx on web1 but a shell on web2
+        entries[2].ancestors = ancestors(&[(1, "systemd"), (10, "sh")]);
+
+        let (groups, top) = group_entries(
+            &entries,
+            GroupBy::Tree,
+            &|_| false,
+            SortColumn::Port,
+            SortOrder::Ascending,
+        );
+        let keys: Vec<&GroupKey> = groups.iter().map(|group| &group.key).collect();
+        assert_eq!(
+            keys,
+            vec![
+                &GroupKey::Process(Some("web1".to_string()), 1),
+                &GroupKey::Process(Some("web2".to_string()), 1),
+                &GroupKey::Process(Some("web2".to_string()), 10),
+            ]
+        );
+        assert_eq!(top.len(), 2);
+        // Each host's systemd only holds that host's listeners
+        assert_eq!(groups[0].entries, vec![0, 1]);
+        assert_eq!(groups[1].entries, vec![2, 3]);
+    }
+}
@@ -0,0 +1,237 @@
+//! Per-process resource history
+//!
+//! Keeps a bounded ring buffer of CPU% and memory samples for every process
+//! (host and PID) seen in recent scans, so the UI can draw sparklines and spot trends (e.g. a dev
+//! server slowly leaking memory) instead of a single snapshot value.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::app::PortEntry;
+
+/// Number of samples kept per process (2 minutes at the default 2s interval)
+pub const HISTORY_CAPACITY: usize = 60;
+
+/// Relative change between the old and new halves of the window that counts as a trend
+const TREND_THRESHOLD: f64 = 0.05;
+
+/// Minimum number of samples before a trend is reported
+const TREND_MIN_SAMPLES: usize = 4;
+
+/// Direction a metric has been moving over the history window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Rising,
+    Falling,
+    Steady,
+}
+
+impl Trend {
+    /// Arrow shown next to the value in the table
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Trend::Rising => "↑",
+            Trend::Falling => "↓",
+            Trend::Steady => "→",
+        }
+    }
+}
+
+/// Samples for a single process, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct PidHistory {
+    /// CPU usage samples in percent
+    pub cpu: VecDeque<f32>,
+    /// Memory (RSS) samples in bytes
+    pub memory: VecDeque<u64>,
+}
+
+impl PidHistory {
+    fn push(&mut self, cpu: f32, memory: u64, capacity: usize) {
+        if self.cpu.len() == capacity {
+            self.cpu.pop_front();
+            self.memory.pop_front();
+        }
+        self.cpu.push_back(cpu);
+        self.memory.push_back(memory);
+    }
+
+    /// Memory trend over the window (None until enough samples exist)
+    pub fn memory_trend(&self) -> Option<Trend> {
+        trend(self.memory.iter().map(|&m| m as f64))
+    }
+}
+
+/// Compare the average of the older half of the samples against the newer half
+fn trend(samples: impl ExactSizeIterator<Item = f64>) -> Option<Trend> {
+    let len = samples.len();
+    if len < TREND_MIN_SAMPLES {
+        return None;
+    }
+
+    let half = len / 2;
+    let (mut old, mut new) = (0.0, 0.0);
+    for (i, value) in samples.enumerate() {
+        if i < half {
+            old += value;
+        } else if i >= len - half {
+            new += value;
+        }
+    }
+    let (old, new) = (old / half as f64, new / half as f64);
+
+    let change = if old == 0.0 {
+        if new == 0.0 {
+            0.0
+        } else {
+            1.0
+        }
+    } else {
+        (new - old) / old
+    };
+
+    Some(if change > TREND_THRESHOLD {
+        Trend::Rising
+    } else if change < -TREND_THRESHOLD {
+        Trend::Falling
+    } else {
+        Trend::Steady
+    })
+}
+
+/// A process across scans: its host (None on a single host's tab) and PID
+///
+/// The fleet view mixes hosts, where the same PID means different processes.
+type ProcessKey = (Option<String>, u32);
+
+/// Ring buffers of samples for every process in the latest scans
+#[derive(Debug, Clone)]
+pub struct ProcessHistory {
+    samples: HashMap<ProcessKey, PidHistory>,
+    capacity: usize,
+}
+
+impl Default for ProcessHistory {
+    fn default() -> Self {
+        Self::new(HISTORY_CAPACITY)
+    }
+}
+
+impl ProcessHistory {
+    /// Create an empty history keeping `capacity` samples per PID
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: HashMap::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Record one sample per process from a scan and forget processes that are gone
+    pub fn record(&mut self, entries: &[PortEntry]) {
+        let mut seen = HashSet::new();
+        for entry in entries {
+            // A process listening on several ports contributes a single sample
+            let key = (entry.host.clone(), entry.pid);
+            if seen.insert(key.clone()) {
+                self.samples.entry(key).or_default().push(
+                    entry.cpu_usage,
+                    entry.memory_usage,
+                    self.capacity,
+                );
+            }
+        }
+        self.samples.retain(|key, _| seen.contains(key));
+    }
+
+    /// History for a PID on `host`, if it appeared in the latest scan
+    pub fn get(&self, host: Option<&str>, pid: u32) -> Option<&PidHistory> {
+        self.samples.get(&(host.map(str::to_string), pid))
+    }
+
+    /// Remove all samples (e.g. when switching hosts)
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(port: u16, pid: u32, cpu: f32, memory: u64) -> PortEntry {
+        PortEntry {
+            cpu_usage: cpu,
+            memory_usage: memory,
+            ..PortEntry::test(port, pid, &format!("process_{}", pid))
+        }
+    }
+
+    #[test]
+    fn test_record_is_bounded() {
+        let mut history = ProcessHistory::new(3);
+        for i in 0..5 {
+            history.record(&[entry(3000, 1, i as f32, i * 100)]);
+        }
+
+        let pid = history.get(None, 1).unwrap();
+        assert_eq!(pid.cpu, vec![2.0, 3.0, 4.0]);
+        assert_eq!(pid.memory, vec![200, 300, 400]);
+    }
+
+    #[test]
+    fn test_record_one_sample_per_pid() {
+        let mut history = ProcessHistory::default();
+        history.record(&[entry(3000, 1, 5.0, 10), entry(3001, 1, 5.0, 10)]);
+        assert_eq!(history.get(None, 1).unwrap().cpu.len(), 1);
+    }
+
+    #[test]
+    fn test_record_prunes_exited_pids() {
+        let mut history = ProcessHistory::default();
+        history.record(&[entry(3000, 1, 0.0, 0), entry(3001, 2, 0.0, 0)]);
+        history.record(&[entry(3001, 2, 0.0, 0)]);
+
+        assert!(history.get(None, 1).is_none());
+        assert!(history.get(None, 2).is_some());
+    }
+
+    #[test]
+    fn test_record_keeps_hosts_apart() {
+        let mut history = ProcessHistory::default();
+        let mut web1 = entry(3000, 1, 10.0, 100);
+        web1.host = Some("web1".to_string());
+        let mut web2 = entry(3000, 1, 90.0, 900);
+        web2.host = Some("web2".to_string());
+        history.record(&[web1, web2]);
+
+        assert_eq!(history.get(Some("web1"), 1).unwrap().cpu, vec![10.0]);
+        assert_eq!(history.get(Some("web2"), 1).unwrap().cpu, vec![90.0]);
+        assert!(history.get(None, 1).is_none());
+    }
+
+    #[test]
+    fn test_memory_trend() {
+        let mut history = ProcessHistory::default();
+        history.record(&[entry(3000, 1, 0.0, 100)]);
+        assert_eq!(history.get(None, 1).unwrap().memory_trend(), None);
+
+        for memory in [110, 130, 160, 200] {
+            history.record(&[entry(3000, 1, 0.0, memory)]);
+        }
+        assert_eq!(
+            history.get(None, 1).unwrap().memory_trend(),
+            Some(Trend::Rising)
+        );
+    }
+
+    #[test]
+    fn test_trend_steady_and_falling() {
+        let steady = [100.0, 101.0, 100.0, 99.0];
+        assert_eq!(trend(steady.into_iter()), Some(Trend::Steady));
+
+        let falling = [200.0, 180.0, 120.0, 100.0];
+        assert_eq!(trend(falling.into_iter()), Some(Trend::Falling));
+
+        let from_zero = [0.0, 0.0, 5.0, 5.0];
+        assert_eq!(trend(from_zero.into_iter()), Some(Trend::Rising));
+    }
+}
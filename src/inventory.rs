@@ -0,0 +1,458 @@
+//! Host inventory
+//!
+//! Hosts monitored often can be listed once in
+//! `$XDG_CONFIG_HOME/lsport/hosts.toml`, next to the config file, and then
+//! picked by label in the connect picker (`c`) or passed to `-H` by label.
+//! `-H @web` opens every host in the `web` group:
+//!
+//! ```toml
+//! [[hosts]]
+//! label = "web1"
+//! host = "deploy@10.0.0.11"
+//! groups = ["web", "prod"]
+//! identity = "~/.ssh/deploy"
+//! jump = "bastion"             # another label, or user@host:port
+//! ```
+//!
+//! Hosts connected to from the TUI are remembered (most recent first) in
+//! `$XDG_STATE_HOME/lsport/recent_hosts.toml` and listed first in the picker.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{config_path, expand_home};
+use crate::pins::pins_path;
+use crate::remote::RemoteConfig;
+
+/// Recent hosts kept for the picker
+const MAX_RECENT: usize = 10;
+
+/// A `[[hosts]]` entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InventoryHost {
+    /// Name shown in the picker and tab bar, and accepted by `-H`
+    pub label: String,
+    /// Address as `user@host:port`, `user@host` or `host`
+    pub host: String,
+    /// Groups the host belongs to, opened together with `-H @group`
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// SSH private key for this host
+    pub identity: Option<PathBuf>,
+    /// Host to connect through: another entry's label or an address
+    pub jump: Option<String>,
+}
+
+/// On-disk format of the inventory file
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct InventoryFile {
+    hosts: Vec<InventoryHost>,
+}
+
+/// On-disk format of the recent hosts file
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RecentFile {
+    hosts: Vec<String>,
+}
+
+/// A row of the connect picker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostChoice {
+    /// What to connect to: an inventory label or an address
+    pub name: String,
+    /// The address behind a label (empty for addresses)
+    pub address: String,
+    pub groups: Vec<String>,
+    /// Whether the host was connected to recently
+    pub recent: bool,
+}
+
+impl HostChoice {
+    /// Whether this is typed text rather than a known host
+    pub fn is_new(&self) -> bool {
+        !self.recent && self.address.is_empty()
+    }
+}
+
+/// Inventory hosts and recently used hosts, the latter saved on every change
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    hosts: Vec<InventoryHost>,
+    /// Most recent first
+    recent: Vec<String>,
+    /// Where to persist the recent hosts (None keeps them in memory only)
+    recent_path: Option<PathBuf>,
+}
+
+impl Inventory {
+    /// Load the inventory and recent hosts from their default files
+    pub fn load() -> Result<Self> {
+        let mut inventory = match config_path() {
+            Some(path) => Self::parse_file(&path.with_file_name("hosts.toml"))?,
+            None => Self::default(),
+        };
+        if let Some(path) = recent_hosts_path() {
+            inventory.load_recent(path)?;
+        }
+        Ok(inventory)
+    }
+
+    /// Read an inventory file (empty if it doesn't exist)
+    pub fn parse_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid inventory file {}", path.display()))
+    }
+
+    /// Parse and check inventory TOML
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: InventoryFile = toml::from_str(text)?;
+        let inventory = Self {
+            hosts: file.hosts,
+            ..Self::default()
+        };
+        for (index, entry) in inventory.hosts.iter().enumerate() {
+            if entry.label.is_empty() || entry.label.starts_with('@') {
+                bail!("Invalid label \"{}\"", entry.label);
+            }
+            if inventory.hosts[..index]
+                .iter()
+                .any(|other| other.label == entry.label)
+            {
+                bail!("Duplicate label \"{}\"", entry.label);
+            }
+            RemoteConfig::parse(&entry.host)
+                .with_context(|| format!("Invalid host for \"{}\"", entry.label))?;
+            if let Some(jump) = &entry.jump {
+                if jump == &entry.label {
+                    bail!("\"{}\" can't be its own jump host", entry.label);
+                }
+                inventory
+                    .jump_config(jump)
+                    .with_context(|| format!("Invalid jump host for \"{}\"", entry.label))?;
+            }
+        }
+        Ok(inventory)
+    }
+
+    /// Load recent hosts from `path`, which is also where changes are saved
+    pub fn load_recent(&mut self, path: PathBuf) -> Result<()> {
+        if path.exists() {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let file: RecentFile = toml::from_str(&text)
+                .with_context(|| format!("Invalid recent hosts file {}", path.display()))?;
+            self.recent = file.hosts;
+        }
+        self.recent_path = Some(path);
+        Ok(())
+    }
+
+    /// Look up a host by label
+    pub fn get(&self, label: &str) -> Option<&InventoryHost> {
+        self.hosts.iter().find(|entry| entry.label == label)
+    }
+
+    /// Replace every `@group` with the labels of the hosts in it, keeping each host once
+    pub fn expand(&self, hosts: &[String]) -> Result<Vec<String>> {
+        let mut expanded: Vec<String> = Vec::new();
+        for host in hosts {
+            let Some(group) = host.strip_prefix('@') else {
+                if !expanded.contains(host) {
+                    expanded.push(host.clone());
+                }
+                continue;
+            };
+            let members: Vec<String> = self
+                .hosts
+                .iter()
+                .filter(|entry| entry.groups.iter().any(|g| g == group))
+                .map(|entry| entry.label.clone())
+                .collect();
+            if members.is_empty() {
+                let mut groups: Vec<&str> = self
+                    .hosts
+                    .iter()
+                    .flat_map(|entry| entry.groups.iter().map(String::as_str))
+                    .collect();
+                groups.sort_unstable();
+                groups.dedup();
+                if groups.is_empty() {
+                    bail!(
+                        "No host group named '{}' (no groups in the inventory)",
+                        group
+                    );
+                }
+                bail!(
+                    "No host group named '{}' (available: {})",
+                    group,
+                    groups.join(", ")
+                );
+            }
+            for member in members {
+                if !expanded.contains(&member) {
+                    expanded.push(member);
+                }
+            }
+        }
+        Ok(expanded)
+    }
+
+    /// Connection settings for a label (with its key and jump host) or an address.
+    /// The key is left unset when the inventory doesn't name one.
+    pub fn remote_config(&self, host: &str) -> Result<RemoteConfig> {
+        let Some(entry) = self.get(host) else {
+            return RemoteConfig::parse(host);
+        };
+        let mut config = RemoteConfig::parse(&entry.host)?;
+        if let Some(identity) = &entry.identity {
+            config = config.with_key(expand_home(identity));
+        }
+        if let Some(jump) = &entry.jump {
+            config.jump = Some(Box::new(self.jump_config(jump)?));
+        }
+        Ok(config)
+    }
+
+    /// Connection settings for a jump host; only one hop is supported
+    fn jump_config(&self, jump: &str) -> Result<RemoteConfig> {
+        let Some(entry) = self.get(jump) else {
+            return RemoteConfig::parse(jump);
+        };
+        if entry.jump.is_some() {
+            bail!("Jump host \"{}\" has a jump host of its own", jump);
+        }
+        let mut config = RemoteConfig::parse(&entry.host)?;
+        if let Some(identity) = &entry.identity {
+            config = config.with_key(expand_home(identity));
+        }
+        Ok(config)
+    }
+
+    /// Address to look a host up by in the config's `[hosts]` tables
+    pub fn address<'a>(&'a self, host: &'a str) -> &'a str {
+        self.get(host).map_or(host, |entry| entry.host.as_str())
+    }
+
+    /// Move a host to the front of the recent hosts and save
+    pub fn remember(&mut self, host: &str) -> Result<()> {
+        self.recent.retain(|recent| recent != host);
+        self.recent.insert(0, host.to_string());
+        self.recent.truncate(MAX_RECENT);
+        self.save_recent()
+    }
+
+    /// Picker rows matching `query`, best match first: recent hosts, then the
+    /// rest of the inventory while the query is empty
+    pub fn choices(&self, query: &str) -> Vec<HostChoice> {
+        let recent = self.recent.iter().map(|name| match self.get(name) {
+            Some(entry) => choice(entry, true),
+            None => HostChoice {
+                name: name.clone(),
+                address: String::new(),
+                groups: Vec::new(),
+                recent: true,
+            },
+        });
+        let rest = self
+            .hosts
+            .iter()
+            .filter(|entry| !self.recent.contains(&entry.label))
+            .map(|entry| choice(entry, false));
+
+        let mut scored: Vec<(i64, HostChoice)> = recent
+            .chain(rest)
+            .filter_map(|choice| {
+                let text = format!(
+                    "{} {} {}",
+                    choice.name,
+                    choice.address,
+                    choice.groups.join(" ")
+                );
+                fuzzy_score(query, &text).map(|score| (score, choice))
+            })
+            .collect();
+        // Stable, so equal scores keep recent hosts first
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, choice)| choice).collect()
+    }
+
+    /// Write the recent hosts file (creating its directory if needed)
+    fn save_recent(&self) -> Result<()> {
+        let Some(path) = &self.recent_path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let file = RecentFile {
+            hosts: self.recent.clone(),
+        };
+        let text = toml::to_string(&file)?;
+        fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+fn choice(entry: &InventoryHost, recent: bool) -> HostChoice {
+    HostChoice {
+        name: entry.label.clone(),
+        address: entry.host.clone(),
+        groups: entry.groups.clone(),
+        recent,
+    }
+}
+
+/// Score `text` against a fuzzy `query` whose characters must appear in order
+/// (case-insensitive); runs of consecutive characters and word starts score higher.
+/// None if it doesn't match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|&c| c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+/// Path of the recent hosts file (`recent_hosts.toml` next to the pins)
+pub fn recent_hosts_path() -> Option<PathBuf> {
+    Some(pins_path()?.with_file_name("recent_hosts.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVENTORY: &str = r#"
+        [[hosts]]
+        label = "bastion"
+        host = "ops@gw.example.com:2222"
+
+        [[hosts]]
+        label = "web1"
+        host = "deploy@10.0.0.11"
+        groups = ["web", "prod"]
+        identity = "/keys/deploy"
+        jump = "bastion"
+
+        [[hosts]]
+        label = "db1"
+        host = "postgres@10.0.0.21"
+        groups = ["prod"]
+    "#;
+
+    #[test]
+    fn test_remote_config_by_label() {
+        let inventory = Inventory::parse(INVENTORY).unwrap();
+        let config = inventory.remote_config("web1").unwrap();
+        assert_eq!(config.display(), "deploy@10.0.0.11:22");
+        assert_eq!(config.key_path, Some(PathBuf::from("/keys/deploy")));
+        let jump = config.jump.unwrap();
+        assert_eq!(jump.display(), "ops@gw.example.com:2222");
+
+        let config = inventory.remote_config("root@other").unwrap();
+        assert_eq!(config.display(), "root@other:22");
+        assert!(config.jump.is_none());
+        assert_eq!(inventory.address("db1"), "postgres@10.0.0.21");
+        assert_eq!(inventory.address("root@other"), "root@other");
+    }
+
+    #[test]
+    fn test_expand_groups() {
+        let inventory = Inventory::parse(INVENTORY).unwrap();
+        let hosts = ["@prod".to_string(), "web1".to_string(), "x@y".to_string()];
+        assert_eq!(
+            inventory.expand(&hosts).unwrap(),
+            vec!["web1", "db1", "x@y"]
+        );
+
+        let err = inventory.expand(&["@dev".to_string()]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No host group named 'dev' (available: prod, web)"
+        );
+    }
+
+    #[test]
+    fn test_invalid_inventory() {
+        let duplicate =
+            "[[hosts]]\nlabel = \"a\"\nhost = \"x\"\n[[hosts]]\nlabel = \"a\"\nhost = \"y\"";
+        assert!(Inventory::parse(duplicate).is_err());
+        let bad_jump = "[[hosts]]\nlabel = \"a\"\nhost = \"x\"\njump = \"b@c:port\"";
+        assert!(Inventory::parse(bad_jump).is_err());
+        let chained = "[[hosts]]\nlabel = \"a\"\nhost = \"x\"\njump = \"b\"\n\
+                       [[hosts]]\nlabel = \"b\"\nhost = \"y\"\njump = \"c@d\"";
+        assert!(Inventory::parse(chained).is_err());
+        assert!(Inventory::parse("[[hosts]]\nlabel = \"a\"").is_err());
+    }
+
+    #[test]
+    fn test_choices_put_recent_first() {
+        let mut inventory = Inventory::parse(INVENTORY).unwrap();
+        inventory.remember("db1").unwrap();
+        inventory.remember("me@laptop").unwrap();
+
+        let names: Vec<String> = inventory
+            .choices("")
+            .into_iter()
+            .map(|choice| choice.name)
+            .collect();
+        assert_eq!(names, vec!["me@laptop", "db1", "bastion", "web1"]);
+
+        // Groups and addresses match too
+        let choices = inventory.choices("web");
+        assert_eq!(choices[0].name, "web1");
+        assert_eq!(choices.len(), 1);
+        assert_eq!(inventory.choices("10.0.0.2")[0].name, "db1");
+        assert!(inventory.choices("zzz").is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("wb1", "web1").is_some());
+        assert!(fuzzy_score("w1b", "web1").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        // Consecutive matches beat scattered ones
+        assert!(fuzzy_score("web", "web1") > fuzzy_score("web", "w-e-b"));
+    }
+
+    #[test]
+    fn test_recent_hosts_persist() {
+        let dir = std::env::temp_dir().join(format!("lsport-recent-{}", std::process::id()));
+        let path = dir.join("recent_hosts.toml");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut inventory = Inventory::default();
+        inventory.load_recent(path.clone()).unwrap();
+        for host in ["a", "b", "a"] {
+            inventory.remember(host).unwrap();
+        }
+
+        let mut reloaded = Inventory::default();
+        reloaded.load_recent(path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(reloaded.recent, vec!["a", "b"]);
+    }
+}
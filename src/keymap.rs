@@ -0,0 +1,816 @@
+//! Keybindings
+//!
+//! Every key the TUI reacts to is looked up in a [`KeyMap`] and turned into
+//! an [`Action`]. Keys are bound per context: the table, text prompts (filter,
+//! connect, save view, command palette), the view picker and bulk action
+//! confirmations. The `[keys]` section of the config file rebinds actions;
+//! each listed action replaces its default keys:
+//!
+//! ```toml
+//! [keys]
+//! kill = ["ctrl+k", "delete"]
+//! connect = "C"
+//! disconnect = []          # unbound
+//!
+//! [keys.prompt]
+//! cancel = ["esc", "ctrl+g"]
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::{anyhow, bail, Context, Result};
+use crossterm::event::{KeyCode, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::app::SortColumn;
+
+/// Something the user can do with a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Help,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    /// Switch to the next or previous host tab
+    NextTab,
+    PreviousTab,
+    /// Switch to the host tab with this number (1-9)
+    SelectTab(u8),
+    /// Show a collapsed group's entries
+    Expand,
+    /// Hide an expanded group's entries
+    Collapse,
+    Kill,
+    /// Stop (SIGSTOP) or resume (SIGCONT) the process
+    Pause,
+    /// Mark or unmark the selected row for a bulk action
+    Mark,
+    /// Mark every row in the table (or unmark them if all are marked)
+    MarkAll,
+    Filter,
+    ClearFilter,
+    Details,
+    /// Move the detail pane between the right of the table and below it
+    DetailsLayout,
+    /// Cycle between the flat table, grouping by PID or process name and the process tree
+    Group,
+    Pin,
+    ViewPicker,
+    SaveView,
+    NextView,
+    Events,
+    EventsOlder,
+    EventsNewer,
+    Connect,
+    Disconnect,
+    /// Open or close the fleet view merging every host tab
+    Fleet,
+    /// Open the `:` command palette
+    Command,
+    CycleSort,
+    ReverseSort,
+    SortBy(SortColumn),
+    /// Submit a prompt or pick the highlighted item
+    Confirm,
+    /// Leave a prompt or popup
+    Cancel,
+    /// Delete the last character typed into a prompt
+    DeleteChar,
+    /// Connect without asking for an SSH key, or complete a palette command
+    Skip,
+    /// Delete the highlighted view in the picker
+    Delete,
+}
+
+/// Heading an action is listed under in the help popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Navigation,
+    Actions,
+    Sorting,
+}
+
+/// Config names of [`Action::SelectTab`] 1-9
+const TAB_NAMES: [&str; 9] = [
+    "tab_1", "tab_2", "tab_3", "tab_4", "tab_5", "tab_6", "tab_7", "tab_8", "tab_9",
+];
+
+/// Help text of [`Action::SelectTab`] 1-9
+const TAB_DESCRIPTIONS: [&str; 9] = [
+    "Switch to host tab 1",
+    "Switch to host tab 2",
+    "Switch to host tab 3",
+    "Switch to host tab 4",
+    "Switch to host tab 5",
+    "Switch to host tab 6",
+    "Switch to host tab 7",
+    "Switch to host tab 8",
+    "Switch to host tab 9",
+];
+
+impl Action {
+    /// Name used in the `[keys]` config section
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::NextTab => "next_tab",
+            Action::PreviousTab => "previous_tab",
+            Action::SelectTab(n) => TAB_NAMES[usize::from(n.clamp(1, 9)) - 1],
+            Action::Expand => "expand",
+            Action::Collapse => "collapse",
+            Action::Kill => "kill",
+            Action::Pause => "pause",
+            Action::Mark => "mark",
+            Action::MarkAll => "mark_all",
+            Action::Filter => "filter",
+            Action::ClearFilter => "clear_filter",
+            Action::Details => "details",
+            Action::DetailsLayout => "details_layout",
+            Action::Group => "group",
+            Action::Pin => "pin",
+            Action::ViewPicker => "view_picker",
+            Action::SaveView => "save_view",
+            Action::NextView => "next_view",
+            Action::Events => "events",
+            Action::EventsOlder => "events_older",
+            Action::EventsNewer => "events_newer",
+            Action::Connect => "connect",
+            Action::Disconnect => "disconnect",
+            Action::Fleet => "fleet",
+            Action::Command => "command",
+            Action::CycleSort => "cycle_sort",
+            Action::ReverseSort => "reverse_sort",
+            Action::SortBy(SortColumn::Port) => "sort_port",
+            Action::SortBy(SortColumn::Protocol) => "sort_protocol",
+            Action::SortBy(SortColumn::Pid) => "sort_pid",
+            Action::SortBy(SortColumn::ProcessName) => "sort_name",
+            Action::SortBy(SortColumn::CpuUsage) => "sort_cpu",
+            Action::SortBy(SortColumn::MemoryUsage) => "sort_memory",
+            Action::SortBy(SortColumn::Uptime) => "sort_uptime",
+            Action::Confirm => "confirm",
+            Action::Cancel => "cancel",
+            Action::DeleteChar => "delete_char",
+            Action::Skip => "skip",
+            Action::Delete => "delete",
+        }
+    }
+
+    /// What the action does, as shown in the help popup
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Help => "Toggle help",
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::PageUp => "Page up (10 rows)",
+            Action::PageDown => "Page down (10 rows)",
+            Action::First => "Go to first",
+            Action::Last => "Go to last",
+            Action::NextTab => "Next host tab",
+            Action::PreviousTab => "Previous host tab",
+            Action::SelectTab(n) => TAB_DESCRIPTIONS[usize::from(n.clamp(1, 9)) - 1],
+            Action::Expand => "Expand group",
+            Action::Collapse => "Collapse group",
+            Action::Kill => "Kill selected (or marked) processes",
+            Action::Pause => "Pause/resume selected (or marked) processes",
+            Action::Mark => "Mark row for bulk kill/pause/export",
+            Action::MarkAll => "Mark/unmark all rows",
+            Action::Filter => "Filter (e.g. proto:tcp cpu>20 !name:sshd)",
+            Action::ClearFilter => "Clear filter",
+            Action::Details => "Toggle process details",
+            Action::DetailsLayout => "Move details pane right/below",
+            Action::Group => "Group by PID / name / process tree / off",
+            Action::Pin => "Pin/unpin port (notify on changes)",
+            Action::ViewPicker => "Pick a saved view",
+            Action::SaveView => "Save filter + sort as a view",
+            Action::NextView => "Switch to next saved view",
+            Action::Events => "Toggle port event log",
+            Action::EventsOlder => "Scroll event log older",
+            Action::EventsNewer => "Scroll event log newer",
+            Action::Connect => "Connect to a remote host in a new tab",
+            Action::Disconnect => "Close host tab / disconnect from remote",
+            Action::Fleet => "Show all hosts in one table",
+            Action::Command => "Command palette (e.g. :kill 8080)",
+            Action::CycleSort => "Cycle sort column",
+            Action::ReverseSort => "Reverse sort order",
+            Action::SortBy(SortColumn::Port) => "Sort by Port",
+            Action::SortBy(SortColumn::Protocol) => "Sort by Protocol",
+            Action::SortBy(SortColumn::Pid) => "Sort by PID",
+            Action::SortBy(SortColumn::ProcessName) => "Sort by Name",
+            Action::SortBy(SortColumn::CpuUsage) => "Sort by CPU %",
+            Action::SortBy(SortColumn::MemoryUsage) => "Sort by Memory",
+            Action::SortBy(SortColumn::Uptime) => "Sort by Uptime",
+            Action::Confirm => "Confirm",
+            Action::Cancel => "Cancel",
+            Action::DeleteChar => "Delete last character",
+            Action::Skip => "Connect without an SSH key / complete a command",
+            Action::Delete => "Delete view",
+        }
+    }
+
+    /// Every action available in the table, in help order
+    pub fn all_in_table() -> impl Iterator<Item = Action> {
+        KeyContext::Table
+            .defaults()
+            .into_iter()
+            .map(|(action, _)| action)
+    }
+
+    /// Help popup heading for a table action
+    pub fn section(self) -> Section {
+        match self {
+            Action::Up
+            | Action::Down
+            | Action::PageUp
+            | Action::PageDown
+            | Action::First
+            | Action::Last
+            | Action::Expand
+            | Action::Collapse
+            | Action::NextTab
+            | Action::PreviousTab
+            | Action::SelectTab(_) => Section::Navigation,
+            Action::CycleSort | Action::ReverseSort | Action::SortBy(_) => Section::Sorting,
+            _ => Section::Actions,
+        }
+    }
+}
+
+/// Where a key was pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    /// The port table (no prompt or popup open)
+    Table,
+    /// Typing a filter, host, key path, view name or palette command
+    Prompt,
+    /// The saved view picker
+    Picker,
+    /// Confirming a bulk action
+    Confirm,
+}
+
+impl KeyContext {
+    /// Config table the context is configured in
+    fn config_name(self) -> &'static str {
+        match self {
+            KeyContext::Table => "keys",
+            KeyContext::Prompt => "keys.prompt",
+            KeyContext::Picker => "keys.picker",
+            KeyContext::Confirm => "keys.confirm",
+        }
+    }
+
+    /// Actions available in this context with their default keys, in help order
+    fn defaults(self) -> Vec<(Action, &'static [&'static str])> {
+        match self {
+            KeyContext::Table => vec![
+                (Action::Up, &["k", "up"]),
+                (Action::Down, &["j", "down"]),
+                (Action::PageUp, &["pageup"]),
+                (Action::PageDown, &["pagedown"]),
+                (Action::First, &["home"]),
+                (Action::Last, &["end"]),
+                (Action::Expand, &["l", "right"]),
+                (Action::Collapse, &["h", "left"]),
+                (Action::NextTab, &["tab", "alt+right", "ctrl+n"]),
+                (Action::PreviousTab, &["backtab", "alt+left", "ctrl+p"]),
+                (Action::SelectTab(1), &["alt+1"]),
+                (Action::SelectTab(2), &["alt+2"]),
+                (Action::SelectTab(3), &["alt+3"]),
+                (Action::SelectTab(4), &["alt+4"]),
+                (Action::SelectTab(5), &["alt+5"]),
+                (Action::SelectTab(6), &["alt+6"]),
+                (Action::SelectTab(7), &["alt+7"]),
+                (Action::SelectTab(8), &["alt+8"]),
+                (Action::SelectTab(9), &["alt+9"]),
+                (Action::Kill, &["enter", "ctrl+K"]),
+                (Action::Pause, &["z"]),
+                (Action::Mark, &["space"]),
+                (Action::MarkAll, &["a"]),
+                (Action::Filter, &["/"]),
+                (Action::ClearFilter, &["esc"]),
+                (Action::Details, &["i"]),
+                (Action::DetailsLayout, &["L"]),
+                (Action::Group, &["g"]),
+                (Action::Pin, &["p"]),
+                (Action::ViewPicker, &["v"]),
+                (Action::SaveView, &["V"]),
+                (Action::NextView, &["n"]),
+                (Action::Events, &["e"]),
+                (Action::EventsOlder, &["["]),
+                (Action::EventsNewer, &["]"]),
+                (Action::Connect, &["c"]),
+                (Action::Disconnect, &["d", "D"]),
+                (Action::Fleet, &["F"]),
+                (Action::Command, &[":"]),
+                (Action::Help, &["?"]),
+                (Action::Quit, &["q", "Q", "ctrl+c"]),
+                (Action::CycleSort, &["s"]),
+                (Action::ReverseSort, &["r"]),
+                (Action::SortBy(SortColumn::Port), &["P", "1"]),
+                (Action::SortBy(SortColumn::Protocol), &["O", "2"]),
+                (Action::SortBy(SortColumn::Pid), &["I", "3"]),
+                (Action::SortBy(SortColumn::ProcessName), &["N", "4"]),
+                (Action::SortBy(SortColumn::CpuUsage), &["C", "5"]),
+                (Action::SortBy(SortColumn::MemoryUsage), &["M", "6"]),
+                (Action::SortBy(SortColumn::Uptime), &["U", "7"]),
+            ],
+            KeyContext::Prompt => vec![
+                (Action::Confirm, &["enter"]),
+                (Action::Cancel, &["esc"]),
+                (Action::DeleteChar, &["backspace"]),
+                (Action::Skip, &["tab"]),
+            ],
+            KeyContext::Picker => vec![
+                (Action::Up, &["k", "up"]),
+                (Action::Down, &["j", "down"]),
+                (Action::Confirm, &["enter"]),
+                (Action::Delete, &["d", "delete"]),
+                (Action::Cancel, &["esc", "v", "q"]),
+            ],
+            KeyContext::Confirm => vec![
+                (Action::Confirm, &["y", "enter"]),
+                (Action::Cancel, &["n", "esc", "q"]),
+            ],
+        }
+    }
+}
+
+/// A key plus modifiers, e.g. `ctrl+c` or `G`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+/// Names of keys that aren't a single character
+const NAMED_KEYS: &[(&str, KeyCode, &str)] = &[
+    ("enter", KeyCode::Enter, "Enter"),
+    ("esc", KeyCode::Esc, "Esc"),
+    ("tab", KeyCode::Tab, "Tab"),
+    ("backtab", KeyCode::BackTab, "Shift+Tab"),
+    ("backspace", KeyCode::Backspace, "Backspace"),
+    ("delete", KeyCode::Delete, "Del"),
+    ("insert", KeyCode::Insert, "Ins"),
+    ("up", KeyCode::Up, "↑"),
+    ("down", KeyCode::Down, "↓"),
+    ("left", KeyCode::Left, "←"),
+    ("right", KeyCode::Right, "→"),
+    ("pageup", KeyCode::PageUp, "PgUp"),
+    ("pagedown", KeyCode::PageDown, "PgDn"),
+    ("home", KeyCode::Home, "Home"),
+    ("end", KeyCode::End, "End"),
+    ("space", KeyCode::Char(' '), "Space"),
+];
+
+impl KeyBinding {
+    /// Binding for a key event as reported by crossterm
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        // Shift is already part of the character ('G') or of BackTab, though some
+        // terminals report ctrl+shift+k as a lowercase 'k' with shift held
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            code => code,
+        };
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+
+    /// Parse a binding such as `q`, `G`, `ctrl+c`, `alt+enter`, `pagedown` or `f5`
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rest = text;
+        let mut modifiers = KeyModifiers::NONE;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl+") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt+") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift+") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            if rest.len() == len {
+                break; // "ctrl+" alone, or the key is '+' itself
+            }
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (None, _) => bail!("Empty key binding"),
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => {
+                let lower = rest.to_ascii_lowercase();
+                if let Some(&(_, code, _)) = NAMED_KEYS.iter().find(|(name, ..)| *name == lower) {
+                    code
+                } else if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    if !(1..=12).contains(&n) {
+                        bail!("Unknown key '{}'", text);
+                    }
+                    KeyCode::F(n)
+                } else {
+                    bail!("Unknown key '{}'", text);
+                }
+            }
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        if let Some((_, _, label)) = NAMED_KEYS.iter().find(|(_, code, _)| *code == self.code) {
+            return write!(f, "{}", label);
+        }
+        match self.code {
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// One key or a list of keys in the `[keys]` config section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn keys(&self) -> &[String] {
+        match self {
+            KeyList::One(key) => std::slice::from_ref(key),
+            KeyList::Many(keys) => keys,
+        }
+    }
+}
+
+/// The `[keys]` config section: table actions, plus `[keys.prompt]`, `[keys.picker]`
+/// and `[keys.confirm]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeysConfig {
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub prompt: BTreeMap<String, KeyList>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub picker: BTreeMap<String, KeyList>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub confirm: BTreeMap<String, KeyList>,
+    #[serde(flatten)]
+    pub table: BTreeMap<String, KeyList>,
+}
+
+/// Actions and their keys for one context
+#[derive(Debug, Clone)]
+struct Bindings {
+    actions: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Bindings {
+    /// Default bindings with the configured overrides applied
+    fn new(context: KeyContext, overrides: &BTreeMap<String, KeyList>) -> Result<Self> {
+        let mut actions: Vec<(Action, Vec<KeyBinding>)> = context
+            .defaults()
+            .into_iter()
+            .map(|(action, keys)| {
+                let keys = keys
+                    .iter()
+                    .map(|key| KeyBinding::parse(key).expect("valid default key"))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+
+        // Parse the overrides, rejecting a key given to two actions
+        let mut configured: Vec<(usize, Vec<KeyBinding>)> = Vec::new();
+        for (name, list) in overrides {
+            let index = actions
+                .iter()
+                .position(|(action, _)| action.name() == name)
+                .ok_or_else(|| {
+                    anyhow!("Unknown action '{}' in [{}]", name, context.config_name())
+                })?;
+            let mut keys = Vec::new();
+            for key in list.keys() {
+                let key = KeyBinding::parse(key)
+                    .with_context(|| format!("Invalid key for '{}'", name))?;
+                if let Some((other, _)) = configured.iter().find(|(_, k)| k.contains(&key)) {
+                    bail!(
+                        "Key '{}' is bound to both '{}' and '{}' in [{}]",
+                        key,
+                        actions[*other].0.name(),
+                        name,
+                        context.config_name()
+                    );
+                }
+                keys.push(key);
+            }
+            configured.push((index, keys));
+        }
+
+        // Configured keys take over from any default binding of the same key
+        for (index, keys) in configured {
+            for (_, defaults) in actions.iter_mut() {
+                defaults.retain(|key| !keys.contains(key));
+            }
+            actions[index].1 = keys;
+        }
+        Ok(Self { actions })
+    }
+
+    fn action(&self, key: KeyBinding) -> Option<Action> {
+        self.actions
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(action, _)| *action)
+    }
+
+    fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.actions
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map_or(&[], |(_, keys)| keys)
+    }
+}
+
+/// Key to action lookup for every context
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    table: Bindings,
+    prompt: Bindings,
+    picker: Bindings,
+    confirm: Bindings,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::new(&KeysConfig::default()).expect("default keymap is valid")
+    }
+}
+
+impl KeyMap {
+    /// Build the keymap from the `[keys]` config section
+    pub fn new(config: &KeysConfig) -> Result<Self> {
+        Ok(Self {
+            table: Bindings::new(KeyContext::Table, &config.table)?,
+            prompt: Bindings::new(KeyContext::Prompt, &config.prompt)?,
+            picker: Bindings::new(KeyContext::Picker, &config.picker)?,
+            confirm: Bindings::new(KeyContext::Confirm, &config.confirm)?,
+        })
+    }
+
+    fn bindings(&self, context: KeyContext) -> &Bindings {
+        match context {
+            KeyContext::Table => &self.table,
+            KeyContext::Prompt => &self.prompt,
+            KeyContext::Picker => &self.picker,
+            KeyContext::Confirm => &self.confirm,
+        }
+    }
+
+    /// The action bound to a key press; an unbound shift+key falls back to the plain key,
+    /// but ctrl and alt chords only ever run what they're bound to
+    pub fn action(
+        &self,
+        context: KeyContext,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Option<Action> {
+        let bindings = self.bindings(context);
+        let binding = KeyBinding::new(code, modifiers);
+        bindings.action(binding).or_else(|| {
+            (binding.modifiers == KeyModifiers::SHIFT)
+                .then(|| bindings.action(KeyBinding::new(binding.code, KeyModifiers::NONE)))
+                .flatten()
+        })
+    }
+
+    /// Keys bound to an action
+    pub fn keys(&self, context: KeyContext, action: Action) -> &[KeyBinding] {
+        self.bindings(context).keys(action)
+    }
+
+    /// Keys bound to an action joined with '/', e.g. "k/↑" (empty if unbound)
+    pub fn label(&self, context: KeyContext, action: Action) -> String {
+        self.keys(context, action)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// The first key bound to an action, for compact hints
+    pub fn first_label(&self, context: KeyContext, action: Action) -> Option<String> {
+        self.keys(context, action).first().map(ToString::to_string)
+    }
+
+    /// Table actions with at least one key, in help order
+    pub fn table_actions(&self) -> impl Iterator<Item = Action> + '_ {
+        self.table
+            .actions
+            .iter()
+            .filter(|(_, keys)| !keys.is_empty())
+            .map(|(action, _)| *action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(entries: &[(&str, &[&str])]) -> BTreeMap<String, KeyList> {
+        entries
+            .iter()
+            .map(|(name, keys)| {
+                let keys = keys.iter().map(|k| k.to_string()).collect();
+                (name.to_string(), KeyList::Many(keys))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_bindings() {
+        let ctrl_c = KeyBinding::parse("ctrl+c").unwrap();
+        assert_eq!(
+            ctrl_c,
+            KeyBinding::new(KeyCode::Char('c'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(ctrl_c.to_string(), "Ctrl+c");
+
+        assert_eq!(
+            KeyBinding::parse("shift+g").unwrap(),
+            KeyBinding::new(KeyCode::Char('G'), KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            KeyBinding::parse("PageDown").unwrap(),
+            KeyBinding::new(KeyCode::PageDown, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            KeyBinding::parse("f5").unwrap().to_string(),
+            "F5".to_string()
+        );
+        assert_eq!(
+            KeyBinding::parse("ctrl++").unwrap(),
+            KeyBinding::new(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(KeyBinding::parse("alt+up").unwrap().to_string(), "Alt+↑");
+
+        assert!(KeyBinding::parse("").is_err());
+        assert!(KeyBinding::parse("hyper+x").is_err());
+        assert!(KeyBinding::parse("f13").is_err());
+    }
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = KeyMap::default();
+        let table = |code, modifiers| keymap.action(KeyContext::Table, code, modifiers);
+
+        assert_eq!(
+            table(KeyCode::Char('q'), KeyModifiers::NONE),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            table(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            table(KeyCode::Char('c'), KeyModifiers::NONE),
+            Some(Action::Connect)
+        );
+        // Shift is implied by the uppercase character
+        assert_eq!(
+            table(KeyCode::Char('P'), KeyModifiers::SHIFT),
+            Some(Action::SortBy(SortColumn::Port))
+        );
+        assert_eq!(table(KeyCode::Char('x'), KeyModifiers::NONE), None);
+
+        assert_eq!(
+            keymap.action(KeyContext::Prompt, KeyCode::Enter, KeyModifiers::NONE),
+            Some(Action::Confirm)
+        );
+        assert_eq!(
+            keymap.action(KeyContext::Picker, KeyCode::Char('d'), KeyModifiers::NONE),
+            Some(Action::Delete)
+        );
+        assert_eq!(
+            keymap.action(KeyContext::Confirm, KeyCode::Char('y'), KeyModifiers::NONE),
+            Some(Action::Confirm)
+        );
+        assert_eq!(keymap.label(KeyContext::Table, Action::Up), "k/↑");
+    }
+
+    #[test]
+    fn test_overrides_replace_defaults() {
+        let config = KeysConfig {
+            table: keys(&[("kill", &["ctrl+k"]), ("connect", &["d"]), ("pin", &[])]),
+            ..Default::default()
+        };
+        let keymap = KeyMap::new(&config).unwrap();
+        let table = |code, modifiers| keymap.action(KeyContext::Table, code, modifiers);
+
+        assert_eq!(table(KeyCode::Enter, KeyModifiers::NONE), None);
+        assert_eq!(
+            table(KeyCode::Char('k'), KeyModifiers::CONTROL),
+            Some(Action::Kill)
+        );
+        // 'd' moved from disconnect to connect; disconnect keeps 'D'
+        assert_eq!(
+            table(KeyCode::Char('d'), KeyModifiers::NONE),
+            Some(Action::Connect)
+        );
+        assert_eq!(keymap.label(KeyContext::Table, Action::Disconnect), "D");
+        assert_eq!(table(KeyCode::Char('p'), KeyModifiers::NONE), None);
+        assert!(!keymap.table_actions().any(|a| a == Action::Pin));
+    }
+
+    #[test]
+    fn test_unbound_chords_do_nothing() {
+        let keymap = KeyMap::new(&KeysConfig::default()).unwrap();
+        let table = |code, modifiers| keymap.action(KeyContext::Table, code, modifiers);
+
+        // Unbound ctrl/alt chords don't fall back to the plain key's action
+        assert_eq!(table(KeyCode::Char('c'), KeyModifiers::ALT), None);
+        assert_eq!(table(KeyCode::Char('a'), KeyModifiers::CONTROL), None);
+        assert_eq!(table(KeyCode::Char('k'), KeyModifiers::CONTROL), None);
+        assert_eq!(table(KeyCode::Down, KeyModifiers::CONTROL), None);
+
+        // ctrl+K is Kill however the terminal reports the shift
+        let ctrl_shift = KeyModifiers::CONTROL | KeyModifiers::SHIFT;
+        assert_eq!(table(KeyCode::Char('K'), ctrl_shift), Some(Action::Kill));
+        assert_eq!(table(KeyCode::Char('k'), ctrl_shift), Some(Action::Kill));
+
+        // Shift alone still falls back to the unshifted key
+        assert_eq!(
+            table(KeyCode::Down, KeyModifiers::SHIFT),
+            Some(Action::Down)
+        );
+    }
+
+    #[test]
+    fn test_invalid_overrides() {
+        let unknown = KeysConfig {
+            table: keys(&[("explode", &["x"])]),
+            ..Default::default()
+        };
+        let err = KeyMap::new(&unknown).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown action 'explode' in [keys]");
+
+        // Prompt actions can't be bound in the table
+        let wrong_context = KeysConfig {
+            table: keys(&[("delete_char", &["x"])]),
+            ..Default::default()
+        };
+        assert!(KeyMap::new(&wrong_context).is_err());
+
+        let conflict = KeysConfig {
+            picker: keys(&[("confirm", &["x"]), ("delete", &["x"])]),
+            ..Default::default()
+        };
+        let err = KeyMap::new(&conflict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Key 'x' is bound to both 'confirm' and 'delete' in [keys.picker]"
+        );
+
+        let bad_key = KeysConfig {
+            prompt: keys(&[("cancel", &["ctrl+"])]),
+            ..Default::default()
+        };
+        assert!(KeyMap::new(&bad_key).is_err());
+    }
+}
@@ -4,37 +4,62 @@
 //! runs the event loop, and coordinates the Model-View-Update cycle.
 
 mod app;
+mod config;
+mod events;
+mod export;
+mod group;
+mod history;
+mod inventory;
+mod keymap;
+mod notify;
+mod palette;
+mod pins;
+mod query;
 mod remote;
+mod rules;
 mod scanner;
+mod tabs;
+mod theme;
 mod ui;
+mod views;
 
 use std::{
-    io::{self, stdout},
-    path::PathBuf,
+    collections::HashSet,
+    io::{self, stdout, Write},
+    path::{Path, PathBuf},
     time::Duration,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::{Parser, Subcommand};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
-use app::SortColumn;
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
 
-use app::App;
+use app::{App, BulkKind, PendingBulk, TabRequest};
+use inventory::Inventory;
+use keymap::{Action, KeyContext};
+use notify::Notifier;
+use pins::Pins;
+use query::{Query, QueryError};
 use remote::{RemoteConfig, RemoteScanner};
-use scanner::Scanner;
+use rules::{RuleEngine, Severity};
+use scanner::{Scanner, Signal};
+use tabs::{ScannerMode, SharedScanner, Tab, Tabs};
+use theme::{ColorSupport, Theme};
+use ui::ClickTarget;
+use views::Views;
 
 /// Poll rate for responsive input (50ms)
 const POLL_RATE: Duration = Duration::from_millis(50);
 
-/// Default scan interval for refreshing port data (2 seconds)
-const DEFAULT_SCAN_INTERVAL: u64 = 2;
-
 /// Lsport: A TUI for managing local and remote ports via SSH
 #[derive(Parser, Debug)]
 #[command(name = "lsport")]
@@ -43,30 +68,61 @@ struct Args {
     #[command(subcommand)]
     command: Option<Command>,
 
-    /// Remote host to monitor (format: user@host:port or user@host or host)
+    /// Remote host to monitor (format: user@host:port or user@host or host), an
+    /// inventory label, or @group for every host in an inventory group.
+    /// Repeat to monitor several hosts, each in its own tab
     /// Only used in TUI mode (when no subcommand is provided)
     #[arg(short = 'H', long)]
-    host: Option<String>,
+    host: Vec<String>,
+
+    /// Start in the fleet view, with the ports of every host in one table
+    /// Only used in TUI mode (when no subcommand is provided)
+    #[arg(long)]
+    fleet: bool,
 
     /// Path to SSH private key (optional, uses ssh-agent or default keys if not specified)
     /// Only used in TUI mode (when no subcommand is provided)
     #[arg(short = 'i', long)]
     identity: Option<PathBuf>,
 
-    /// Scan interval in seconds (default: 2)
+    /// Scan interval in seconds (default: 2, or `scan_interval` from the config file)
+    /// Only used in TUI mode (when no subcommand is provided)
+    #[arg(short = 's', long, value_parser = clap::value_parser!(u64).range(1..))]
+    scan_interval: Option<u64>,
+
+    /// Append port open/close events to this file
+    /// Only used in TUI mode (when no subcommand is provided)
+    #[arg(long, value_name = "FILE")]
+    event_log: Option<PathBuf>,
+
+    /// Start with this filter query (e.g. "proto:tcp cpu>20 !name:sshd")
+    /// Only used in TUI mode (when no subcommand is provided)
+    #[arg(long, value_name = "QUERY", value_parser = validate_filter)]
+    filter: Option<String>,
+
+    /// Start with a saved view's filter and sort (--filter overrides the view's filter)
+    /// Only used in TUI mode (when no subcommand is provided)
+    #[arg(long, value_name = "NAME")]
+    view: Option<String>,
+
+    /// Color theme: dark, light, high-contrast, 16-color or one from the config file
     /// Only used in TUI mode (when no subcommand is provided)
-    #[arg(short = 's', long, default_value_t = DEFAULT_SCAN_INTERVAL)]
-    scan_interval: u64,
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Command {
     /// Describe a port or process (by port number or PID)
     Describe {
-        /// Port number or PID to describe
-        #[arg(value_name = "PORT_OR_PID")]
+        /// Port number or PID to describe (omit to describe everything matching --filter)
+        #[arg(value_name = "PORT_OR_PID", default_value = "")]
         target: String,
 
+        /// Only describe entries matching this filter query
+        #[arg(long, value_name = "QUERY")]
+        filter: Option<Query>,
+
         /// Remote host to query (format: user@host:port or user@host or host)
         #[arg(short = 'H', long)]
         host: Option<String>,
@@ -85,6 +141,10 @@ enum Command {
         #[arg(long, value_name = "PORT")]
         port: Option<u16>,
 
+        /// Only kill a process matching this filter query (narrows --pid/--port if given)
+        #[arg(long, value_name = "QUERY")]
+        filter: Option<Query>,
+
         /// Remote host to query (format: user@host:port or user@host or host)
         #[arg(short = 'H', long)]
         host: Option<String>,
@@ -97,6 +157,61 @@ enum Command {
         #[arg(short = 'f', long)]
         force: bool,
     },
+    /// List ports whose process matches a health rule from the config file
+    Health {
+        /// Only show alerts at or above this severity (info, warning, critical)
+        #[arg(long, value_name = "SEVERITY", default_value = "info")]
+        min_severity: Severity,
+
+        /// Only check entries matching this filter query
+        #[arg(long, value_name = "QUERY")]
+        filter: Option<Query>,
+
+        /// Only show alerts raised by this rule
+        #[arg(long, value_name = "NAME")]
+        rule: Option<String>,
+
+        /// Remote host to query (format: user@host:port or user@host or host)
+        #[arg(short = 'H', long)]
+        host: Option<String>,
+
+        /// Path to SSH private key (optional, uses ssh-agent or default keys if not specified)
+        #[arg(short = 'i', long)]
+        identity: Option<PathBuf>,
+    },
+    /// Stream port open/close/owner-change events to stdout as NDJSON
+    Watch {
+        /// Time between scans (e.g. 1s, 5s, 1m)
+        #[arg(long, value_name = "DURATION", default_value = "1s", value_parser = parse_interval)]
+        interval: Duration,
+
+        /// Only report changes to entries matching this filter query
+        #[arg(long, value_name = "QUERY")]
+        filter: Option<Query>,
+
+        /// Remote host to watch (format: user@host:port or user@host or host)
+        #[arg(short = 'H', long)]
+        host: Option<String>,
+
+        /// Path to SSH private key (optional, uses ssh-agent or default keys if not specified)
+        #[arg(short = 'i', long)]
+        identity: Option<PathBuf>,
+    },
+    /// Inspect the configuration file
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone, Copy)]
+enum ConfigCommand {
+    /// Print where the config file is read from
+    Path,
+    /// Print the configuration with every default filled in
+    Show,
+    /// Check the config file for errors
+    Validate,
 }
 
 fn main() -> Result<()> {
@@ -105,16 +220,41 @@ fn main() -> Result<()> {
     match args.command {
         Some(Command::Describe {
             target,
+            filter,
             host,
             identity,
-        }) => run_describe(target, host, identity),
+        }) => run_describe(target, filter, host, identity),
         Some(Command::Kill {
             pid,
             port,
+            filter,
             host,
             identity,
             force,
-        }) => run_kill(pid, port, host, identity, force),
+        }) => run_kill(pid, port, filter, host, identity, force),
+        Some(Command::Health {
+            min_severity,
+            filter,
+            rule,
+            host,
+            identity,
+        }) => run_health(
+            &config::load_rule_engine()?,
+            min_severity,
+            filter,
+            rule,
+            host,
+            identity,
+        ),
+        Some(Command::Watch {
+            interval,
+            filter,
+            host,
+            identity,
+        }) => run_watch(interval, filter, host, identity),
+        Some(Command::Config { action }) => {
+            run_config(action, config::config_path().as_deref(), &mut stdout())
+        }
         None => {
             // Setup terminal for TUI mode
             let terminal = setup_terminal().context("Failed to setup terminal")?;
@@ -135,7 +275,8 @@ fn main() -> Result<()> {
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     enable_raw_mode().context("Failed to enable raw mode")?;
     let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen).context("Failed to enter alternate screen")?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
+        .context("Failed to enter alternate screen")?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend).context("Failed to create terminal")?;
     Ok(terminal)
@@ -144,13 +285,24 @@ fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
 /// Restore the terminal to its original state
 fn restore_terminal() -> Result<()> {
     disable_raw_mode().context("Failed to disable raw mode")?;
-    execute!(stdout(), LeaveAlternateScreen).context("Failed to leave alternate screen")?;
+    execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen)
+        .context("Failed to leave alternate screen")?;
     Ok(())
 }
 
 /// Run the describe command
-fn run_describe(target: String, host: Option<String>, identity: Option<PathBuf>) -> Result<()> {
-    let entries = scan_ports(host.as_deref(), identity.as_ref())?;
+fn run_describe(
+    target: String,
+    filter: Option<Query>,
+    host: Option<String>,
+    identity: Option<PathBuf>,
+) -> Result<()> {
+    let engine = config::load_rule_engine()?;
+    let mut entries = scan_ports(host.as_deref(), identity.as_ref())?;
+    engine.evaluate_once(&mut entries);
+    if let Some(query) = &filter {
+        entries.retain(|e| query.matches(e));
+    }
 
     // Try to parse as port number first, then PID
     let port: Option<u16> = target.parse().ok();
@@ -172,6 +324,9 @@ fn run_describe(target: String, host: Option<String>, identity: Option<PathBuf>)
         .collect();
 
     if matching_entries.is_empty() {
+        if target.is_empty() {
+            anyhow::bail!("No process found matching the filter");
+        }
         anyhow::bail!("No process found matching '{}'", target);
     }
 
@@ -180,36 +335,92 @@ fn run_describe(target: String, host: Option<String>, identity: Option<PathBuf>)
     for entry in matching_entries {
         println!("Port:        {}", entry.port);
         println!("Protocol:    {}", entry.protocol);
+        println!("Address:     {}", entry.address.as_deref().unwrap_or("-"));
         println!("PID:         {}", entry.pid);
         println!("Process:     {}", entry.process_name);
+        println!("User:        {}", entry.user.as_deref().unwrap_or("-"));
+        println!("Command:     {}", entry.command.as_deref().unwrap_or("-"));
+        println!("Executable:  {}", entry.exe_path.as_deref().unwrap_or("-"));
+        println!("Working Dir: {}", entry.cwd.as_deref().unwrap_or("-"));
         println!("CPU Usage:   {:.1}%", entry.cpu_usage);
         println!("Memory:      {}", entry.memory_display);
+        println!("Started:     {}", entry.start_time_display());
+        println!("Uptime:      {}", entry.uptime_display());
+        println!("State:       {}", entry.state);
+        println!(
+            "Parent:      {}",
+            match (entry.parent_pid, entry.parent_name.as_deref()) {
+                (Some(ppid), Some(name)) => format!("{} ({})", ppid, name),
+                (Some(ppid), None) => ppid.to_string(),
+                (None, _) => "-".to_string(),
+            }
+        );
+        println!("Ancestry:    {}", entry.ancestry_display());
         println!(
-            "Has Parent:  {}",
-            if entry.has_parent { "Yes" } else { "No" }
+            "Connections: {}",
+            entry
+                .connections
+                .map_or_else(|| "-".to_string(), |count| count.to_string())
         );
         println!(
-            "Zombie:      {}",
+            "Defunct:     {}",
             if entry.is_zombie { "Yes ⚠️" } else { "No" }
         );
+        println!("Orphaned:    {}", orphan_display(&entry));
+        println!(
+            "Runaway CPU: {}",
+            if entry.is_runaway() {
+                "Yes ⚠️"
+            } else {
+                "No"
+            }
+        );
+        if entry.alerts.is_empty() {
+            println!("Health:      OK");
+        } else {
+            let alerts: Vec<String> = entry
+                .alerts
+                .iter()
+                .map(|alert| format!("{} ({})", alert.rule, alert.severity))
+                .collect();
+            let marker = match entry.worst_severity() {
+                Some(Severity::Critical) => " 🚨",
+                Some(Severity::Warning) => " ⚠️",
+                _ => "",
+            };
+            println!("Health:      {}{}", alerts.join(", "), marker);
+        }
         println!();
     }
 
     Ok(())
 }
 
+/// Whether an entry is orphaned, for `describe`; hosts that don't report sessions
+/// (remote macOS) can't tell for processes adopted by init
+fn orphan_display(entry: &app::PortEntry) -> &'static str {
+    if entry.is_orphan {
+        "Yes"
+    } else if entry.is_adopted() && entry.session_leader_alive.is_none() {
+        "Unknown (no session data from this host)"
+    } else {
+        "No"
+    }
+}
+
 /// Run the kill command
 fn run_kill(
     pid: Option<u32>,
     port: Option<u16>,
+    filter: Option<Query>,
     host: Option<String>,
     identity: Option<PathBuf>,
     force: bool,
 ) -> Result<()> {
-    // Validate that exactly one of pid or port is specified
+    // Validate that at most one of pid or port is specified, and something selects a process
     match (pid, port) {
-        (None, None) => {
-            anyhow::bail!("Either --pid or --port must be specified");
+        (None, None) if filter.is_none() => {
+            anyhow::bail!("Either --pid, --port or --filter must be specified");
         }
         (Some(_), Some(_)) => {
             anyhow::bail!("Cannot specify both --pid and --port. Please use only one.");
@@ -217,21 +428,19 @@ fn run_kill(
         _ => {}
     }
 
-    let entries = scan_ports(host.as_deref(), identity.as_ref())?;
+    let mut entries = scan_ports(host.as_deref(), identity.as_ref())?;
+    if let Some(query) = &filter {
+        config::load_rule_engine()?.evaluate_once(&mut entries);
+        entries.retain(|e| query.matches(e));
+    }
 
-    // Find matching entries
-    let matching_entries: Vec<_> = entries
+    // Find matching entries, one per process
+    let mut matching_entries: Vec<_> = entries
         .into_iter()
-        .filter(|e| {
-            if let Some(p) = pid {
-                e.pid == p
-            } else if let Some(p) = port {
-                e.port == p
-            } else {
-                false
-            }
-        })
+        .filter(|e| pid.is_none_or(|p| e.pid == p) && port.is_none_or(|p| e.port == p))
         .collect();
+    let mut seen = HashSet::new();
+    matching_entries.retain(|e| seen.insert(e.pid));
 
     if matching_entries.is_empty() {
         if let Some(p) = pid {
@@ -239,6 +448,7 @@ fn run_kill(
         } else if let Some(p) = port {
             anyhow::bail!("No process found on port {}", p);
         }
+        anyhow::bail!("No process found matching the filter");
     }
 
     if matching_entries.len() > 1 {
@@ -259,12 +469,7 @@ fn run_kill(
     // Kill the process
     if let Some(host_str) = host {
         // Remote kill
-        let mut config = RemoteConfig::parse(&host_str)?;
-        if let Some(key_path) = identity {
-            config = config.with_key(key_path);
-        }
-        let mut scanner = RemoteScanner::new(config);
-        scanner.connect()?;
+        let scanner = connect_remote(&host_str, identity.as_ref())?;
 
         if force {
             scanner.kill_process_force(pid_to_kill)?;
@@ -288,17 +493,220 @@ fn run_kill(
     Ok(())
 }
 
+/// Run the health command
+fn run_health(
+    engine: &RuleEngine,
+    min_severity: Severity,
+    filter: Option<Query>,
+    rule: Option<String>,
+    host: Option<String>,
+    identity: Option<PathBuf>,
+) -> Result<()> {
+    if let Some(name) = &rule {
+        if !engine.rules().iter().any(|r| &r.name == name) {
+            anyhow::bail!("No health rule named '{}'", name);
+        }
+    }
+
+    let mut entries = scan_ports(host.as_deref(), identity.as_ref())?;
+    if let Some(query) = &filter {
+        entries.retain(|e| query.matches(e));
+    }
+    engine.evaluate_once(&mut entries);
+
+    let mut found = 0;
+    for entry in &entries {
+        for alert in &entry.alerts {
+            if alert.severity < min_severity || rule.as_ref().is_some_and(|r| r != &alert.rule) {
+                continue;
+            }
+            if found == 0 {
+                println!(
+                    "{:<9} {:>5} {:<5} {:>7}  {:<20} RULE",
+                    "SEVERITY", "PORT", "PROTO", "PID", "PROCESS"
+                );
+            }
+            println!(
+                "{:<9} {:>5} {:<5} {:>7}  {:<20} {}",
+                alert.severity,
+                entry.port,
+                entry.protocol,
+                entry.pid,
+                entry.process_name,
+                alert.rule
+            );
+            found += 1;
+        }
+    }
+
+    if found == 0 {
+        println!("No health alerts ({} ports checked)", entries.len());
+    }
+
+    Ok(())
+}
+
+/// Parse the `watch --interval` value (a non-zero duration like "1s" or "5m")
+fn parse_interval(raw: &str) -> std::result::Result<Duration, String> {
+    match rules::parse_duration(raw) {
+        Some(interval) if !interval.is_zero() => Ok(interval),
+        Some(_) => Err("interval must be greater than zero".to_string()),
+        None => Err(format!("invalid duration '{}' (expected e.g. 1s, 5m)", raw)),
+    }
+}
+
+/// Run the watch command: scan continuously and print one JSON object per port change
+fn run_watch(
+    interval: Duration,
+    filter: Option<Query>,
+    host: Option<String>,
+    identity: Option<PathBuf>,
+) -> Result<()> {
+    let mut remote = match &host {
+        Some(host_str) => Some(connect_remote(host_str, identity.as_ref())?),
+        None => None,
+    };
+    let mut local = Scanner::new();
+    // A dropped session is restored on a later scan rather than ending the stream
+    let mut scan = || match &mut remote {
+        Some(scanner) => scanner.scan_reconnecting(),
+        None => Ok(local.scan()),
+    };
+
+    // The first scan is the baseline; only changes after it are reported. After a
+    // failed scan, changes are reported against the last scan that worked.
+    let mut previous: Option<Vec<app::PortEntry>> = None;
+    loop {
+        let mut out = io::stdout().lock();
+        let written = match scan() {
+            Ok(current) => {
+                let mut changes = Vec::new();
+                if let Some(previous) = &previous {
+                    changes = events::diff_entries(previous, &current, app::unix_now());
+                    if let Some(query) = &filter {
+                        changes.retain(|event| event_matches(event, query, previous, &current));
+                    }
+                }
+                previous = Some(current);
+                write_events(&mut out, &changes)
+            }
+            Err(e) => write_scan_error(&mut out, app::unix_now(), &e),
+        };
+        if let Err(e) = written {
+            // The consumer went away (e.g. `lsport watch | head`)
+            if e.kind() == io::ErrorKind::BrokenPipe {
+                return Ok(());
+            }
+            return Err(e).context("Failed to write events");
+        }
+        drop(out);
+        std::thread::sleep(interval);
+    }
+}
+
+/// Whether the entry behind an event matches a query (closed ports are
+/// looked up in the previous scan, everything else in the current one)
+fn event_matches(
+    event: &events::PortEvent,
+    query: &Query,
+    previous: &[app::PortEntry],
+    current: &[app::PortEntry],
+) -> bool {
+    let scan = match event.kind {
+        events::PortEventKind::Closed => previous,
+        _ => current,
+    };
+    scan.iter().any(|e| {
+        e.port == event.port
+            && e.protocol == event.protocol
+            && e.pid == event.pid
+            && query.matches(e)
+    })
+}
+
+/// Check a `--filter` query up front so typos are reported before the TUI starts
+fn validate_filter(raw: &str) -> std::result::Result<String, QueryError> {
+    Query::parse(raw).map(|_| raw.to_string())
+}
+
+/// Write events as newline-delimited JSON and flush so consumers see them immediately
+fn write_events(out: &mut impl Write, changes: &[events::PortEvent]) -> io::Result<()> {
+    for event in changes {
+        writeln!(out, "{}", event.to_json())?;
+    }
+    out.flush()
+}
+
+/// Report a failed scan as an `error` event, in the same format as port events
+fn write_scan_error(out: &mut impl Write, timestamp: u64, error: &anyhow::Error) -> io::Result<()> {
+    #[derive(serde::Serialize)]
+    struct ScanError {
+        timestamp: u64,
+        event: &'static str,
+        message: String,
+    }
+    let line = ScanError {
+        timestamp,
+        event: "error",
+        message: format!("{:#}", error),
+    };
+    let json = serde_json::to_string(&line).expect("scan errors always serialize");
+    writeln!(out, "{}", json)?;
+    out.flush()
+}
+
+/// Connect to a remote host for a one-off CLI command
+fn connect_remote(host: &str, identity: Option<&PathBuf>) -> Result<RemoteScanner> {
+    if host.starts_with('@') {
+        bail!("{} is a host group; commands take a single host", host);
+    }
+    let config = remote_config(
+        &config::Config::load()?,
+        &Inventory::load()?,
+        host,
+        identity.cloned(),
+    )?;
+    let mut scanner = RemoteScanner::new(config);
+    scanner.connect()?;
+    Ok(scanner)
+}
+
+/// Run the config command
+fn run_config(action: ConfigCommand, path: Option<&Path>, out: &mut impl Write) -> Result<()> {
+    let Some(path) = path else {
+        anyhow::bail!("Cannot locate the config directory (set XDG_CONFIG_HOME or HOME)");
+    };
+
+    match action {
+        ConfigCommand::Path => writeln!(out, "{}", path.display())?,
+        ConfigCommand::Show => {
+            let config = config::Config::load_from(path)?;
+            if path.exists() {
+                writeln!(out, "# {}", path.display())?;
+            } else {
+                writeln!(out, "# {} (not found, showing defaults)", path.display())?;
+            }
+            write!(out, "{}", config.to_toml()?)?;
+        }
+        ConfigCommand::Validate => {
+            if !path.exists() {
+                writeln!(out, "No config file at {} (using defaults)", path.display())?;
+                return Ok(());
+            }
+            config::Config::load_from(path)?
+                .validate()
+                .with_context(|| format!("Invalid config file {}", path.display()))?;
+            writeln!(out, "✓ {} is valid", path.display())?;
+        }
+    }
+    Ok(())
+}
+
 /// Scan ports (local or remote)
 fn scan_ports(host: Option<&str>, identity: Option<&PathBuf>) -> Result<Vec<app::PortEntry>> {
     if let Some(host_str) = host {
         // Remote scan
-        let mut config = RemoteConfig::parse(host_str)?;
-        if let Some(key_path) = identity {
-            config = config.with_key(key_path.clone());
-        }
-        let mut scanner = RemoteScanner::new(config);
-        scanner.connect()?;
-        Ok(scanner.scan()?)
+        connect_remote(host_str, identity)?.scan()
     } else {
         // Local scan
         let mut scanner = Scanner::new();
@@ -354,116 +762,334 @@ fn kill_process_force(pid: u32) -> Result<()> {
     }
 }
 
-/// Scanner mode - either local or remote
-enum ScannerMode {
-    Local(Box<Scanner>),
-    Remote(RemoteScanner),
-}
-
-impl ScannerMode {
-    fn scan(&mut self) -> Vec<app::PortEntry> {
-        match self {
-            ScannerMode::Local(scanner) => scanner.scan(),
-            ScannerMode::Remote(scanner) => scanner.scan().unwrap_or_default(),
-        }
-    }
-
-    fn kill_process(&mut self, pid: u32) -> Result<()> {
-        match self {
-            ScannerMode::Local(scanner) => scanner.kill_process(pid),
-            ScannerMode::Remote(scanner) => scanner.kill_process(pid),
-        }
-    }
+/// Pick the color theme: `--theme`, then `NO_COLOR`, then `defaults.theme`,
+/// reduced to the colors the terminal supports
+fn load_theme(config: &config::Config, name: Option<&str>) -> Result<Theme> {
+    let theme = match name {
+        Some(name) => config.theme(name)?,
+        None if theme::no_color_requested() => return Ok(Theme::no_color()),
+        None => config.theme(&config.defaults.theme)?,
+    };
+    Ok(theme.for_terminal(ColorSupport::detect()))
 }
 
 /// Main application loop implementing Model-View-Update pattern
 fn run(mut terminal: Terminal<CrosstermBackend<io::Stdout>>, args: &Args) -> Result<()> {
-    use std::time::Instant;
-
     // Initialize application state (Model)
     let mut app = App::new();
-
-    // Calculate scan interval from args
-    let scan_interval = Duration::from_secs(args.scan_interval);
-
-    // Initialize the scanner (local or remote)
-    let mut scanner_mode = if let Some(host_str) = &args.host {
-        // Remote mode
-        let mut config = RemoteConfig::parse(host_str)?;
-        if let Some(key_path) = &args.identity {
-            config = config.with_key(key_path.clone());
+    let config = config::Config::load()?;
+    app.rules = config.rule_engine()?;
+    app.keymap = config.keymap()?;
+    app.theme = load_theme(&config, args.theme.as_deref())?;
+    app.pins = Pins::load()?;
+    app.views = Views::load()?;
+    app.inventory = Inventory::load()?;
+    let notifier = Notifier::new(config.notify.clone());
+    app.apply_config(config);
+    apply_startup_args(&mut app, args)?;
+
+    // Flags take precedence over per-host options, which take precedence over [defaults]
+    let hosts: Vec<String> = if args.host.is_empty() {
+        app.config.defaults.host.iter().cloned().collect()
+    } else {
+        args.host.clone()
+    };
+    // `@group` opens every host in an inventory group
+    let hosts = app.inventory.expand(&hosts)?;
+
+    // One tab per host, or a single local tab
+    let mut tabs: Option<Tabs> = None;
+    if hosts.is_empty() {
+        app.scan_interval = Duration::from_secs(
+            args.scan_interval
+                .unwrap_or_else(|| app.config.scan_interval_for(None)),
+        );
+        if let Some(path) = &args.event_log {
+            app.events.write_to(path)?;
         }
-
-        app.set_remote_host(Some(config.display()));
-        app.set_info(format!("Connecting to {}...", config.display()));
-
-        // Draw connecting message
-        terminal.draw(|frame| ui::render(frame, &app))?;
-
-        let mut remote_scanner = RemoteScanner::new(config.clone());
-        match remote_scanner.connect() {
-            Ok(()) => {
-                app.set_success(format!("Connected to {}", config.display()));
+        tabs = Some(Tabs::new(Tab::new(app, ScannerMode::Local(Box::default()))));
+    } else {
+        let mut apps = vec![app];
+        for _ in 1..hosts.len() {
+            let mut app = apps[0].new_tab();
+            apply_startup_args(&mut app, args)?;
+            apps.push(app);
+        }
+        for (mut app, host) in apps.into_iter().zip(&hosts) {
+            let config = remote_config(&app.config, &app.inventory, host, args.identity.clone())?;
+            setup_remote_app(&mut app, host, &config, args)?;
+            app.set_info(format!("Connecting to {}...", config.display()));
+
+            // Draw connecting message
+            terminal.draw(|frame| ui::render(frame, &app))?;
+
+            let mut remote_scanner = RemoteScanner::new(config.clone());
+            match remote_scanner.connect() {
+                Ok(()) => {
+                    app.set_success(format!("Connected to {}", config.display()));
+                }
+                Err(e) => {
+                    app.set_error(format!("Connection failed: {}", e));
+                    // Still allow viewing the error
+                }
             }
-            Err(e) => {
-                app.set_error(format!("Connection failed: {}", e));
-                // Still allow viewing the error
+
+            let tab = Tab::new(app, ScannerMode::Remote(Box::new(remote_scanner)));
+            match &mut tabs {
+                Some(tabs) => tabs.push(tab),
+                None => tabs = Some(Tabs::new(tab)),
             }
         }
-
-        ScannerMode::Remote(remote_scanner)
-    } else {
-        // Local mode
-        ScannerMode::Local(Box::default())
+    }
+    let Some(mut tabs) = tabs else {
+        unreachable!("a tab is opened for every host, or a local one");
     };
-
-    // Perform initial scan
-    let entries = scanner_mode.scan();
-    app.update_entries(entries);
-
-    // Track last scan time for throttling
-    let mut last_scan = Instant::now();
+    // Start on the first host, or on all of them
+    tabs.select(0);
+    if args.fleet {
+        let mut app = tabs.active().app.new_tab();
+        apply_startup_args(&mut app, args)?;
+        tabs.toggle_fleet(app);
+    }
 
     // Main event loop
     loop {
         // VIEW: Render the current state
-        terminal.draw(|frame| ui::render(frame, &app))?;
+        terminal.draw(|frame| ui::render(frame, &tabs.active().app))?;
 
         // Check if we should quit
-        if app.should_quit {
+        if tabs.active().app.should_quit {
             break;
         }
 
         // UPDATE: Handle events with short poll for responsive input
         if event::poll(POLL_RATE)? {
-            if let Event::Key(key) = event::read()? {
+            let tab = tabs.active_mut();
+            match event::read()? {
                 // Only handle key press events (not release)
-                if key.kind == KeyEventKind::Press {
-                    handle_key_event(&mut app, key.code, key.modifiers, &mut scanner_mode);
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    handle_key_event(&mut tab.app, key.code, key.modifiers, &tab.scanner);
+                }
+                Event::Mouse(mouse) => {
+                    let size = terminal.size()?;
+                    let area = Rect::new(0, 0, size.width, size.height);
+                    handle_mouse_event(&mut tab.app, mouse, area);
                 }
+                _ => {}
             }
         }
 
-        // TICK: Update data only at scan interval (not every poll)
-        if last_scan.elapsed() >= scan_interval {
-            let entries = scanner_mode.scan();
-            app.update_entries(entries);
-            last_scan = Instant::now();
+        // Switch, open or close tabs as asked by the key just handled
+        if let Some(request) = tabs.active_mut().app.tab_request.take() {
+            handle_tab_request(&mut terminal, &mut tabs, request, args)?;
+        }
+
+        // TICK: Show finished scans and start the ones that are due, without waiting on any host
+        tabs.scan_due();
+
+        for tab in tabs.iter_mut() {
+            // Tell the user about changes to pinned ports, on any host
+            deliver_notifications(&mut tab.app, &notifier);
+
+            // Maybe clear old status messages
+            tab.app.maybe_clear_status();
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply the `--view` and `--filter` flags that every startup tab shares
+fn apply_startup_args(app: &mut App, args: &Args) -> Result<()> {
+    if let Some(name) = &args.view {
+        let view = app.views.find(name)?.clone();
+        app.apply_view(&view);
+    }
+    if let Some(filter) = &args.filter {
+        app.filter = filter.clone();
+    }
+    Ok(())
+}
+
+/// Connection settings for `host` (an inventory label or an address), with the given
+/// SSH key, else the inventory's, else the one configured for its address
+fn remote_config(
+    config: &config::Config,
+    inventory: &Inventory,
+    host: &str,
+    identity: Option<PathBuf>,
+) -> Result<RemoteConfig> {
+    let mut remote = inventory.remote_config(host)?;
+    let key_path = identity
+        .or_else(|| remote.key_path.take())
+        .or_else(|| config.identity_for(inventory.address(host)));
+    if let Some(key_path) = key_path {
+        remote = remote.with_key(key_path);
+    }
+    Ok(remote)
+}
+
+/// Name of a host in the tab bar: its inventory label, or its address
+fn host_name(inventory: &Inventory, host: &str, config: &RemoteConfig) -> String {
+    match inventory.get(host) {
+        Some(entry) => entry.label.clone(),
+        None => config.display(),
+    }
+}
+
+/// Point a tab's app at a remote host: its name, scan interval and event log
+fn setup_remote_app(app: &mut App, host: &str, config: &RemoteConfig, args: &Args) -> Result<()> {
+    let name = host_name(&app.inventory, host, config);
+    app.set_remote_host(Some(name.clone()));
+    let scan_interval = args.scan_interval.unwrap_or_else(|| {
+        app.config
+            .scan_interval_for(Some(app.inventory.address(host)))
+    });
+    app.scan_interval = Duration::from_secs(scan_interval);
+    if let Some(path) = &args.event_log {
+        app.events.write_to(path)?;
+        app.events.label_host(name);
+    }
+    Ok(())
+}
+
+/// Carry out a tab change asked for by a key, click or palette command
+fn handle_tab_request(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    tabs: &mut Tabs,
+    request: TabRequest,
+    args: &Args,
+) -> Result<()> {
+    match request {
+        TabRequest::Next => tabs.next(),
+        TabRequest::Previous => tabs.previous(),
+        TabRequest::Select(index) => {
+            if !tabs.select(index) {
+                tabs.active_mut()
+                    .app
+                    .set_info(format!("No tab {}", index + 1));
+            }
+        }
+        TabRequest::Close => match tabs.close() {
+            Some(closed) => {
+                let message = format!("Closed {}", closed.app.tab_label());
+                tabs.active_mut().app.set_info(message);
+            }
+            None => tabs.active_mut().app.set_info("Can't close the last host"),
+        },
+        TabRequest::ToggleFleet => {
+            let app = tabs.active().app.new_tab();
+            if !tabs.toggle_fleet(app) {
+                tabs.active_mut().app.set_info("Closed the fleet view");
+            }
         }
+        TabRequest::Open { host, identity } => open_tab(terminal, tabs, &host, identity, args)?,
+    }
+    Ok(())
+}
 
-        // Maybe clear old status messages
-        app.maybe_clear_status();
+/// Connect to a host in a new tab, or switch to its tab if it's already open
+fn open_tab(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    tabs: &mut Tabs,
+    host: &str,
+    identity: Option<PathBuf>,
+    args: &Args,
+) -> Result<()> {
+    let origin = &mut tabs.active_mut().app;
+    let config = match remote_config(&origin.config, &origin.inventory, host, identity) {
+        Ok(config) => config,
+        Err(e) => {
+            origin.set_error(format!("Invalid host format: {}", e));
+            origin.exit_connect_mode();
+            return Ok(());
+        }
+    };
+    let name = host_name(&origin.inventory, host, &config);
+    if let Some(index) = tabs.find(&name) {
+        tabs.active_mut().app.exit_connect_mode();
+        tabs.select(index);
+        return Ok(());
     }
 
+    // Draw connecting message
+    let origin = &mut tabs.active_mut().app;
+    origin.set_info(format!("Connecting to {}...", config.display()));
+    terminal.draw(|frame| ui::render(frame, &tabs.active().app))?;
+
+    let mut remote_scanner = RemoteScanner::new(config.clone());
+    let origin = &mut tabs.active_mut().app;
+    if let Err(e) = remote_scanner.connect() {
+        // Stay in the connect prompt so the host or key can be corrected
+        origin.set_error(format!("Connection failed: {}", e));
+        return Ok(());
+    }
+    origin.exit_connect_mode();
+    // Offer the host first in the picker next time
+    if let Err(e) = origin.inventory.remember(host) {
+        origin.set_error(format!("Failed to save recent hosts: {:#}", e));
+    }
+
+    let mut app = origin.new_tab();
+    setup_remote_app(&mut app, host, &config, args)?;
+    app.set_success(format!("Connected to {}", config.display()));
+    tabs.push(Tab::new(app, ScannerMode::Remote(Box::new(remote_scanner))));
     Ok(())
 }
 
+/// Send queued pinned-port notifications to the terminal and the notify command
+fn deliver_notifications(app: &mut App, notifier: &Notifier) {
+    for event in std::mem::take(&mut app.notifications) {
+        if let Err(e) = notifier.notify(&event, app.remote_host.as_deref(), &mut stdout()) {
+            app.set_error(format!("Notification failed: {:#}", e));
+        }
+    }
+}
+
+/// Handle mouse clicks and wheel scrolling on a screen of size `area`
+fn handle_mouse_event(app: &mut App, mouse: MouseEvent, area: Rect) {
+    // Prompts and pickers are keyboard-driven; a click only closes the help popup
+    if app.show_help {
+        if let MouseEventKind::Down(_) = mouse.kind {
+            app.show_help = false;
+        }
+        return;
+    }
+    if app.filter_mode
+        || app.connect_mode
+        || app.view_name_mode
+        || app.show_view_picker
+        || app.command_mode
+        || app.pending_bulk.is_some()
+    {
+        return;
+    }
+
+    match mouse.kind {
+        MouseEventKind::ScrollUp => app.scroll_selection(-1),
+        MouseEventKind::ScrollDown => app.scroll_selection(1),
+        MouseEventKind::Down(MouseButton::Left) => {
+            match ui::click_target(app, area, mouse.column, mouse.row) {
+                Some(ClickTarget::Row(index)) => app.selected_index = index,
+                Some(ClickTarget::Header(column)) => app.sort_by_column(column),
+                Some(ClickTarget::Hint(Action::Help)) => app.toggle_help(),
+                Some(ClickTarget::Hint(Action::Quit)) => app.quit(),
+                Some(ClickTarget::Hint(Action::SelectTab(n))) => {
+                    app.tab_request = Some(TabRequest::Select(usize::from(n) - 1));
+                }
+                Some(ClickTarget::Hint(_)) | None => {}
+            }
+        }
+        _ => {}
+    }
+}
+
 /// Handle keyboard input events
 fn handle_key_event(
     app: &mut App,
     code: KeyCode,
     modifiers: KeyModifiers,
-    scanner: &mut ScannerMode,
+    scanner: &SharedScanner,
 ) {
     // If help is shown, close it on any key
     if app.show_help {
@@ -473,267 +1099,507 @@ fn handle_key_event(
 
     // Handle filter mode separately
     if app.filter_mode {
-        handle_filter_input(app, code);
+        handle_filter_input(app, code, modifiers);
         return;
     }
 
     // Handle connect mode separately
     if app.connect_mode {
-        handle_connect_input(app, code, scanner);
+        handle_connect_input(app, code, modifiers);
         return;
     }
 
-    match code {
-        // Quit commands
-        KeyCode::Char('q' | 'Q') => {
-            app.quit();
-        }
-        // Ctrl+C to quit
-        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
-            app.quit();
-        }
-        // Help toggle
-        KeyCode::Char('?') => {
-            app.toggle_help();
-        }
+    if app.view_name_mode {
+        handle_view_name_input(app, code, modifiers);
+        return;
+    }
+
+    if app.show_view_picker {
+        handle_view_picker_input(app, code, modifiers);
+        return;
+    }
+
+    if app.command_mode {
+        handle_command_input(app, code, modifiers, scanner);
+        return;
+    }
+
+    if app.pending_bulk.is_some() {
+        handle_confirm_input(app, code, modifiers, scanner);
+        return;
+    }
+
+    if let Some(action) = app.keymap.action(KeyContext::Table, code, modifiers) {
+        run_action(app, action, scanner);
+    }
+}
+
+/// Do what a table key (or a palette command named after it) is bound to
+fn run_action(app: &mut App, action: Action, scanner: &SharedScanner) {
+    match action {
+        Action::Quit => app.quit(),
+        Action::Help => app.toggle_help(),
         // Navigation
-        KeyCode::Up | KeyCode::Char('k') => {
-            app.select_previous();
-        }
-        KeyCode::Down | KeyCode::Char('j') => {
-            app.select_next();
-        }
-        // Page navigation
-        KeyCode::PageUp => {
+        Action::Up => app.select_previous(),
+        Action::Down => app.select_next(),
+        Action::PageUp => {
             for _ in 0..10 {
                 app.select_previous();
             }
         }
-        KeyCode::PageDown => {
+        Action::PageDown => {
             for _ in 0..10 {
                 app.select_next();
             }
         }
-        // Home/End
-        KeyCode::Home => {
-            app.selected_index = 0;
-        }
-        KeyCode::End => {
-            if !app.entries.is_empty() {
-                app.selected_index = app.entries.len() - 1;
-            }
-        }
-        // Kill selected process
-        KeyCode::Enter => {
-            handle_kill(app, scanner);
-        }
-        // Alternative kill with 'k' + Ctrl
-        KeyCode::Char('K') if modifiers.contains(KeyModifiers::CONTROL) => {
-            handle_kill(app, scanner);
-        }
-        // Sort: cycle through columns (legacy)
-        KeyCode::Char('s') => {
-            app.cycle_sort_column();
-        }
-        // Reverse sort order (legacy)
-        KeyCode::Char('r') => {
-            app.toggle_sort_order();
-        }
-        // K9s-style sorting: Shift + letter or number keys
-        // Press same key again to toggle ascending/descending
-        KeyCode::Char('P') => app.sort_by_column(SortColumn::Port), // Shift+P = Port
-        KeyCode::Char('O') => app.sort_by_column(SortColumn::Protocol), // Shift+O = prOtocol
-        KeyCode::Char('I') => app.sort_by_column(SortColumn::Pid),  // Shift+I = pId
-        KeyCode::Char('N') => app.sort_by_column(SortColumn::ProcessName), // Shift+N = Name
-        KeyCode::Char('C') => app.sort_by_column(SortColumn::CpuUsage), // Shift+C = Cpu
-        KeyCode::Char('M') => app.sort_by_column(SortColumn::MemoryUsage), // Shift+M = Memory
-        // Number keys for quick sort
-        KeyCode::Char('1') => app.sort_by_column(SortColumn::Port),
-        KeyCode::Char('2') => app.sort_by_column(SortColumn::Protocol),
-        KeyCode::Char('3') => app.sort_by_column(SortColumn::Pid),
-        KeyCode::Char('4') => app.sort_by_column(SortColumn::ProcessName),
-        KeyCode::Char('5') => app.sort_by_column(SortColumn::CpuUsage),
-        KeyCode::Char('6') => app.sort_by_column(SortColumn::MemoryUsage),
-        // Filter mode
-        KeyCode::Char('/') => {
-            app.enter_filter_mode();
-        }
-        // Connect mode (use 'c' for connect, but not Ctrl+C which is quit)
-        KeyCode::Char('c') if !modifiers.contains(KeyModifiers::CONTROL) => {
-            app.enter_connect_mode();
-        }
-        // Disconnect from remote
-        KeyCode::Char('d' | 'D') if app.remote_host.is_some() => {
-            handle_disconnect(app, scanner);
-        }
-        // Clear filter or close help
-        KeyCode::Esc => {
-            if !app.filter.is_empty() {
-                app.clear_filter();
-            }
+        Action::First => app.selected_index = 0,
+        Action::Last if app.row_count() > 0 => {
+            app.selected_index = app.row_count() - 1;
         }
+        Action::Expand => app.expand_selected(),
+        Action::Collapse => app.collapse_selected(),
+        // Enter on a group header expands or collapses it
+        Action::Kill if app.selected_group().is_some() => app.toggle_group_selected(),
+        Action::Kill if !app.marked_entries().is_empty() => app.request_bulk(BulkKind::Kill),
+        Action::Kill => handle_kill(app, scanner),
+        Action::Pause => handle_pause(app, scanner),
+        Action::Mark => app.toggle_mark_selected(),
+        Action::MarkAll => app.toggle_mark_all(),
+        // Sort: cycle through columns / reverse order (legacy)
+        Action::CycleSort => app.cycle_sort_column(),
+        Action::ReverseSort => app.toggle_sort_order(),
+        // K9s-style sorting: same key again toggles ascending/descending
+        Action::SortBy(column) => app.sort_by_column(column),
+        Action::Details => app.toggle_details(),
+        Action::DetailsLayout => app.toggle_details_layout(),
+        Action::Group => app.cycle_group_by(),
+        Action::Pin => app.toggle_pin_selected(),
+        Action::Events => app.toggle_events(),
+        Action::EventsOlder if app.show_events => app.scroll_events_older(),
+        Action::EventsNewer if app.show_events => app.scroll_events_newer(),
+        Action::Filter => app.enter_filter_mode(),
+        Action::ClearFilter if !app.filter.is_empty() => app.clear_filter(),
+        // Saved views: pick, save current filter + sort, cycle
+        Action::ViewPicker => app.open_view_picker(),
+        Action::SaveView => app.enter_view_name_mode(),
+        Action::NextView => app.cycle_view(),
+        Action::Connect => app.enter_connect_mode(),
+        Action::Command => app.enter_command_mode(),
+        // With several tabs open, disconnecting closes the current one
+        Action::Disconnect if app.tab_labels.len() > 1 => app.tab_request = Some(TabRequest::Close),
+        Action::Disconnect if app.remote_host.is_some() => handle_disconnect(app, scanner),
+        Action::NextTab => app.tab_request = Some(TabRequest::Next),
+        Action::PreviousTab => app.tab_request = Some(TabRequest::Previous),
+        Action::SelectTab(n) => app.tab_request = Some(TabRequest::Select(usize::from(n) - 1)),
+        Action::Fleet => app.tab_request = Some(TabRequest::ToggleFleet),
         _ => {}
     }
 }
 
-/// Handle input while in filter mode
-fn handle_filter_input(app: &mut App, code: KeyCode) {
+/// What a key press means in a text prompt
+enum PromptKey {
+    /// A key bound in the prompt keymap
+    Action(Action),
+    /// A character to insert
+    Char(char),
+    /// Anything else
+    Ignored,
+}
+
+/// Look up a key press in the prompt keymap, falling back to inserting the character
+fn prompt_key(app: &App, code: KeyCode, modifiers: KeyModifiers) -> PromptKey {
+    if let Some(action) = app.keymap.action(KeyContext::Prompt, code, modifiers) {
+        return PromptKey::Action(action);
+    }
     match code {
-        KeyCode::Enter => {
-            app.exit_filter_mode();
-        }
-        KeyCode::Esc => {
-            app.filter.clear();
-            app.exit_filter_mode();
-        }
-        KeyCode::Backspace => {
-            app.filter_pop();
+        KeyCode::Char(c) if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            PromptKey::Char(c)
         }
-        KeyCode::Char(c) => {
-            app.filter_push(c);
+        _ => PromptKey::Ignored,
+    }
+}
+
+/// Handle keyboard input in the command palette
+fn handle_command_input(
+    app: &mut App,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    scanner: &SharedScanner,
+) {
+    match prompt_key(app, code, modifiers) {
+        PromptKey::Action(Action::Confirm) => {
+            let input = std::mem::take(&mut app.command_input);
+            app.exit_command_mode();
+            match palette::Command::parse(&input) {
+                Ok(command) => run_command(app, command, scanner),
+                Err(e) => app.set_error(format!("{:#}", e)),
+            }
         }
+        PromptKey::Action(Action::Cancel) => app.exit_command_mode(),
+        PromptKey::Action(Action::DeleteChar) => app.command_pop(),
+        PromptKey::Action(Action::Skip) => app.complete_command(),
+        PromptKey::Char(c) => app.command_push(c),
         _ => {}
     }
 }
 
-/// Handle input while in connect mode
-fn handle_connect_input(app: &mut App, code: KeyCode, scanner: &mut ScannerMode) {
-    match code {
-        KeyCode::Enter => {
-            if app.connect_key_mode {
-                // Second Enter - attempt connection
-                handle_connect(app, scanner);
-            } else if !app.connect_input.is_empty() {
-                // First Enter - ask for SSH key (optional)
-                app.enter_connect_key_mode();
+/// Run a palette command
+fn run_command(app: &mut App, command: palette::Command, scanner: &SharedScanner) {
+    use palette::Command;
+
+    match command {
+        Command::Action(action) => run_action(app, action, scanner),
+        Command::Kill(target) => match app.find_port_or_pid(target).cloned() {
+            Some(entry) => kill_entry(app, &entry, scanner),
+            None => app.set_error(format!("No port or PID {} in the table", target)),
+        },
+        Command::Signal(signal, None) if !app.marked_entries().is_empty() => {
+            app.request_bulk(BulkKind::Signal(signal));
+        }
+        Command::Signal(signal, target) => {
+            let entry = match target {
+                Some(target) => app.find_port_or_pid(target),
+                None => app.selected_entry(),
+            };
+            let Some(entry) = entry.cloned() else {
+                match target {
+                    Some(target) => {
+                        app.set_error(format!("No port or PID {} in the table", target))
+                    }
+                    None => app.set_info("No process selected"),
+                }
+                return;
+            };
+            let result = tabs::lock_idle(scanner).and_then(|mut scanner| {
+                scanner.send_signal(entry.pid, signal, entry.host.as_deref())
+            });
+            match result {
+                Ok(()) => app.set_success(format!(
+                    "Sent SIG{} to '{}' (PID: {})",
+                    signal.name(),
+                    entry.process_name,
+                    entry.pid
+                )),
+                Err(e) => app.set_error(format!("{}", e)),
+            }
+        }
+        Command::Connect(host) => {
+            app.connect_input = host.clone();
+            app.connect_key_input.clear();
+            handle_connect(app, host);
+        }
+        Command::Filter(query) => match Query::parse(&query) {
+            Ok(_) => {
+                app.filter = query;
+                app.exit_filter_mode();
+            }
+            Err(e) => app.set_error(format!("Invalid filter: {}", e)),
+        },
+        Command::Sort(column, order) => {
+            app.sort_by_column(column);
+            // Sorting by the same column again toggles the order
+            if order.is_some_and(|order| order != app.sort_order) {
+                app.sort_by_column(column);
             }
         }
-        KeyCode::Esc => {
-            if app.connect_key_mode {
-                // Go back to host input
-                app.connect_key_mode = false;
-                app.connect_key_input.clear();
+        Command::View(name) => match app.views.find(&name) {
+            Ok(view) => {
+                let view = view.clone();
+                app.apply_view(&view);
+            }
+            Err(e) => app.set_error(e.to_string()),
+        },
+        Command::Group(group_by) => app.set_group_by(group_by),
+        Command::Pin(port) => app.set_pinned(app.command_pin_host().as_deref(), port, true),
+        Command::Unpin(port) => app.set_pinned(app.command_pin_host().as_deref(), port, false),
+        Command::Export(format, path) => {
+            let path = config::expand_home(&path);
+            // Only the marked rows, if any are marked
+            let marked: Vec<app::PortEntry> = app.marked_entries().into_iter().cloned().collect();
+            let (rows, what) = if marked.is_empty() {
+                (&app.entries, "rows")
             } else {
-                // Cancel connect mode
-                app.exit_connect_mode();
+                (&marked, "marked rows")
+            };
+            let result = std::fs::File::create(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|file| {
+                    let mut out = io::BufWriter::new(file);
+                    export::write_entries(rows, format, &mut out)?;
+                    Ok(out.flush()?)
+                });
+            match result {
+                Ok(()) => app.set_success(format!(
+                    "Exported {} {} to {}",
+                    rows.len(),
+                    what,
+                    path.display()
+                )),
+                Err(e) => app.set_error(format!("Failed to export to {}: {:#}", path.display(), e)),
             }
         }
-        KeyCode::Backspace => {
-            app.connect_pop();
+    }
+}
+
+/// Handle input while typing a name to save the current view under
+fn handle_view_name_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    match prompt_key(app, code, modifiers) {
+        PromptKey::Action(Action::Confirm) => app.save_view_from_input(),
+        PromptKey::Action(Action::Cancel) => app.exit_view_name_mode(),
+        PromptKey::Action(Action::DeleteChar) => {
+            app.view_name_input.pop();
+        }
+        PromptKey::Char(c) => app.view_name_input.push(c),
+        _ => {}
+    }
+}
+
+/// Handle input while the view picker is open
+fn handle_view_picker_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    match app.keymap.action(KeyContext::Picker, code, modifiers) {
+        Some(Action::Up) => app.move_view_picker(-1),
+        Some(Action::Down) => app.move_view_picker(1),
+        Some(Action::Confirm) => app.pick_view(),
+        Some(Action::Delete) => app.delete_picked_view(),
+        Some(Action::Cancel) => app.show_view_picker = false,
+        _ => {}
+    }
+}
+
+/// Handle input while in filter mode
+fn handle_filter_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    match prompt_key(app, code, modifiers) {
+        PromptKey::Action(Action::Confirm) => app.exit_filter_mode(),
+        PromptKey::Action(Action::Cancel) => {
+            app.filter.clear();
+            app.exit_filter_mode();
+        }
+        PromptKey::Action(Action::DeleteChar) => app.filter_pop(),
+        PromptKey::Char(c) => app.filter_push(c),
+        _ => {}
+    }
+}
+
+/// Handle input while in connect mode
+fn handle_connect_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    if app.connect_key_mode {
+        handle_connect_key_input(app, code, modifiers);
+        return;
+    }
+    match prompt_key(app, code, modifiers) {
+        PromptKey::Action(Action::Confirm) => match app.picked_host() {
+            // A host typed for the first time - ask for SSH key (optional)
+            Some(choice) if choice.is_new() => app.enter_connect_key_mode(),
+            // Inventory and recent hosts already know their key
+            Some(choice) => handle_connect(app, choice.name),
+            None => {}
+        },
+        PromptKey::Action(Action::Cancel) => app.exit_connect_mode(),
+        PromptKey::Action(Action::DeleteChar) => app.connect_pop(),
+        PromptKey::Action(Action::Skip) => {
+            // Skip the SSH key and connect directly
+            if let Some(choice) = app.picked_host() {
+                handle_connect(app, choice.name);
+            }
         }
-        KeyCode::Tab if !app.connect_key_mode && !app.connect_input.is_empty() => {
-            // Tab to skip SSH key and connect directly
-            handle_connect(app, scanner);
+        PromptKey::Char(c) => app.connect_push(c),
+        // Letters search, so only arrow-style picker keys move the highlight
+        PromptKey::Ignored => match app.keymap.action(KeyContext::Picker, code, modifiers) {
+            Some(Action::Up) => app.move_connect_picker(-1),
+            Some(Action::Down) => app.move_connect_picker(1),
+            _ => {}
+        },
+        PromptKey::Action(_) => {}
+    }
+}
+
+/// Handle input while typing the SSH key for a new host (second step of connect)
+fn handle_connect_key_input(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    match prompt_key(app, code, modifiers) {
+        PromptKey::Action(Action::Confirm) => {
+            let host = app.connect_input.trim().to_string();
+            handle_connect(app, host);
         }
-        KeyCode::Char(c) => {
-            app.connect_push(c);
+        PromptKey::Action(Action::Cancel) => {
+            // Go back to host input
+            app.connect_key_mode = false;
+            app.connect_key_input.clear();
         }
+        PromptKey::Action(Action::DeleteChar) => app.connect_pop(),
+        PromptKey::Char(c) => app.connect_push(c),
         _ => {}
     }
 }
 
-/// Handle connection to remote host
-fn handle_connect(app: &mut App, scanner: &mut ScannerMode) {
-    let host_str = app.connect_input.trim().to_string();
+/// Handle connection to remote host: the main loop opens a tab for it
+fn handle_connect(app: &mut App, host_str: String) {
     if host_str.is_empty() {
         app.set_error("Host cannot be empty");
         app.exit_connect_mode();
         return;
     }
 
-    app.set_info(format!("Connecting to {}...", host_str));
-
-    // Parse remote config
-    let mut config = match RemoteConfig::parse(&host_str) {
-        Ok(cfg) => cfg,
-        Err(e) => {
-            app.set_error(format!("Invalid host format: {}", e));
-            app.exit_connect_mode();
-            return;
-        }
-    };
-
-    // Set SSH key if provided
-    if !app.connect_key_input.is_empty() {
-        let key_path = PathBuf::from(app.connect_key_input.trim());
-        config = config.with_key(key_path);
+    if let Err(e) = app.inventory.remote_config(&host_str) {
+        app.set_error(format!("Invalid host format: {}", e));
+        app.exit_connect_mode();
+        return;
     }
 
-    // Attempt connection
-    let mut remote_scanner = RemoteScanner::new(config.clone());
-    match remote_scanner.connect() {
-        Ok(()) => {
-            // Success - switch to remote mode
-            *scanner = ScannerMode::Remote(remote_scanner);
-            app.set_remote_host(Some(config.display()));
-            app.set_success(format!("Connected to {}", config.display()));
-            app.exit_connect_mode();
-
-            // Perform initial scan
-            let entries = scanner.scan();
-            app.update_entries(entries);
-        }
-        Err(e) => {
-            app.set_error(format!("Connection failed: {}", e));
-            // Don't exit connect mode, allow user to retry
-        }
-    }
+    // The SSH key if one was entered, otherwise the one configured for this host
+    let identity = Some(app.connect_key_input.trim())
+        .filter(|key| !key.is_empty())
+        .map(PathBuf::from);
+    app.tab_request = Some(TabRequest::Open {
+        host: host_str,
+        identity,
+    });
 }
 
 /// Handle disconnection from remote host
-fn handle_disconnect(app: &mut App, scanner: &mut ScannerMode) {
+fn handle_disconnect(app: &mut App, scanner: &SharedScanner) {
+    let mut scanner = match tabs::lock_idle(scanner) {
+        Ok(scanner) => scanner,
+        Err(e) => return app.set_error(format!("{}", e)),
+    };
     app.disconnect();
     // Switch back to local scanner
     *scanner = ScannerMode::Local(Box::default());
 
     // Perform initial scan
-    let entries = scanner.scan();
+    let entries = scanner.scan().unwrap_or_default();
+    app.scan_error = None;
     app.update_entries(entries);
 }
 
 /// Handle the kill command for the selected process
-fn handle_kill(app: &mut App, scanner: &mut ScannerMode) {
-    if let Some(entry) = app.selected_entry() {
-        let pid = entry.pid;
-        let process_name = entry.process_name.clone();
-        let port = entry.port;
-
-        // Attempt to kill the process
-        match scanner.kill_process(pid) {
-            Ok(()) => {
-                app.set_success(format!(
-                    "Killed '{}' (PID: {}) on port {}",
-                    process_name, pid, port
-                ));
+fn handle_kill(app: &mut App, scanner: &SharedScanner) {
+    if let Some(entry) = app.selected_entry().cloned() {
+        kill_entry(app, &entry, scanner);
+    } else {
+        app.set_info("No process selected");
+    }
+}
+
+/// Pause or resume the marked processes (after confirmation) or the selected one
+fn handle_pause(app: &mut App, scanner: &SharedScanner) {
+    let marked = app.marked_entries();
+    if !marked.is_empty() {
+        let signal = app::pause_signal(&marked);
+        app.request_bulk(BulkKind::Signal(signal));
+        return;
+    }
+
+    let Some(entry) = app.selected_entry().cloned() else {
+        app.set_info("No process selected");
+        return;
+    };
+    let signal = app::pause_signal(&[&entry]);
+    let result = tabs::lock_idle(scanner)
+        .and_then(|mut scanner| scanner.send_signal(entry.pid, signal, entry.host.as_deref()));
+    match result {
+        Ok(()) => {
+            let verb = if signal == Signal::CONT {
+                "Resumed"
+            } else {
+                "Paused"
+            };
+            app.set_success(format!(
+                "{} '{}' (PID: {})",
+                verb, entry.process_name, entry.pid
+            ));
+        }
+        Err(e) => app.set_error(format!("{}", e)),
+    }
+}
+
+/// Handle keyboard input in the bulk action confirmation popup
+fn handle_confirm_input(
+    app: &mut App,
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    scanner: &SharedScanner,
+) {
+    match app.keymap.action(KeyContext::Confirm, code, modifiers) {
+        Some(Action::Confirm) => {
+            if let Some(bulk) = app.pending_bulk.take() {
+                run_bulk(app, &bulk, scanner);
             }
-            Err(e) => {
-                // Handle permission errors gracefully
-                app.set_error(format!("{}", e));
+        }
+        Some(Action::Cancel) => {
+            app.pending_bulk = None;
+            app.set_info("Cancelled");
+        }
+        _ => {}
+    }
+}
+
+/// Apply a confirmed bulk action to every target, then clear the marks
+fn run_bulk(app: &mut App, bulk: &PendingBulk, scanner: &SharedScanner) {
+    let mut scanner = match tabs::lock_idle(scanner) {
+        Ok(scanner) => scanner,
+        Err(e) => return app.set_error(format!("{}", e)),
+    };
+    let mut failures = Vec::new();
+    for target in &bulk.targets {
+        let result = match bulk.kind {
+            BulkKind::Kill => scanner.kill_process(target.pid, target.host.as_deref()),
+            BulkKind::Signal(signal) => {
+                scanner.send_signal(target.pid, signal, target.host.as_deref())
             }
+        };
+        if let Err(e) = result {
+            failures.push(format!("{} (PID: {}): {}", target.name, target.pid, e));
+        }
+    }
+    app.marked.clear();
+
+    let total = bulk.targets.len();
+    let done = total - failures.len();
+    let verb = match bulk.kind {
+        BulkKind::Kill => "Killed".to_string(),
+        BulkKind::Signal(signal) => format!("Sent SIG{} to", signal.name()),
+    };
+    match failures.first() {
+        None => app.set_success(format!("{} {} processes", verb, total)),
+        Some(first) => app.set_error(format!(
+            "{} {} of {} processes; {}",
+            verb, done, total, first
+        )),
+    }
+}
+
+/// Kill the process behind an entry and report the outcome
+fn kill_entry(app: &mut App, entry: &app::PortEntry, scanner: &SharedScanner) {
+    let result = tabs::lock_idle(scanner)
+        .and_then(|mut scanner| scanner.kill_process(entry.pid, entry.host.as_deref()));
+    match result {
+        Ok(()) => {
+            app.set_success(format!(
+                "Killed '{}' (PID: {}) on port {}",
+                entry.process_name, entry.pid, entry.port
+            ));
+        }
+        Err(e) => {
+            // Handle permission errors gracefully
+            app.set_error(format!("{}", e));
         }
-    } else {
-        app.set_info("No process selected");
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app::{PortEntry, Protocol, StatusMessage};
+    use crate::app::{PortEntry, ProcessState, Protocol, StatusMessage};
+    use std::sync::{Arc, Mutex};
 
     // ==================== Helper Functions ====================
 
     fn create_test_entry(port: u16, protocol: Protocol, pid: u32) -> PortEntry {
         PortEntry {
-            port,
             protocol,
-            pid,
-            process_name: format!("process_{}", pid),
-            cpu_usage: 0.0,
             memory_usage: 1024 * pid as u64,
             memory_display: format!("{} KB", pid),
-            has_parent: true,
-            is_zombie: false,
+            ..PortEntry::test(port, pid, &format!("process_{}", pid))
         }
     }
 
@@ -749,14 +1615,14 @@ mod tests {
         app
     }
 
-    fn create_test_scanner() -> ScannerMode {
-        ScannerMode::Local(Box::default())
+    fn create_test_scanner() -> SharedScanner {
+        Arc::new(Mutex::new(ScannerMode::Local(Box::default())))
     }
 
     /// Helper to call handle_key_event without scanner (for tests that don't need kill)
     fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
-        let mut scanner = create_test_scanner();
-        handle_key_event(app, code, modifiers, &mut scanner);
+        let scanner = create_test_scanner();
+        handle_key_event(app, code, modifiers, &scanner);
     }
 
     // ==================== App Initialization Tests ====================
@@ -882,6 +1748,92 @@ mod tests {
         assert_eq!(app.selected_index, 2);
     }
 
+    // ==================== Detail Panel Tests ====================
+
+    #[test]
+    fn test_key_event_i_toggles_details() {
+        let mut app = create_app_with_entries(3);
+        assert!(!app.show_details);
+
+        handle_key(&mut app, KeyCode::Char('i'), KeyModifiers::NONE);
+        assert!(app.show_details);
+
+        handle_key(&mut app, KeyCode::Char('i'), KeyModifiers::NONE);
+        assert!(!app.show_details);
+    }
+
+    #[test]
+    fn test_key_event_shift_l_moves_details() {
+        let mut app = create_app_with_entries(3);
+
+        handle_key(&mut app, KeyCode::Char('L'), KeyModifiers::SHIFT);
+        assert!(app.show_details);
+        assert_eq!(app.details_layout, app::DetailsLayout::Bottom);
+    }
+
+    #[test]
+    fn test_tab_keys() {
+        let mut app = create_app_with_entries(3);
+
+        handle_key(&mut app, KeyCode::Char('3'), KeyModifiers::ALT);
+        assert_eq!(app.tab_request.take(), Some(TabRequest::Select(2)));
+        // Without Alt the digit still sorts
+        handle_key(&mut app, KeyCode::Char('3'), KeyModifiers::NONE);
+        assert_eq!(app.tab_request, None);
+        assert_eq!(app.sort_column, app::SortColumn::Pid);
+
+        handle_key(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(app.tab_request.take(), Some(TabRequest::Next));
+        handle_key(&mut app, KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(app.tab_request.take(), Some(TabRequest::Previous));
+        handle_key(&mut app, KeyCode::Right, KeyModifiers::ALT);
+        assert_eq!(app.tab_request.take(), Some(TabRequest::Next));
+        handle_key(&mut app, KeyCode::Char('p'), KeyModifiers::CONTROL);
+        assert_eq!(app.tab_request.take(), Some(TabRequest::Previous));
+
+        // Disconnecting closes the tab once several are open
+        handle_key(&mut app, KeyCode::Char('d'), KeyModifiers::NONE);
+        assert_eq!(app.tab_request, None);
+        app.tab_labels = vec!["localhost".to_string(), "web1".to_string()];
+        handle_key(&mut app, KeyCode::Char('d'), KeyModifiers::NONE);
+        assert_eq!(app.tab_request, Some(TabRequest::Close));
+    }
+
+    #[test]
+    fn test_key_event_shift_f_toggles_fleet() {
+        let mut app = create_app_with_entries(1);
+        handle_key(&mut app, KeyCode::Char('F'), KeyModifiers::SHIFT);
+        assert_eq!(app.tab_request, Some(TabRequest::ToggleFleet));
+        app.tab_request = None;
+        run_palette(&mut app, "fleet");
+        assert_eq!(app.tab_request, Some(TabRequest::ToggleFleet));
+    }
+
+    #[test]
+    fn test_key_event_e_toggles_event_log() {
+        let mut app = create_app_with_entries(3);
+        assert!(!app.show_events);
+
+        handle_key(&mut app, KeyCode::Char('e'), KeyModifiers::NONE);
+        assert!(app.show_events);
+
+        handle_key(&mut app, KeyCode::Char('e'), KeyModifiers::NONE);
+        assert!(!app.show_events);
+    }
+
+    #[test]
+    fn test_key_event_p_toggles_pin() {
+        let mut app = create_app_with_entries(3);
+        app.selected_index = 1;
+
+        handle_key(&mut app, KeyCode::Char('p'), KeyModifiers::NONE);
+        assert!(app.pins.contains(None, 3001));
+        assert_eq!(app.selected_entry().unwrap().port, 3001);
+
+        handle_key(&mut app, KeyCode::Char('p'), KeyModifiers::NONE);
+        assert!(!app.pins.contains(None, 3001));
+    }
+
     // ==================== Page Navigation Tests ====================
 
     #[test]
@@ -1008,8 +1960,8 @@ mod tests {
     #[test]
     fn test_handle_kill_no_selection() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
-        handle_kill(&mut app, &mut scanner);
+        let scanner = create_test_scanner();
+        handle_kill(&mut app, &scanner);
 
         match &app.status_message {
             StatusMessage::Info(msg) => assert!(msg.contains("No process")),
@@ -1020,20 +1972,11 @@ mod tests {
     #[test]
     fn test_handle_kill_nonexistent_process() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
-        app.entries = vec![PortEntry {
-            port: 3000,
-            protocol: Protocol::Tcp,
-            pid: 999_999_999, // Unlikely to exist
-            process_name: "fake".into(),
-            cpu_usage: 0.0,
-            memory_usage: 0,
-            memory_display: "0 B".into(),
-            has_parent: true,
-            is_zombie: false,
-        }];
-
-        handle_kill(&mut app, &mut scanner);
+        let scanner = create_test_scanner();
+        // PID unlikely to exist
+        app.entries = vec![PortEntry::test(3000, 999_999_999, "fake")];
+
+        handle_kill(&mut app, &scanner);
 
         // Should get an error message
         match &app.status_message {
@@ -1114,11 +2057,15 @@ mod tests {
     }
 
     #[test]
-    fn test_alt_modifier_ignored_for_navigation() {
+    fn test_unbound_alt_chord_does_nothing() {
         let mut app = create_app_with_entries(5);
 
         handle_key(&mut app, KeyCode::Down, KeyModifiers::ALT);
-        assert_eq!(app.selected_index, 1);
+        assert_eq!(app.selected_index, 0);
+
+        // alt+c isn't Connect
+        handle_key(&mut app, KeyCode::Char('c'), KeyModifiers::ALT);
+        assert!(!app.connect_mode);
     }
 
     // ==================== Integration Tests ====================
@@ -1192,7 +2139,7 @@ mod tests {
     #[test]
     fn test_timing_constants() {
         assert_eq!(POLL_RATE, Duration::from_millis(50));
-        assert_eq!(DEFAULT_SCAN_INTERVAL, 2);
+        assert_eq!(config::DEFAULT_SCAN_INTERVAL, 2);
     }
 
     // ==================== Connect Mode Tests ====================
@@ -1200,14 +2147,9 @@ mod tests {
     #[test]
     fn test_key_event_enter_connect_mode() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
+        let scanner = create_test_scanner();
 
-        handle_key_event(
-            &mut app,
-            KeyCode::Char('c'),
-            KeyModifiers::NONE,
-            &mut scanner,
-        );
+        handle_key_event(&mut app, KeyCode::Char('c'), KeyModifiers::NONE, &scanner);
 
         assert!(app.connect_mode);
         assert!(!app.connect_key_mode);
@@ -1216,13 +2158,13 @@ mod tests {
     #[test]
     fn test_key_event_c_with_ctrl_does_not_enter_connect() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
+        let scanner = create_test_scanner();
 
         handle_key_event(
             &mut app,
             KeyCode::Char('c'),
             KeyModifiers::CONTROL,
-            &mut scanner,
+            &scanner,
         );
 
         assert!(!app.connect_mode);
@@ -1232,11 +2174,10 @@ mod tests {
     #[test]
     fn test_handle_connect_input_enter_host() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
         app.enter_connect_mode();
         app.connect_input.push_str("user@host");
 
-        handle_connect_input(&mut app, KeyCode::Enter, &mut scanner);
+        handle_connect_input(&mut app, KeyCode::Enter, KeyModifiers::NONE);
 
         assert!(app.connect_key_mode);
         assert_eq!(app.connect_input, "user@host");
@@ -1245,10 +2186,9 @@ mod tests {
     #[test]
     fn test_handle_connect_input_enter_empty_host() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
         app.enter_connect_mode();
 
-        handle_connect_input(&mut app, KeyCode::Enter, &mut scanner);
+        handle_connect_input(&mut app, KeyCode::Enter, KeyModifiers::NONE);
 
         // Should not enter key mode if host is empty
         assert!(!app.connect_key_mode);
@@ -1257,11 +2197,10 @@ mod tests {
     #[test]
     fn test_handle_connect_input_esc_cancels() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
         app.enter_connect_mode();
         app.connect_input.push_str("user@host");
 
-        handle_connect_input(&mut app, KeyCode::Esc, &mut scanner);
+        handle_connect_input(&mut app, KeyCode::Esc, KeyModifiers::NONE);
 
         assert!(!app.connect_mode);
         assert!(app.connect_input.is_empty());
@@ -1270,13 +2209,12 @@ mod tests {
     #[test]
     fn test_handle_connect_input_esc_in_key_mode() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
         app.enter_connect_mode();
         app.connect_input.push_str("user@host");
         app.enter_connect_key_mode();
         app.connect_key_input.push_str("/key");
 
-        handle_connect_input(&mut app, KeyCode::Esc, &mut scanner);
+        handle_connect_input(&mut app, KeyCode::Esc, KeyModifiers::NONE);
 
         assert!(!app.connect_key_mode);
         assert!(app.connect_key_input.is_empty());
@@ -1286,11 +2224,10 @@ mod tests {
     #[test]
     fn test_handle_connect_input_backspace() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
         app.enter_connect_mode();
         app.connect_input.push_str("user@host");
 
-        handle_connect_input(&mut app, KeyCode::Backspace, &mut scanner);
+        handle_connect_input(&mut app, KeyCode::Backspace, KeyModifiers::NONE);
 
         assert_eq!(app.connect_input, "user@hos");
     }
@@ -1298,13 +2235,12 @@ mod tests {
     #[test]
     fn test_handle_connect_input_backspace_key_mode() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
         app.enter_connect_mode();
         app.connect_input.push_str("user@host");
         app.enter_connect_key_mode();
         app.connect_key_input.push_str("/path/to/key");
 
-        handle_connect_input(&mut app, KeyCode::Backspace, &mut scanner);
+        handle_connect_input(&mut app, KeyCode::Backspace, KeyModifiers::NONE);
 
         assert_eq!(app.connect_key_input, "/path/to/ke");
         assert_eq!(app.connect_input, "user@host");
@@ -1313,13 +2249,12 @@ mod tests {
     #[test]
     fn test_handle_connect_input_char() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
         app.enter_connect_mode();
 
-        handle_connect_input(&mut app, KeyCode::Char('u'), &mut scanner);
-        handle_connect_input(&mut app, KeyCode::Char('s'), &mut scanner);
-        handle_connect_input(&mut app, KeyCode::Char('e'), &mut scanner);
-        handle_connect_input(&mut app, KeyCode::Char('r'), &mut scanner);
+        handle_connect_input(&mut app, KeyCode::Char('u'), KeyModifiers::NONE);
+        handle_connect_input(&mut app, KeyCode::Char('s'), KeyModifiers::NONE);
+        handle_connect_input(&mut app, KeyCode::Char('e'), KeyModifiers::NONE);
+        handle_connect_input(&mut app, KeyCode::Char('r'), KeyModifiers::NONE);
 
         assert_eq!(app.connect_input, "user");
     }
@@ -1327,26 +2262,69 @@ mod tests {
     #[test]
     fn test_handle_connect_input_tab_skips_key() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
         app.enter_connect_mode();
         app.connect_input.push_str("user@host");
 
-        // Tab should attempt connection (will fail in test, but should exit connect mode)
-        handle_connect_input(&mut app, KeyCode::Tab, &mut scanner);
+        // Tab skips the key and asks the main loop to open a tab for the host
+        handle_connect_input(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+
+        assert_eq!(
+            app.tab_request,
+            Some(TabRequest::Open {
+                host: "user@host".to_string(),
+                identity: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_connect_picker_opens_inventory_host() {
+        let mut app = App::new();
+        app.inventory = Inventory::parse(
+            "[[hosts]]\nlabel = \"web1\"\nhost = \"deploy@10.0.0.11\"\n\
+             [[hosts]]\nlabel = \"web2\"\nhost = \"deploy@10.0.0.12\"",
+        )
+        .unwrap();
+        app.enter_connect_mode();
+        for c in "wb".chars() {
+            handle_connect_input(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        // Both labels match, followed by the typed text as a new host
+        assert_eq!(app.connect_choices().len(), 3);
+        handle_connect_input(&mut app, KeyCode::Down, KeyModifiers::NONE);
+        handle_connect_input(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+
+        // Known hosts connect without asking for a key
+        assert!(!app.connect_key_mode);
+        assert_eq!(
+            app.tab_request,
+            Some(TabRequest::Open {
+                host: "web2".to_string(),
+                identity: None,
+            })
+        );
+    }
 
-        // Connect mode should be exited (connection will fail, but mode should exit)
-        // Note: handle_connect will fail because we can't actually connect in tests
-        // but the mode should be handled
+    #[test]
+    fn test_connect_picker_new_host_asks_for_key() {
+        let mut app = App::new();
+        app.enter_connect_mode();
+        for c in "user@host".chars() {
+            handle_connect_input(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        handle_connect_input(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(app.connect_key_mode);
+        assert!(app.tab_request.is_none());
     }
 
     #[test]
     fn test_handle_disconnect() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
+        let scanner = create_test_scanner();
         app.set_remote_host(Some("user@host:22".to_string()));
         assert!(app.remote_host.is_some());
 
-        handle_disconnect(&mut app, &mut scanner);
+        handle_disconnect(&mut app, &scanner);
 
         assert!(app.remote_host.is_none());
         match &app.status_message {
@@ -1360,15 +2338,10 @@ mod tests {
     #[test]
     fn test_key_event_disconnect() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
+        let scanner = create_test_scanner();
         app.set_remote_host(Some("user@host:22".to_string()));
 
-        handle_key_event(
-            &mut app,
-            KeyCode::Char('d'),
-            KeyModifiers::NONE,
-            &mut scanner,
-        );
+        handle_key_event(&mut app, KeyCode::Char('d'), KeyModifiers::NONE, &scanner);
 
         assert!(app.remote_host.is_none());
     }
@@ -1376,15 +2349,10 @@ mod tests {
     #[test]
     fn test_key_event_disconnect_not_connected() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
+        let scanner = create_test_scanner();
         assert!(app.remote_host.is_none());
 
-        handle_key_event(
-            &mut app,
-            KeyCode::Char('d'),
-            KeyModifiers::NONE,
-            &mut scanner,
-        );
+        handle_key_event(&mut app, KeyCode::Char('d'), KeyModifiers::NONE, &scanner);
 
         // Should not error, just do nothing
         assert!(app.remote_host.is_none());
@@ -1393,18 +2361,13 @@ mod tests {
     #[test]
     fn test_connect_mode_blocks_other_keys() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
+        let scanner = create_test_scanner();
         app.enter_connect_mode();
         let initial_entries = app.entries.len();
 
         // Navigation keys should not work in connect mode
-        handle_key_event(&mut app, KeyCode::Down, KeyModifiers::NONE, &mut scanner);
-        handle_key_event(
-            &mut app,
-            KeyCode::Char('j'),
-            KeyModifiers::NONE,
-            &mut scanner,
-        );
+        handle_key_event(&mut app, KeyCode::Down, KeyModifiers::NONE, &scanner);
+        handle_key_event(&mut app, KeyCode::Char('j'), KeyModifiers::NONE, &scanner);
 
         assert_eq!(app.selected_index, 0);
         assert_eq!(app.entries.len(), initial_entries);
@@ -1413,17 +2376,12 @@ mod tests {
     #[test]
     fn test_connect_mode_filter_mode_exclusive() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
+        let scanner = create_test_scanner();
         app.enter_filter_mode();
         assert!(app.filter_mode);
         assert!(!app.connect_mode);
 
-        handle_key_event(
-            &mut app,
-            KeyCode::Char('c'),
-            KeyModifiers::NONE,
-            &mut scanner,
-        );
+        handle_key_event(&mut app, KeyCode::Char('c'), KeyModifiers::NONE, &scanner);
 
         // Filter mode should block connect mode
         assert!(app.filter_mode);
@@ -1433,21 +2391,531 @@ mod tests {
     #[test]
     fn test_connect_mode_help_mode_exclusive() {
         let mut app = App::new();
-        let mut scanner = create_test_scanner();
+        let scanner = create_test_scanner();
         app.show_help = true;
 
-        handle_key_event(
-            &mut app,
-            KeyCode::Char('c'),
-            KeyModifiers::NONE,
-            &mut scanner,
-        );
+        handle_key_event(&mut app, KeyCode::Char('c'), KeyModifiers::NONE, &scanner);
 
         // Help mode should close first
         assert!(!app.show_help);
         // Connect mode should not be entered when help closes
         assert!(!app.connect_mode);
     }
+
+    // ==================== Keymap Tests ====================
+
+    #[test]
+    fn test_custom_keymap_routes_all_handlers() {
+        let config = config::Config::parse(
+            r#"
+            [keys]
+            filter = "f"
+            quit = "ctrl+q"
+
+            [keys.prompt]
+            cancel = "ctrl+g"
+            "#,
+        )
+        .unwrap();
+        let mut app = create_app_with_entries(3);
+        app.keymap = config.keymap().unwrap();
+
+        handle_key(&mut app, KeyCode::Char('/'), KeyModifiers::NONE);
+        assert!(!app.filter_mode);
+        handle_key(&mut app, KeyCode::Char('f'), KeyModifiers::NONE);
+        assert!(app.filter_mode);
+
+        // Esc is no longer bound in prompts, so it's ignored
+        handle_key(&mut app, KeyCode::Char('x'), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(app.filter_mode);
+        assert_eq!(app.filter, "x");
+        handle_key(&mut app, KeyCode::Char('g'), KeyModifiers::CONTROL);
+        assert!(!app.filter_mode);
+        assert!(app.filter.is_empty());
+
+        handle_key(&mut app, KeyCode::Char('q'), KeyModifiers::NONE);
+        assert!(!app.should_quit);
+        handle_key(&mut app, KeyCode::Char('q'), KeyModifiers::CONTROL);
+        assert!(app.should_quit);
+    }
+
+    // ==================== Mouse Tests ====================
+
+    const SCREEN: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 120,
+        height: 30,
+    };
+
+    /// Position of the first occurrence of `text` on the rendered screen
+    fn find_on_screen(app: &App, text: &str) -> (u16, u16) {
+        let backend = ratatui::backend::TestBackend::new(SCREEN.width, SCREEN.height);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|frame| ui::render(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        for y in 0..SCREEN.height {
+            let line: String = (0..SCREEN.width)
+                .map(|x| buffer[(x, y)].symbol().chars().next().unwrap_or(' '))
+                .collect();
+            if let Some(byte) = line.find(text) {
+                return (line[..byte].chars().count() as u16, y);
+            }
+        }
+        panic!("{:?} is not on screen", text);
+    }
+
+    fn click(app: &mut App, (column, row): (u16, u16)) {
+        let mouse = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        handle_mouse_event(app, mouse, SCREEN);
+    }
+
+    fn scroll(app: &mut App, kind: MouseEventKind) {
+        let mouse = MouseEvent {
+            kind,
+            column: 10,
+            row: 10,
+            modifiers: KeyModifiers::NONE,
+        };
+        handle_mouse_event(app, mouse, SCREEN);
+    }
+
+    #[test]
+    fn test_mouse_click_selects_row() {
+        let mut app = create_app_with_entries(5);
+        let position = find_on_screen(&app, "3003");
+        click(&mut app, position);
+        assert_eq!(app.selected_index, 3);
+
+        // Clicking below the last row changes nothing
+        click(&mut app, (position.0, position.1 + 5));
+        assert_eq!(app.selected_index, 3);
+    }
+
+    #[test]
+    fn test_mouse_click_accounts_for_scrolling() {
+        let mut app = create_app_with_entries(60);
+        app.selected_index = 50;
+        let position = find_on_screen(&app, "3045");
+        click(&mut app, position);
+        assert_eq!(app.selected_index, 45);
+    }
+
+    #[test]
+    fn test_mouse_click_header_sorts() {
+        let mut app = create_app_with_entries(3);
+        let position = find_on_screen(&app, "CPU%");
+        click(&mut app, position);
+        assert_eq!(app.sort_column, app::SortColumn::CpuUsage);
+        assert_eq!(app.sort_order, app::SortOrder::Ascending);
+
+        click(&mut app, (position.0 + 5, position.1));
+        assert_eq!(app.sort_order, app::SortOrder::Descending);
+
+        // HEALTH isn't sortable
+        let position = find_on_screen(&app, "HEALTH");
+        click(&mut app, position);
+        assert_eq!(app.sort_column, app::SortColumn::CpuUsage);
+    }
+
+    #[test]
+    fn test_mouse_click_top_bar_hints() {
+        let mut app = create_app_with_entries(3);
+        let position = find_on_screen(&app, "Help");
+        click(&mut app, position);
+        assert!(app.show_help);
+
+        // Any click closes help
+        click(&mut app, (0, 0));
+        assert!(!app.show_help);
+
+        let position = find_on_screen(&app, "<q>");
+        click(&mut app, position);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_mouse_click_tab_selects_it() {
+        let mut app = create_app_with_entries(3);
+        app.tab_labels = vec!["localhost".to_string(), "deploy@web1:22".to_string()];
+        let position = find_on_screen(&app, "web1");
+        click(&mut app, position);
+        assert_eq!(app.tab_request, Some(TabRequest::Select(1)));
+    }
+
+    #[test]
+    fn test_mouse_wheel_scrolls_without_wrapping() {
+        let mut app = create_app_with_entries(3);
+        scroll(&mut app, MouseEventKind::ScrollUp);
+        assert_eq!(app.selected_index, 0);
+        for _ in 0..5 {
+            scroll(&mut app, MouseEventKind::ScrollDown);
+        }
+        assert_eq!(app.selected_index, 2);
+    }
+
+    #[test]
+    fn test_mouse_ignored_in_prompts() {
+        let mut app = create_app_with_entries(3);
+        app.enter_filter_mode();
+        scroll(&mut app, MouseEventKind::ScrollDown);
+        let position = find_on_screen(&app, "3002");
+        click(&mut app, position);
+        assert_eq!(app.selected_index, 0);
+        assert!(app.filter_mode);
+    }
+
+    // ==================== Command Palette Tests ====================
+
+    /// Open the palette, type a command and run it
+    fn run_palette(app: &mut App, command: &str) {
+        handle_key(app, KeyCode::Char(':'), KeyModifiers::NONE);
+        assert!(app.command_mode);
+        for c in command.chars() {
+            handle_key(app, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        handle_key(app, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(!app.command_mode);
+    }
+
+    #[test]
+    fn test_palette_runs_actions_by_name() {
+        let mut app = create_app_with_entries(3);
+        run_palette(&mut app, "details");
+        assert!(app.show_details);
+        run_palette(&mut app, "last");
+        assert_eq!(app.selected_index, 2);
+        run_palette(&mut app, "quit");
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_palette_sort() {
+        let mut app = create_app_with_entries(3);
+        run_palette(&mut app, "sort mem desc");
+        assert_eq!(app.sort_column, app::SortColumn::MemoryUsage);
+        assert_eq!(app.sort_order, app::SortOrder::Descending);
+
+        // Asking for the current order keeps it
+        run_palette(&mut app, "sort mem desc");
+        assert_eq!(app.sort_order, app::SortOrder::Descending);
+        run_palette(&mut app, "sort mem");
+        assert_eq!(app.sort_order, app::SortOrder::Ascending);
+    }
+
+    #[test]
+    fn test_palette_filter() {
+        let mut app = create_app_with_entries(3);
+        run_palette(&mut app, "filter proto:tcp port:3000");
+        assert_eq!(app.filter, "proto:tcp port:3000");
+        assert!(!app.filter_mode);
+
+        run_palette(&mut app, "filter cpu>");
+        assert_eq!(app.filter, "proto:tcp port:3000");
+        assert!(matches!(app.status_message, StatusMessage::Error(_)));
+    }
+
+    #[test]
+    fn test_palette_errors() {
+        let mut app = create_app_with_entries(3);
+        run_palette(&mut app, "frobnicate");
+        assert!(matches!(
+            &app.status_message,
+            StatusMessage::Error(msg) if msg == "Unknown command ':frobnicate' (Tab lists commands)"
+        ));
+
+        run_palette(&mut app, "kill 9999");
+        assert!(matches!(
+            &app.status_message,
+            StatusMessage::Error(msg) if msg == "No port or PID 9999 in the table"
+        ));
+    }
+
+    #[test]
+    fn test_palette_cancel() {
+        let mut app = create_app_with_entries(3);
+        handle_key(&mut app, KeyCode::Char(':'), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Char('q'), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(!app.command_mode);
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_palette_tab_completion() {
+        let mut app = create_app_with_entries(3);
+        handle_key(&mut app, KeyCode::Char(':'), KeyModifiers::NONE);
+        for c in "sig".chars() {
+            handle_key(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        // A single match is accepted, ready for the next argument
+        handle_key(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(app.command_input, "signal ");
+
+        // Several matches are cycled through
+        handle_key(&mut app, KeyCode::Char('u'), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(app.command_input, "signal USR1");
+        handle_key(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(app.command_input, "signal USR2");
+        handle_key(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(app.command_input, "signal USR1");
+
+        // Typing starts a new completion
+        handle_key(&mut app, KeyCode::Char(' '), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Char('3'), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(app.command_input, "signal USR1 3000");
+    }
+
+    #[test]
+    fn test_palette_pins_ports_not_in_table() {
+        let mut app = create_app_with_entries(1);
+        run_palette(&mut app, "pin 5432");
+        assert!(app.pins.contains(None, 5432));
+        assert_eq!(app.pinned_down(), vec![(None, 5432)]);
+
+        run_palette(&mut app, "unpin 5432");
+        assert!(!app.pins.contains(None, 5432));
+        run_palette(&mut app, "unpin 5432");
+        assert!(matches!(
+            &app.status_message,
+            StatusMessage::Info(msg) if msg == "Port 5432 is not pinned"
+        ));
+    }
+
+    #[test]
+    fn test_palette_export() {
+        let path = std::env::temp_dir().join(format!("lsport-export-{}.csv", std::process::id()));
+        let mut app = create_app_with_entries(2);
+        run_palette(&mut app, &format!("export csv {}", path.display()));
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        assert!(text.lines().nth(1).unwrap().starts_with("3000,TCP,"));
+        assert!(matches!(app.status_message, StatusMessage::Success(_)));
+    }
+
+    // ==================== Bulk Action Tests ====================
+
+    /// An app whose PIDs don't exist, so bulk kills and signals fail harmlessly
+    fn create_app_with_missing_pids(count: usize) -> App {
+        let mut app = create_app_with_entries(count);
+        for entry in &mut app.entries {
+            entry.pid += 4_000_000;
+        }
+        app
+    }
+
+    #[test]
+    fn test_space_marks_and_moves_down() {
+        let mut app = create_app_with_entries(3);
+        handle_key(&mut app, KeyCode::Char(' '), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Char(' '), KeyModifiers::NONE);
+        assert_eq!(app.selected_index, 2);
+        let ports: Vec<u16> = app.marked_entries().iter().map(|e| e.port).collect();
+        assert_eq!(ports, vec![3000, 3001]);
+
+        handle_key(&mut app, KeyCode::Char('k'), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Char(' '), KeyModifiers::NONE);
+        assert_eq!(app.marked_entries().len(), 1);
+    }
+
+    #[test]
+    fn test_mark_all_toggles() {
+        let mut app = create_app_with_entries(3);
+        handle_key(&mut app, KeyCode::Char('a'), KeyModifiers::NONE);
+        assert_eq!(app.marked_entries().len(), 3);
+        handle_key(&mut app, KeyCode::Char('a'), KeyModifiers::NONE);
+        assert!(app.marked.is_empty());
+    }
+
+    #[test]
+    fn test_bulk_kill_asks_for_confirmation() {
+        let mut app = create_app_with_missing_pids(3);
+        handle_key(&mut app, KeyCode::Char('a'), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        let bulk = app.pending_bulk.as_ref().unwrap();
+        assert_eq!(bulk.kind, BulkKind::Kill);
+        assert_eq!(bulk.question(), "Kill 3 processes?");
+
+        // Other keys are ignored while the popup is open
+        handle_key(&mut app, KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(app.selected_index, 0);
+
+        handle_key(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(app.pending_bulk.is_none());
+        assert_eq!(app.marked.len(), 3);
+    }
+
+    #[test]
+    fn test_bulk_kill_reports_failures() {
+        let mut app = create_app_with_missing_pids(3);
+        handle_key(&mut app, KeyCode::Char('a'), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Char('y'), KeyModifiers::NONE);
+
+        assert!(app.pending_bulk.is_none());
+        assert!(app.marked.is_empty());
+        assert!(matches!(
+            &app.status_message,
+            StatusMessage::Error(msg) if msg.starts_with("Killed 0 of 3 processes; process_1 (PID: 4000001)")
+        ));
+    }
+
+    #[test]
+    fn test_bulk_pause_picks_signal() {
+        let mut app = create_app_with_missing_pids(2);
+        handle_key(&mut app, KeyCode::Char('a'), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Char('z'), KeyModifiers::NONE);
+        assert_eq!(
+            app.pending_bulk.take().unwrap().kind,
+            BulkKind::Signal(Signal::STOP)
+        );
+
+        // Resume once every marked process is stopped
+        for entry in &mut app.entries {
+            entry.state = ProcessState::Stopped;
+        }
+        handle_key(&mut app, KeyCode::Char('z'), KeyModifiers::NONE);
+        assert_eq!(
+            app.pending_bulk.take().unwrap().kind,
+            BulkKind::Signal(Signal::CONT)
+        );
+    }
+
+    #[test]
+    fn test_palette_signal_uses_marked_rows() {
+        let mut app = create_app_with_missing_pids(3);
+        handle_key(&mut app, KeyCode::Char(' '), KeyModifiers::NONE);
+        run_palette(&mut app, "signal HUP");
+        let bulk = app.pending_bulk.as_ref().unwrap();
+        assert_eq!(bulk.question(), "Send SIGHUP to 1 process?");
+    }
+
+    #[test]
+    fn test_palette_export_marked_rows() {
+        let path =
+            std::env::temp_dir().join(format!("lsport-export-marked-{}.csv", std::process::id()));
+        let mut app = create_app_with_entries(3);
+        app.selected_index = 1;
+        handle_key(&mut app, KeyCode::Char(' '), KeyModifiers::NONE);
+        run_palette(&mut app, &format!("export csv {}", path.display()));
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.lines().nth(1).unwrap().starts_with("3001,TCP,"));
+    }
+
+    // ==================== Group Tests ====================
+
+    #[test]
+    fn test_group_keys() {
+        let mut app = App::new();
+        // PIDs that don't exist, in case Enter ever kills
+        let mut entries = create_entries(3);
+        for entry in &mut entries {
+            entry.pid += 4_000_000;
+        }
+        entries[1].pid = entries[0].pid;
+        app.update_entries(entries);
+
+        handle_key(&mut app, KeyCode::Char('g'), KeyModifiers::NONE);
+        assert_eq!(app.group_by, Some(group::GroupBy::Pid));
+        assert_eq!(app.row_count(), 2);
+
+        // Enter on a group header expands it instead of killing
+        handle_key(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.row_count(), 4);
+        handle_key(&mut app, KeyCode::Char('h'), KeyModifiers::NONE);
+        assert_eq!(app.row_count(), 2);
+        handle_key(&mut app, KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!(app.row_count(), 4);
+
+        handle_key(&mut app, KeyCode::End, KeyModifiers::NONE);
+        assert_eq!(app.selected_index, 3);
+        assert_eq!(app.selected_entry().unwrap().port, 3002);
+    }
+
+    #[test]
+    fn test_palette_group() {
+        let mut app = create_app_with_entries(3);
+        run_palette(&mut app, "group name");
+        assert_eq!(app.group_by, Some(group::GroupBy::Name));
+        run_palette(&mut app, "group tree");
+        assert_eq!(app.group_by, Some(group::GroupBy::Tree));
+        run_palette(&mut app, "group off");
+        assert_eq!(app.group_by, None);
+        run_palette(&mut app, "group user");
+        assert!(matches!(app.status_message, StatusMessage::Error(_)));
+    }
+
+    // ==================== Saved View Tests ====================
+
+    #[test]
+    fn test_key_event_save_and_pick_view() {
+        let mut app = create_app_with_entries(3);
+        app.filter = "port:3001".into();
+
+        handle_key(&mut app, KeyCode::Char('V'), KeyModifiers::SHIFT);
+        assert!(app.view_name_mode);
+        for c in "one".chars() {
+            handle_key(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        // Keys go to the prompt, not the table
+        assert!(!app.should_quit);
+        handle_key(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(!app.view_name_mode);
+        assert!(app.views.get("one").is_some());
+
+        app.clear_filter();
+        handle_key(&mut app, KeyCode::Char('v'), KeyModifiers::NONE);
+        assert!(app.show_view_picker);
+        // 'j' moves within the picker instead of the table
+        handle_key(&mut app, KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(app.selected_index, 0);
+        handle_key(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(!app.show_view_picker);
+        assert_eq!(app.filter, "port:3001");
+    }
+
+    #[test]
+    fn test_key_event_view_name_esc_cancels() {
+        let mut app = App::new();
+        handle_key(&mut app, KeyCode::Char('V'), KeyModifiers::SHIFT);
+        handle_key(&mut app, KeyCode::Char('x'), KeyModifiers::NONE);
+        handle_key(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(!app.view_name_mode);
+        assert!(app.views.is_empty());
+    }
+
+    #[test]
+    fn test_key_event_n_cycles_views() {
+        let mut app = create_app_with_entries(3);
+        handle_key(&mut app, KeyCode::Char('n'), KeyModifiers::NONE);
+        assert!(app.active_view.is_none());
+
+        app.filter = "port:3000".into();
+        app.enter_view_name_mode();
+        app.view_name_input = "a".into();
+        app.save_view_from_input();
+        app.filter = "port:3002".into();
+        app.enter_view_name_mode();
+        app.view_name_input = "b".into();
+        app.save_view_from_input();
+
+        handle_key(&mut app, KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(app.active_view.as_deref(), Some("a"));
+        assert_eq!(app.filter, "port:3000");
+    }
 }
 
 #[cfg(test)]
@@ -1459,17 +2927,17 @@ mod cli_tests {
 
     #[test]
     fn test_run_kill_neither_pid_nor_port() {
-        let result = run_kill(None, None, None, None, false);
+        let result = run_kill(None, None, None, None, None, false);
         assert!(result.is_err());
         let error = result.unwrap_err();
         assert!(error
             .to_string()
-            .contains("Either --pid or --port must be specified"));
+            .contains("Either --pid, --port or --filter must be specified"));
     }
 
     #[test]
     fn test_run_kill_both_pid_and_port() {
-        let result = run_kill(Some(123), Some(8080), None, None, false);
+        let result = run_kill(Some(123), Some(8080), None, None, None, false);
         assert!(result.is_err());
         let error = result.unwrap_err();
         assert!(error.to_string().contains("Cannot specify both"));
@@ -1478,7 +2946,7 @@ mod cli_tests {
     #[test]
     fn test_run_kill_pid_only() {
         // This will fail because PID likely doesn't exist, but validates the logic
-        let result = run_kill(Some(999_999_999), None, None, None, false);
+        let result = run_kill(Some(999_999_999), None, None, None, None, false);
         // Should fail with "not found" not "must be specified"
         assert!(result.is_err());
         let error = result.unwrap_err();
@@ -1490,7 +2958,7 @@ mod cli_tests {
     #[test]
     fn test_run_kill_port_only() {
         // This will fail because port likely doesn't exist, but validates the logic
-        let result = run_kill(None, Some(65535), None, None, false);
+        let result = run_kill(None, Some(65535), None, None, None, false);
         // Should fail with "not found" not "must be specified"
         assert!(result.is_err());
         let error = result.unwrap_err();
@@ -1499,10 +2967,21 @@ mod cli_tests {
         );
     }
 
+    #[test]
+    fn test_run_kill_filter_only() {
+        // A filter alone selects the process; nothing listens on this port
+        let query = Query::parse("port:65535 name:no-such-process").unwrap();
+        let result = run_kill(None, None, Some(query), None, None, false);
+        let error = result.unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("No process found matching the filter"));
+    }
+
     #[test]
     fn test_run_kill_force_flag() {
         // Test that force flag is accepted (will fail on actual kill, but validates parsing)
-        let result = run_kill(Some(999_999_999), None, None, None, true);
+        let result = run_kill(Some(999_999_999), None, None, None, None, true);
         assert!(result.is_err()); // Will fail because PID doesn't exist
     }
 
@@ -1512,6 +2991,7 @@ mod cli_tests {
         let result = run_kill(
             Some(123),
             None,
+            None,
             Some("invalid-host".to_string()),
             None,
             false,
@@ -1526,6 +3006,7 @@ mod cli_tests {
         let result = run_kill(
             Some(123),
             None,
+            None,
             Some("invalid-host".to_string()),
             Some(key_path),
             false,
@@ -1537,7 +3018,7 @@ mod cli_tests {
 
     #[test]
     fn test_run_describe_empty_target() {
-        let result = run_describe(String::new(), None, None);
+        let result = run_describe(String::new(), None, None, None);
         // Empty string matches all processes (contains("") is always true)
         // So it will succeed and return all processes, not fail
         // This is expected behavior - empty string matches everything
@@ -1546,7 +3027,7 @@ mod cli_tests {
 
     #[test]
     fn test_run_describe_nonexistent_port() {
-        let result = run_describe("99999".to_string(), None, None);
+        let result = run_describe("99999".to_string(), None, None, None);
         // Will fail because port doesn't exist
         assert!(result.is_err());
         let error = result.unwrap_err();
@@ -1557,7 +3038,7 @@ mod cli_tests {
 
     #[test]
     fn test_run_describe_nonexistent_pid() {
-        let result = run_describe("999999999".to_string(), None, None);
+        let result = run_describe("999999999".to_string(), None, None, None);
         // Will fail because PID doesn't exist
         assert!(result.is_err());
         let error = result.unwrap_err();
@@ -1569,7 +3050,12 @@ mod cli_tests {
     #[test]
     fn test_run_describe_remote_host() {
         // Test remote host parsing (will fail on connection, but validates parsing)
-        let result = run_describe("8080".to_string(), Some("invalid-host".to_string()), None);
+        let result = run_describe(
+            "8080".to_string(),
+            None,
+            Some("invalid-host".to_string()),
+            None,
+        );
         assert!(result.is_err()); // Will fail on connection
     }
 
@@ -1579,6 +3065,7 @@ mod cli_tests {
         let key_path = PathBuf::from("/nonexistent/key");
         let result = run_describe(
             "8080".to_string(),
+            None,
             Some("invalid-host".to_string()),
             Some(key_path),
         );
@@ -1588,10 +3075,196 @@ mod cli_tests {
     #[test]
     fn test_run_describe_process_name() {
         // Test with process name (will likely fail, but validates logic)
-        let result = run_describe("nonexistent_process".to_string(), None, None);
+        let result = run_describe("nonexistent_process".to_string(), None, None, None);
         assert!(result.is_err()); // Will fail because process doesn't exist
     }
 
+    #[test]
+    fn test_run_describe_filter_no_match() {
+        let query = Query::parse("port:1-2 proto:udp").unwrap();
+        let result = run_describe(String::new(), Some(query), None, None);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("filter"));
+    }
+
+    // ==================== Config Command Tests ====================
+
+    fn run_config_to_string(action: ConfigCommand, path: &Path) -> Result<String> {
+        let mut out = Vec::new();
+        run_config(action, Some(path), &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_run_config_missing_file() {
+        let path = std::env::temp_dir().join("lsport-config-missing/config.toml");
+        assert_eq!(
+            run_config_to_string(ConfigCommand::Path, &path).unwrap(),
+            format!("{}\n", path.display())
+        );
+
+        let shown = run_config_to_string(ConfigCommand::Show, &path).unwrap();
+        assert!(shown.contains("(not found, showing defaults)"));
+        assert!(shown.contains("scan_interval = 2"));
+        assert!(shown.contains("sort = \"port\""));
+
+        let validated = run_config_to_string(ConfigCommand::Validate, &path).unwrap();
+        assert!(validated.starts_with("No config file"));
+    }
+
+    #[test]
+    fn test_run_config_validate() {
+        let dir = std::env::temp_dir().join(format!("lsport-config-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(&path, "[defaults]\nsort = \"cpu\"\nfilter = \"cpu>5\"\n").unwrap();
+        let valid = run_config_to_string(ConfigCommand::Validate, &path);
+        let shown = run_config_to_string(ConfigCommand::Show, &path);
+
+        std::fs::write(&path, "[defaults]\nfilter = \"colour:red\"\n").unwrap();
+        let invalid = run_config_to_string(ConfigCommand::Validate, &path);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(valid.unwrap().contains("is valid"));
+        assert!(shown.unwrap().contains("filter = \"cpu>5\""));
+        let err = format!("{:#}", invalid.unwrap_err());
+        assert!(err.contains("defaults.filter"), "{}", err);
+    }
+
+    #[test]
+    fn test_scan_interval_flag() {
+        let args = Args::try_parse_from(["lsport"]).unwrap();
+        assert_eq!(args.scan_interval, None);
+        let args = Args::try_parse_from(["lsport", "-s", "5"]).unwrap();
+        assert_eq!(args.scan_interval, Some(5));
+        assert!(Args::try_parse_from(["lsport", "-s", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_view_flag() {
+        let args = Args::try_parse_from(["lsport", "--view", "databases"]).unwrap();
+        assert_eq!(args.view.as_deref(), Some("databases"));
+    }
+
+    #[test]
+    fn test_load_theme() {
+        let config = config::Config::default();
+        let theme = load_theme(&config, Some("16-color")).unwrap();
+        assert_eq!(theme, Theme::ansi16());
+
+        let err = load_theme(&config, Some("neon")).unwrap_err();
+        assert!(err.to_string().starts_with("Unknown theme 'neon'"));
+    }
+
+    #[test]
+    fn test_validate_filter() {
+        assert_eq!(validate_filter("cpu>20").unwrap(), "cpu>20");
+        let err = validate_filter("cpu>20 colour:red").unwrap_err();
+        assert_eq!(err.column, 8);
+    }
+
+    // ==================== Health Command Tests ====================
+
+    #[test]
+    fn test_run_health_local() {
+        let engine = config::Config::parse("").unwrap().rule_engine().unwrap();
+        let result = run_health(&engine, Severity::Info, None, None, None, None);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_run_health_unknown_rule() {
+        let engine = config::Config::parse("").unwrap().rule_engine().unwrap();
+        let result = run_health(
+            &engine,
+            Severity::Info,
+            None,
+            Some("no-such-rule".into()),
+            None,
+            None,
+        );
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("no-such-rule"));
+    }
+
+    // ==================== Watch Command Tests ====================
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("1s"), Ok(Duration::from_secs(1)));
+        assert_eq!(parse_interval("5"), Ok(Duration::from_secs(5)));
+        assert_eq!(parse_interval("2m"), Ok(Duration::from_secs(120)));
+        assert!(parse_interval("0s").is_err());
+        assert!(parse_interval("soon").is_err());
+    }
+
+    #[test]
+    fn test_write_events_ndjson() {
+        let event = |port, kind| events::PortEvent {
+            timestamp: 42,
+            kind,
+            port,
+            protocol: app::Protocol::Tcp,
+            pid: 7,
+            process_name: "node".into(),
+        };
+        let changes = vec![
+            event(3000, events::PortEventKind::Closed),
+            event(8080, events::PortEventKind::Opened),
+        ];
+
+        let mut out = Vec::new();
+        write_events(&mut out, &changes).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(r#"{"timestamp":42,"event":"closed","port":3000"#));
+        assert!(lines[1].starts_with(r#"{"timestamp":42,"event":"opened","port":8080"#));
+    }
+
+    #[test]
+    fn test_event_matches_filter() {
+        let entry = |port, pid, name: &str| app::PortEntry::test(port, pid, name);
+        let previous = vec![entry(5432, 1, "postgres")];
+        let current = vec![entry(8080, 2, "node")];
+        let changes = events::diff_entries(&previous, &current, 0);
+
+        // Closed ports are matched against the scan they were last seen in
+        let query = Query::parse("name:postgres").unwrap();
+        let kept: Vec<u16> = changes
+            .iter()
+            .filter(|e| event_matches(e, &query, &previous, &current))
+            .map(|e| e.port)
+            .collect();
+        assert_eq!(kept, vec![5432]);
+
+        let query = Query::parse("port:8000-9000").unwrap();
+        assert!(event_matches(&changes[1], &query, &previous, &current));
+        assert!(!event_matches(&changes[0], &query, &previous, &current));
+    }
+
+    #[test]
+    fn test_write_scan_error() {
+        let mut out = Vec::new();
+        let error = anyhow::anyhow!("Disconnected: Connection reset");
+        write_scan_error(&mut out, 42, &error).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(
+            text,
+            "{\"timestamp\":42,\"event\":\"error\",\"message\":\"Disconnected: Connection reset\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_events_empty() {
+        let mut out = Vec::new();
+        write_events(&mut out, &[]).unwrap();
+        assert!(out.is_empty());
+    }
+
     // ==================== Scan Ports Tests ====================
 
     #[test]
@@ -0,0 +1,233 @@
+//! Notifications for pinned ports
+//!
+//! When a pinned port goes down, comes up or changes owner the user is told
+//! via the terminal bell, an OSC 9 / OSC 777 desktop notification, and/or a
+//! command from the `[notify]` section of the config file. Events on remote
+//! hosts name the host, so several open hosts can be told apart.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::LOCAL_LABEL;
+use crate::events::{PortEvent, PortEventKind};
+
+/// Terminal escape sequence used for desktop notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OscStyle {
+    /// `ESC ] 9 ; message BEL` (iTerm2, Windows Terminal, ConEmu)
+    #[serde(rename = "9")]
+    Osc9,
+    /// `ESC ] 777 ; notify ; title ; message BEL` (urxvt, foot, VTE-based terminals)
+    #[serde(rename = "777")]
+    Osc777,
+}
+
+/// The `[notify]` config table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// Ring the terminal bell
+    pub bell: bool,
+    /// Send a desktop notification through the terminal
+    pub osc: Option<OscStyle>,
+    /// Shell command to run; the event is passed in `LSPORT_*` environment variables
+    pub command: Option<String>,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            bell: true,
+            osc: None,
+            command: None,
+        }
+    }
+}
+
+/// Delivers notifications for pinned port events
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    config: NotifyConfig,
+}
+
+impl Notifier {
+    pub fn new(config: NotifyConfig) -> Self {
+        Self { config }
+    }
+
+    /// Notify about one event on `host` (None for this machine), writing escape
+    /// sequences to `out` (the terminal)
+    pub fn notify(
+        &self,
+        event: &PortEvent,
+        host: Option<&str>,
+        out: &mut impl Write,
+    ) -> Result<()> {
+        let message = match host {
+            Some(host) => format!("{}: {}", host, event),
+            None => event.to_string(),
+        };
+        let sequence = terminal_sequence(&self.config, &message);
+        if !sequence.is_empty() {
+            out.write_all(sequence.as_bytes())?;
+            out.flush()?;
+        }
+
+        if let Some(command) = &self.config.command {
+            spawn_command(command, event, host.unwrap_or(LOCAL_LABEL), &message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bell and/or OSC escape sequences for a notification message
+pub fn terminal_sequence(config: &NotifyConfig, message: &str) -> String {
+    // Control characters would end the escape sequence early
+    let message: String = message.chars().filter(|c| !c.is_control()).collect();
+
+    let mut sequence = String::new();
+    match config.osc {
+        Some(OscStyle::Osc9) => sequence.push_str(&format!("\x1b]9;lsport: {}\x07", message)),
+        Some(OscStyle::Osc777) => {
+            sequence.push_str(&format!("\x1b]777;notify;lsport;{}\x07", message))
+        }
+        None => {}
+    }
+    if config.bell {
+        sequence.push('\x07');
+    }
+    sequence
+}
+
+/// Short name of an event kind, as used in `LSPORT_EVENT`
+fn event_name(kind: &PortEventKind) -> &'static str {
+    match kind {
+        PortEventKind::Opened => "opened",
+        PortEventKind::Closed => "closed",
+        PortEventKind::OwnerChanged { .. } => "owner_changed",
+    }
+}
+
+/// Run the user's command in the background without touching the TUI's terminal
+fn spawn_command(command: &str, event: &PortEvent, host: &str, message: &str) -> Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("LSPORT_EVENT", event_name(&event.kind))
+        .env("LSPORT_PORT", event.port.to_string())
+        .env("LSPORT_PROTOCOL", event.protocol.to_string())
+        .env("LSPORT_PID", event.pid.to_string())
+        .env("LSPORT_PROCESS", &event.process_name)
+        .env("LSPORT_HOST", host)
+        .env("LSPORT_MESSAGE", message)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run notify command \"{}\"", command))?;
+
+    // Reap the child in the background so it doesn't linger as a zombie
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Protocol;
+
+    fn event(kind: PortEventKind) -> PortEvent {
+        PortEvent {
+            timestamp: 0,
+            kind,
+            port: 8000,
+            protocol: Protocol::Tcp,
+            pid: 42,
+            process_name: "uvicorn".into(),
+        }
+    }
+
+    #[test]
+    fn test_terminal_sequence() {
+        let bell_only = NotifyConfig::default();
+        assert_eq!(terminal_sequence(&bell_only, "hi"), "\x07");
+
+        let osc9 = NotifyConfig {
+            bell: false,
+            osc: Some(OscStyle::Osc9),
+            command: None,
+        };
+        assert_eq!(terminal_sequence(&osc9, "hi"), "\x1b]9;lsport: hi\x07");
+
+        let osc777 = NotifyConfig {
+            bell: true,
+            osc: Some(OscStyle::Osc777),
+            command: None,
+        };
+        assert_eq!(
+            terminal_sequence(&osc777, "a\x07b"),
+            "\x1b]777;notify;lsport;ab\x07\x07"
+        );
+    }
+
+    #[test]
+    fn test_notify_writes_to_terminal() {
+        let notifier = Notifier::new(NotifyConfig {
+            bell: false,
+            osc: Some(OscStyle::Osc9),
+            command: None,
+        });
+
+        let mut out = Vec::new();
+        notifier
+            .notify(&event(PortEventKind::Closed), None, &mut out)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b]9;lsport: 8000/TCP closed (was uvicorn (42))\x07"
+        );
+
+        // Remote events say which host they're from
+        let mut out = Vec::new();
+        notifier
+            .notify(&event(PortEventKind::Closed), Some("web1"), &mut out)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b]9;lsport: web1: 8000/TCP closed (was uvicorn (42))\x07"
+        );
+    }
+
+    #[test]
+    fn test_notify_runs_command() {
+        let path = std::env::temp_dir().join(format!("lsport-notify-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let notifier = Notifier::new(NotifyConfig {
+            bell: false,
+            osc: None,
+            command: Some(format!(
+                "echo \"$LSPORT_HOST $LSPORT_EVENT $LSPORT_PORT $LSPORT_PROCESS\" > {}",
+                path.display()
+            )),
+        });
+        notifier
+            .notify(&event(PortEventKind::Opened), Some("web1"), &mut Vec::new())
+            .unwrap();
+
+        // The command runs in the background
+        let mut contents = String::new();
+        for _ in 0..50 {
+            contents = std::fs::read_to_string(&path).unwrap_or_default();
+            if !contents.is_empty() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(contents, "web1 opened 8000 uvicorn\n");
+    }
+}
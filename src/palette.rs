@@ -0,0 +1,406 @@
+//! Command palette
+//!
+//! `:` opens a k9s-style command line. Every table action can be run by its
+//! `[keys]` name (`:details`, `:sort_cpu`, `:quit`), and a few commands take
+//! arguments:
+//!
+//! ```text
+//! :kill 8080              kill the process on a port (or with a PID)
+//! :signal HUP 8080        send a signal (to the selected process without a target)
+//! :connect staging        connect to a host
+//! :filter proto:udp       filter the table
+//! :sort mem desc          sort by a column
+//! :view databases         apply a saved view
+//! :group tree             group rows by PID, process name or process tree (off: flat table)
+//! :pin 8080 / :unpin 8080 pin or unpin a port, even one that is down
+//! :export json out.json   write the visible rows to a file (json or csv)
+//! ```
+//!
+//! `Tab` completes command names and arguments (columns, signals, hosts, views
+//! and ports).
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::app::{App, SortColumn, SortOrder};
+use crate::export::ExportFormat;
+use crate::group::GroupBy;
+use crate::keymap::Action;
+use crate::scanner::Signal;
+
+/// Commands with arguments and their usage (without arguments, the ones that
+/// share a name with an action run that action instead)
+const COMMANDS: &[(&str, &str)] = &[
+    ("kill", "[PORT|PID]"),
+    ("signal", "SIGNAL [PORT|PID]"),
+    ("connect", "[HOST]"),
+    ("filter", "[QUERY]"),
+    ("sort", "COLUMN [asc|desc]"),
+    ("view", "[NAME]"),
+    ("group", "[pid|name|tree|off]"),
+    ("pin", "[PORT]"),
+    ("unpin", "PORT"),
+    ("export", "json|csv FILE"),
+];
+
+/// Column names accepted by `:sort`
+const SORT_COLUMNS: &[(&str, SortColumn)] = &[
+    ("port", SortColumn::Port),
+    ("proto", SortColumn::Protocol),
+    ("protocol", SortColumn::Protocol),
+    ("pid", SortColumn::Pid),
+    ("name", SortColumn::ProcessName),
+    ("cpu", SortColumn::CpuUsage),
+    ("mem", SortColumn::MemoryUsage),
+    ("memory", SortColumn::MemoryUsage),
+    ("uptime", SortColumn::Uptime),
+];
+
+/// A parsed palette command
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Run a table action, as if its key was pressed
+    Action(Action),
+    /// Kill the process on a port, or with a PID
+    Kill(u32),
+    /// Send a signal to the process on a port or with a PID (None: the selected one)
+    Signal(Signal, Option<u32>),
+    /// Connect to a host
+    Connect(String),
+    /// Replace the filter query
+    Filter(String),
+    /// Sort by a column (toggling the order when none is given)
+    Sort(SortColumn, Option<SortOrder>),
+    /// Apply a saved view
+    View(String),
+    /// Group the table (None: flat table)
+    Group(Option<GroupBy>),
+    /// Pin a port
+    Pin(u16),
+    /// Unpin a port
+    Unpin(u16),
+    /// Write the visible rows to a file
+    Export(ExportFormat, PathBuf),
+}
+
+impl Command {
+    /// Parse a command line (without the leading ':')
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        let (name, rest) = input
+            .split_once(char::is_whitespace)
+            .map_or((input, ""), |(name, rest)| (name, rest.trim()));
+        let args: Vec<&str> = rest.split_whitespace().collect();
+        if name.is_empty() {
+            bail!("Type a command (Tab completes)");
+        }
+
+        let usage = || {
+            let (_, usage) = COMMANDS.iter().find(|(n, _)| *n == name).unwrap();
+            anyhow!("Usage: :{} {}", name, usage)
+        };
+
+        let command = match (name, args.as_slice()) {
+            ("kill", [target]) => Command::Kill(parse_target(target)?),
+            ("signal", [signal]) => Command::Signal(Signal::parse(signal)?, None),
+            ("signal", [signal, target]) => {
+                Command::Signal(Signal::parse(signal)?, Some(parse_target(target)?))
+            }
+            ("connect", [host]) => Command::Connect(host.to_string()),
+            // The query may contain spaces
+            ("filter", [_, ..]) => Command::Filter(rest.to_string()),
+            ("sort", [column]) => Command::Sort(parse_sort_column(column)?, None),
+            ("sort", [column, order]) => {
+                Command::Sort(parse_sort_column(column)?, Some(parse_sort_order(order)?))
+            }
+            ("view", [_, ..]) => Command::View(rest.to_string()),
+            ("group", [by]) => Command::Group(GroupBy::parse(by)?),
+            ("pin", [port]) => Command::Pin(parse_port(port)?),
+            ("unpin", [port]) => Command::Unpin(parse_port(port)?),
+            ("export", [format, path]) => Command::Export(format.parse()?, PathBuf::from(path)),
+            _ => {
+                if let Some(action) = action_named(name) {
+                    if args.is_empty() {
+                        return Ok(Command::Action(action));
+                    }
+                }
+                if COMMANDS.iter().any(|(n, _)| *n == name) {
+                    return Err(usage());
+                }
+                if action_named(name).is_some() {
+                    bail!(":{} takes no arguments", name);
+                }
+                bail!("Unknown command ':{}' (Tab lists commands)", name);
+            }
+        };
+        Ok(command)
+    }
+}
+
+/// Usage hint for the command being typed, e.g. "sort COLUMN [asc|desc]"
+pub fn usage(input: &str) -> Option<String> {
+    let name = input.split_whitespace().next()?;
+    if let Some((name, usage)) = COMMANDS.iter().find(|(n, _)| *n == name) {
+        return Some(format!("{} {}", name, usage));
+    }
+    action_named(name).map(|action| format!("{} - {}", name, action.description()))
+}
+
+/// Completions for the last word of `input`, as whole command lines
+pub fn complete(input: &str, app: &App) -> Vec<String> {
+    let word_start = input.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+    let (prefix, word) = input.split_at(word_start);
+    let args: Vec<&str> = prefix.split_whitespace().collect();
+
+    let candidates: Vec<String> = match args.as_slice() {
+        [] => command_names(),
+        ["sort"] => SORT_COLUMNS.iter().map(|(n, _)| n.to_string()).collect(),
+        ["sort", _] => vec!["asc".to_string(), "desc".to_string()],
+        ["signal"] => Signal::names().map(str::to_string).collect(),
+        ["kill" | "pin"] | ["signal", _] => ports(app),
+        ["unpin"] => {
+            let host = app.command_pin_host();
+            app.pins
+                .ports(host.as_deref())
+                .map(|port| port.to_string())
+                .collect()
+        }
+        ["connect"] => hosts(app),
+        ["view"] => app.views.iter().map(|view| view.name.clone()).collect(),
+        ["group"] => GroupBy::NAMES.iter().map(|n| n.to_string()).collect(),
+        ["export"] => ExportFormat::NAMES.iter().map(|n| n.to_string()).collect(),
+        _ => Vec::new(),
+    };
+
+    let word_lower = word.to_ascii_lowercase();
+    let mut matches: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let line = format!("{}{}", prefix, candidate);
+        if candidate.to_ascii_lowercase().starts_with(&word_lower) && !matches.contains(&line) {
+            matches.push(line);
+        }
+    }
+    matches
+}
+
+/// Command and action names, sorted
+fn command_names() -> Vec<String> {
+    let mut names: Vec<String> = COMMANDS
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .chain(Action::all_in_table().map(|action| action.name().to_string()))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Ports in the table, in table order
+fn ports(app: &App) -> Vec<String> {
+    app.entries
+        .iter()
+        .map(|entry| entry.port.to_string())
+        .collect()
+}
+
+/// Recent and inventory hosts (as in the host picker), hosts from the config file,
+/// and the one currently connected to
+fn hosts(app: &App) -> Vec<String> {
+    let picker = app
+        .inventory
+        .choices("")
+        .into_iter()
+        .map(|choice| choice.name);
+    picker
+        .chain(
+            app.config
+                .defaults
+                .host
+                .iter()
+                .chain(app.config.hosts.keys())
+                .chain(app.remote_host.iter())
+                .cloned(),
+        )
+        .collect()
+}
+
+/// The table action with a `[keys]` name
+fn action_named(name: &str) -> Option<Action> {
+    Action::all_in_table().find(|action| action.name() == name)
+}
+
+fn parse_target(text: &str) -> Result<u32> {
+    text.parse()
+        .with_context(|| format!("Expected a port or PID, got '{}'", text))
+}
+
+fn parse_port(text: &str) -> Result<u16> {
+    text.parse()
+        .with_context(|| format!("Expected a port number, got '{}'", text))
+}
+
+fn parse_sort_column(text: &str) -> Result<SortColumn> {
+    SORT_COLUMNS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(text))
+        .map(|(_, column)| *column)
+        .ok_or_else(|| {
+            anyhow!(
+                "Unknown column '{}' (expected port, proto, pid, name, cpu, mem or uptime)",
+                text
+            )
+        })
+}
+
+fn parse_sort_order(text: &str) -> Result<SortOrder> {
+    match text.to_ascii_lowercase().as_str() {
+        "asc" => Ok(SortOrder::Ascending),
+        "desc" => Ok(SortOrder::Descending),
+        _ => bail!("Unknown sort order '{}' (expected asc or desc)", text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{PortEntry, ProcessState};
+    use crate::inventory::Inventory;
+
+    fn app_with_ports(ports: &[u16]) -> App {
+        let mut app = App::new();
+        app.entries = ports
+            .iter()
+            .map(|&port| PortEntry {
+                state: ProcessState::Running,
+                ..PortEntry::test(port, u32::from(port) + 1, "node")
+            })
+            .collect();
+        app
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(Command::parse("kill 8080").unwrap(), Command::Kill(8080));
+        assert_eq!(
+            Command::parse("connect staging").unwrap(),
+            Command::Connect("staging".to_string())
+        );
+        assert_eq!(
+            Command::parse("filter proto:udp  cpu>20").unwrap(),
+            Command::Filter("proto:udp  cpu>20".to_string())
+        );
+        assert_eq!(
+            Command::parse("sort mem desc").unwrap(),
+            Command::Sort(SortColumn::MemoryUsage, Some(SortOrder::Descending))
+        );
+        assert_eq!(
+            Command::parse("sort CPU").unwrap(),
+            Command::Sort(SortColumn::CpuUsage, None)
+        );
+        assert_eq!(
+            Command::parse("export json out.json").unwrap(),
+            Command::Export(ExportFormat::Json, PathBuf::from("out.json"))
+        );
+        assert_eq!(Command::parse("unpin 5432").unwrap(), Command::Unpin(5432));
+        assert_eq!(
+            Command::parse("group name").unwrap(),
+            Command::Group(Some(GroupBy::Name))
+        );
+        assert_eq!(Command::parse("group off").unwrap(), Command::Group(None));
+
+        let Command::Signal(signal, target) = Command::parse("signal sighup 8080").unwrap() else {
+            panic!("expected a signal command");
+        };
+        assert_eq!((signal.name(), target), ("HUP", Some(8080)));
+    }
+
+    #[test]
+    fn test_parse_actions() {
+        assert_eq!(
+            Command::parse("  details ").unwrap(),
+            Command::Action(Action::Details)
+        );
+        assert_eq!(
+            Command::parse("sort_cpu").unwrap(),
+            Command::Action(Action::SortBy(SortColumn::CpuUsage))
+        );
+        // Commands that share a name with an action run it without arguments
+        assert_eq!(
+            Command::parse("kill").unwrap(),
+            Command::Action(Action::Kill)
+        );
+        assert_eq!(
+            Command::parse("filter").unwrap(),
+            Command::Action(Action::Filter)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |input: &str| Command::parse(input).unwrap_err().to_string();
+        assert_eq!(error("sort"), "Usage: :sort COLUMN [asc|desc]");
+        assert_eq!(error("export json"), "Usage: :export json|csv FILE");
+        assert_eq!(error("quit now"), ":quit takes no arguments");
+        assert_eq!(
+            error("frobnicate"),
+            "Unknown command ':frobnicate' (Tab lists commands)"
+        );
+        assert!(error("kill http").starts_with("Expected a port or PID"));
+        assert!(error("pin 70000").starts_with("Expected a port number"));
+        assert!(error("sort size").starts_with("Unknown column 'size'"));
+        assert!(error("signal FOO").starts_with("Unknown signal 'FOO'"));
+        assert!(error("export xml out.xml").starts_with("Unknown export format"));
+    }
+
+    #[test]
+    fn test_complete_command_names() {
+        let app = App::new();
+        assert_eq!(complete("si", &app), vec!["signal"]);
+        assert_eq!(complete("sort", &app)[0], "sort");
+        assert!(complete("sort", &app).contains(&"sort_cpu".to_string()));
+        assert!(complete("", &app).contains(&"quit".to_string()));
+        assert!(complete("xyz", &app).is_empty());
+    }
+
+    #[test]
+    fn test_complete_arguments() {
+        let app = app_with_ports(&[3000, 8080, 8081]);
+        assert_eq!(complete("sort m", &app), vec!["sort mem", "sort memory"]);
+        assert_eq!(complete("sort cpu d", &app), vec!["sort cpu desc"]);
+        assert_eq!(complete("signal h", &app), vec!["signal HUP"]);
+        assert_eq!(complete("kill 80", &app), vec!["kill 8080", "kill 8081"]);
+        assert_eq!(complete("signal HUP 3", &app), vec!["signal HUP 3000"]);
+        assert_eq!(complete("export ", &app), vec!["export json", "export csv"]);
+        assert_eq!(complete("group n", &app), vec!["group name"]);
+        assert!(complete("export json ", &app).is_empty());
+    }
+
+    #[test]
+    fn test_complete_hosts() {
+        let mut app = App::new();
+        let inventory = r#"
+            [[hosts]]
+            label = "web1"
+            host = "deploy@10.0.0.11"
+        "#;
+        app.inventory = Inventory::parse(inventory).unwrap();
+        app.inventory.remember("staging.example.com").unwrap();
+        app.remote_host = Some("db1".to_string());
+        assert_eq!(
+            complete("connect ", &app),
+            vec!["connect staging.example.com", "connect web1", "connect db1"]
+        );
+        assert_eq!(complete("connect w", &app), vec!["connect web1"]);
+    }
+
+    #[test]
+    fn test_usage() {
+        assert_eq!(usage("sort m").unwrap(), "sort COLUMN [asc|desc]");
+        assert_eq!(
+            usage("details").unwrap(),
+            "details - Toggle process details"
+        );
+        assert!(usage("").is_none());
+    }
+}
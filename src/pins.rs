@@ -0,0 +1,192 @@
+//! Pinned ports
+//!
+//! Ports the user cares about (e.g. an API on 8000) are pinned from the TUI.
+//! Pins belong to a host (the local machine, or a remote host by its tab
+//! label), so a database pinned on one server doesn't show as down on the
+//! others. Pinned ports stay at the top of the table even while nothing
+//! listens on them, trigger notifications when they change, and are
+//! remembered across sessions in `$XDG_STATE_HOME/lsport/pins.toml` (falling
+//! back to `~/.local/state/lsport/pins.toml`).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// On-disk format of the pins file
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PinsFile {
+    /// Ports pinned on the local machine
+    ports: Vec<u16>,
+    /// Ports pinned on remote hosts, by host
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    hosts: BTreeMap<String, Vec<u16>>,
+}
+
+/// A pinned port: its host (None for the local machine) and number
+pub type Pin = (Option<String>, u16);
+
+/// Set of pinned ports, saved to disk on every change
+#[derive(Debug, Clone, Default)]
+pub struct Pins {
+    pins: BTreeSet<Pin>,
+    /// Where to persist the pins (None keeps them in memory only)
+    path: Option<PathBuf>,
+}
+
+impl Pins {
+    /// Load pins from the default state file (empty if it doesn't exist)
+    pub fn load() -> Result<Self> {
+        match pins_path() {
+            Some(path) => Self::load_from(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load pins from `path`, which is also where changes are saved
+    pub fn load_from(path: PathBuf) -> Result<Self> {
+        let mut pins = Self {
+            pins: BTreeSet::new(),
+            path: None,
+        };
+        if path.exists() {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let file: PinsFile = toml::from_str(&text)
+                .with_context(|| format!("Invalid pins file {}", path.display()))?;
+            let local = file.ports.into_iter().map(|port| (None, port));
+            let remote = file.hosts.into_iter().flat_map(|(host, ports)| {
+                ports
+                    .into_iter()
+                    .map(move |port| (Some(host.clone()), port))
+            });
+            pins.pins = local.chain(remote).collect();
+        }
+        pins.path = Some(path);
+        Ok(pins)
+    }
+
+    /// Whether a port is pinned on `host` (None for the local machine)
+    pub fn contains(&self, host: Option<&str>, port: u16) -> bool {
+        self.pins.contains(&(host.map(str::to_string), port))
+    }
+
+    /// Every pin, local ones first, then by host and port
+    pub fn iter(&self) -> impl Iterator<Item = &Pin> + '_ {
+        self.pins.iter()
+    }
+
+    /// Ports pinned on `host`, in ascending order
+    pub fn ports<'a>(&'a self, host: Option<&'a str>) -> impl Iterator<Item = u16> + 'a {
+        self.pins
+            .iter()
+            .filter(move |(pin_host, _)| pin_host.as_deref() == host)
+            .map(|&(_, port)| port)
+    }
+
+    /// Pin or unpin a port on `host` and save; returns whether the port is now pinned
+    pub fn toggle(&mut self, host: Option<&str>, port: u16) -> Result<bool> {
+        let pin = (host.map(str::to_string), port);
+        let pinned = if self.pins.remove(&pin) {
+            false
+        } else {
+            self.pins.insert(pin)
+        };
+        self.save()?;
+        Ok(pinned)
+    }
+
+    /// Write the pins file (creating its directory if needed)
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let mut file = PinsFile::default();
+        for (host, port) in &self.pins {
+            match host {
+                Some(host) => file.hosts.entry(host.clone()).or_default().push(*port),
+                None => file.ports.push(*port),
+            }
+        }
+        let text = toml::to_string(&file)?;
+        fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Path of the pins file (`$XDG_STATE_HOME/lsport/pins.toml`)
+pub fn pins_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs_next::home_dir().map(|home| home.join(".local").join("state")))?;
+    Some(base.join("lsport").join("pins.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("lsport-pins-{}-{}", name, std::process::id()))
+            .join("pins.toml")
+    }
+
+    #[test]
+    fn test_toggle_in_memory() {
+        let mut pins = Pins::default();
+        assert!(pins.toggle(None, 8000).unwrap());
+        assert!(pins.contains(None, 8000));
+        assert!(!pins.toggle(None, 8000).unwrap());
+        assert!(!pins.contains(None, 8000));
+    }
+
+    #[test]
+    fn test_pins_belong_to_a_host() {
+        let mut pins = Pins::default();
+        pins.toggle(Some("staging"), 5432).unwrap();
+        assert!(pins.contains(Some("staging"), 5432));
+        assert!(!pins.contains(Some("prod"), 5432));
+        assert!(!pins.contains(None, 5432));
+        assert_eq!(pins.ports(Some("staging")).collect::<Vec<_>>(), vec![5432]);
+        assert_eq!(pins.ports(None).count(), 0);
+    }
+
+    #[test]
+    fn test_pins_persist() {
+        let path = temp_path("persist");
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+
+        let mut pins = Pins::load_from(path.clone()).unwrap();
+        assert_eq!(pins.iter().count(), 0);
+        pins.toggle(None, 8000).unwrap();
+        pins.toggle(None, 5432).unwrap();
+        pins.toggle(Some("staging"), 5432).unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        let reloaded = Pins::load_from(path.clone()).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert_eq!(text, "ports = [5432, 8000]\n\n[hosts]\nstaging = [5432]\n");
+        assert_eq!(reloaded.ports(None).collect::<Vec<_>>(), vec![5432, 8000]);
+        assert!(reloaded.contains(Some("staging"), 5432));
+    }
+
+    #[test]
+    fn test_invalid_pins_file() {
+        let path = temp_path("invalid");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "ports = \"8000\"").unwrap();
+
+        let result = Pins::load_from(path.clone());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert!(result.is_err());
+    }
+}
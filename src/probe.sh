@@ -0,0 +1,74 @@
+# Remote probe: everything one scan needs, gathered in a single round trip.
+#
+# Run as `sh -s -- [os]` with this script on stdin; the OS is detected when
+# not given. Each section of the output starts with a `@@ports:<name>` line,
+# and the last line is `@@ports:end` so a cut-off run can be told apart.
+
+# Keep number formats parseable
+LC_ALL=C
+export LC_ALL
+
+os=${1:-$(uname -s)}
+printf '@@ports:os\n%s\n' "$os"
+
+case $os in
+Linux)
+    tcp=$(ss -tlnp 2>/dev/null || netstat -tlnp 2>/dev/null)
+    udp=$(ss -ulnp 2>/dev/null || netstat -ulnp 2>/dev/null)
+    printf '@@ports:tcp\n%s\n@@ports:udp\n%s\n' "$tcp" "$udp"
+    pids=$(printf '%s\n%s\n' "$tcp" "$udp" | grep -o 'pid=[0-9]*' | cut -d= -f2 | sort -un)
+
+    echo '@@ports:conns'
+    cat /proc/net/tcp /proc/net/tcp6 2>/dev/null
+
+    # One line per PID: pid<TAB>etime<TAB>exe<TAB>cwd<TAB>cmdline
+    echo '@@ports:details'
+    for p in $pids; do
+        printf '%s\t%s\t%s\t%s\t' "$p" "$(ps -o etime= -p "$p" 2>/dev/null)" \
+            "$(readlink "/proc/$p/exe" 2>/dev/null)" "$(readlink "/proc/$p/cwd" 2>/dev/null)"
+        tr '\0' ' ' < "/proc/$p/cmdline" 2>/dev/null
+        echo
+    done
+
+    # CPU ticks per second, uptime, then /proc/<pid>/stat per PID, for usage between scans
+    echo '@@ports:cpu'
+    getconf CLK_TCK 2>/dev/null || echo 100
+    cat /proc/uptime 2>/dev/null
+    for p in $pids; do
+        cat "/proc/$p/stat" 2>/dev/null
+    done
+
+    # Session of every process, to tell orphans from services init started
+    # (macOS ps has no session IDs, so orphans aren't detected there)
+    echo '@@ports:sessions'
+    ps -e -o pid=,sid= 2>/dev/null
+    ;;
+Darwin)
+    tcp=$(lsof -iTCP -sTCP:LISTEN -P -n 2>/dev/null)
+    udp=$(lsof -iUDP -P -n 2>/dev/null)
+    printf '@@ports:tcp\n%s\n@@ports:udp\n%s\n' "$tcp" "$udp"
+    pids=$(printf '%s\n%s\n' "$tcp" "$udp" | awk '$2 ~ /^[0-9]+$/ { print $2 }' | sort -un | paste -sd, -)
+
+    echo '@@ports:conns'
+    netstat -an -p tcp 2>/dev/null
+
+    if [ -n "$pids" ]; then
+        echo '@@ports:args'
+        ps -ww -o pid=,args= -p "$pids" 2>/dev/null
+        echo '@@ports:comm'
+        ps -ww -o pid=,etime=,comm= -p "$pids" 2>/dev/null
+        echo '@@ports:cwd'
+        lsof -a -d cwd -Fpn -p "$pids" 2>/dev/null
+    fi
+    ;;
+*)
+    echo '@@ports:tcp'
+    netstat -tlnp 2>/dev/null || netstat -an 2>/dev/null
+    echo '@@ports:conns'
+    netstat -an -p tcp 2>/dev/null
+    ;;
+esac
+
+echo '@@ports:ps'
+ps -e -o pid=,ppid=,stat=,rss=,pcpu=,user=,comm= 2>/dev/null
+echo '@@ports:end'
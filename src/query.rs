@@ -0,0 +1,592 @@
+//! Filter query language
+//!
+//! Used by the TUI `/` prompt and `--filter` on the CLI. A query is a list of
+//! whitespace-separated terms that must all match:
+//!
+//! ```text
+//! proto:udp port:8000-9000 cpu>20 mem>500MB user:alice name~^node addr:0.0.0.0 !name:sshd
+//! ```
+//!
+//! - `field:value` (or `field=value`) tests equality. Text is case-insensitive and
+//!   may use `*` wildcards; numbers also accept ranges (`8000-9000`) and lists
+//!   (`80,443`, which works for text too).
+//! - `field>n`, `>=`, `<`, `<=` compare numeric fields; `field~regex` matches text.
+//! - `!` in front of a term negates it, and `field!=value` is `!field:value`.
+//! - A bare word matches the process name, port or PID (as a regex when it is one).
+//! - Values with spaces can be quoted: `cmd:"npm run dev"`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::app::PortEntry;
+use crate::rules::{is_wildcard_addr, parse_duration, parse_size, state_keyword};
+
+/// Field names accepted in `field:value` terms (for error messages)
+const FIELD_NAMES: &str = "port, proto, pid, name, user, cpu, mem, uptime, addr, state, cmd, host";
+
+/// A query that failed to parse, with the 1-based column where the problem is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    pub column: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (column {})", self.message, self.column)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// Shorthand for building an error at a character offset
+fn error<T>(offset: usize, message: impl Into<String>) -> Result<T, QueryError> {
+    Err(QueryError {
+        message: message.into(),
+        column: offset + 1,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumField {
+    Port,
+    Pid,
+    Cpu,
+    Memory,
+    Uptime,
+}
+
+impl NumField {
+    fn value_of(self, entry: &PortEntry) -> Option<f64> {
+        match self {
+            NumField::Port => Some(f64::from(entry.port)),
+            NumField::Pid => Some(f64::from(entry.pid)),
+            NumField::Cpu => Some(f64::from(entry.cpu_usage)),
+            NumField::Memory => Some(entry.memory_usage as f64),
+            NumField::Uptime => entry.uptime_secs().map(|s| s as f64),
+        }
+    }
+
+    /// Parse a literal for this field (`8080`, `20%`, `500MB`, `2h`)
+    fn parse_value(self, raw: &str) -> Option<f64> {
+        match self {
+            NumField::Port | NumField::Pid => raw.parse::<u32>().ok().map(f64::from),
+            NumField::Cpu => raw.trim_end_matches('%').parse::<f64>().ok(),
+            NumField::Memory => parse_size(raw).map(|b| b as f64),
+            NumField::Uptime => parse_duration(raw).map(|d| d.as_secs() as f64),
+        }
+    }
+
+    fn example(self) -> &'static str {
+        match self {
+            NumField::Port => "8080",
+            NumField::Pid => "1234",
+            NumField::Cpu => "20 or 20%",
+            NumField::Memory => "500MB",
+            NumField::Uptime => "2h",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextField {
+    Name,
+    User,
+    Addr,
+    Proto,
+    State,
+    Cmd,
+    /// Host of the row in the fleet view
+    Host,
+}
+
+impl TextField {
+    fn value_of(self, entry: &PortEntry) -> Option<String> {
+        let value = match self {
+            TextField::Name => entry.process_name.clone(),
+            TextField::User => entry.user.clone()?,
+            TextField::Addr => entry.address.clone()?,
+            TextField::Proto => entry.protocol.to_string(),
+            TextField::State => state_keyword(entry),
+            TextField::Cmd => entry.command.clone()?,
+            TextField::Host => entry.host.clone()?,
+        };
+        Some(value.to_lowercase())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Num(NumField),
+    Text(TextField),
+}
+
+impl Field {
+    fn parse(word: &str) -> Option<Self> {
+        let field = match word.to_lowercase().as_str() {
+            "port" => Field::Num(NumField::Port),
+            "pid" => Field::Num(NumField::Pid),
+            "cpu" => Field::Num(NumField::Cpu),
+            "mem" | "memory" | "rss" => Field::Num(NumField::Memory),
+            "uptime" | "age" => Field::Num(NumField::Uptime),
+            "name" | "process" => Field::Text(TextField::Name),
+            "user" => Field::Text(TextField::User),
+            "addr" | "address" => Field::Text(TextField::Addr),
+            "proto" | "protocol" => Field::Text(TextField::Proto),
+            "state" => Field::Text(TextField::State),
+            "cmd" | "command" => Field::Text(TextField::Cmd),
+            "host" => Field::Text(TextField::Host),
+            _ => return None,
+        };
+        Some(field)
+    }
+}
+
+/// How a numeric field is compared
+#[derive(Debug, Clone, PartialEq)]
+enum NumTest {
+    Gt(f64),
+    Ge(f64),
+    Lt(f64),
+    Le(f64),
+    /// Matches if the value falls in any inclusive range (single values are `n-n`)
+    Within(Vec<(f64, f64)>),
+}
+
+impl NumTest {
+    fn matches(&self, value: f64) -> bool {
+        match self {
+            NumTest::Gt(n) => value > *n,
+            NumTest::Ge(n) => value >= *n,
+            NumTest::Lt(n) => value < *n,
+            NumTest::Le(n) => value <= *n,
+            NumTest::Within(ranges) => ranges.iter().any(|(lo, hi)| value >= *lo && value <= *hi),
+        }
+    }
+}
+
+/// How a text field is compared
+#[derive(Debug, Clone)]
+enum TextTest {
+    /// Lowercase patterns with `*` wildcards; any may match
+    Glob(Vec<String>),
+    Regex(Regex),
+}
+
+/// What a single term tests
+#[derive(Debug, Clone)]
+enum Predicate {
+    /// Bare word: name, port or PID, as a regex when it compiles
+    Any(TextTest),
+    Num(NumField, NumTest),
+    Text(TextField, TextTest),
+}
+
+impl Predicate {
+    fn matches(&self, entry: &PortEntry) -> bool {
+        match self {
+            Predicate::Any(TextTest::Regex(regex)) => {
+                regex.is_match(&entry.process_name)
+                    || regex.is_match(&entry.port.to_string())
+                    || regex.is_match(&entry.pid.to_string())
+            }
+            Predicate::Any(TextTest::Glob(words)) => {
+                let name = entry.process_name.to_lowercase();
+                words.iter().any(|word| {
+                    name.contains(word.as_str())
+                        || entry.port.to_string().contains(word.as_str())
+                        || entry.pid.to_string().contains(word.as_str())
+                })
+            }
+            Predicate::Num(field, test) => field.value_of(entry).is_some_and(|v| test.matches(v)),
+            Predicate::Text(field, test) => {
+                let Some(value) = field.value_of(entry) else {
+                    return false;
+                };
+                match test {
+                    TextTest::Regex(regex) => regex.is_match(&value),
+                    TextTest::Glob(patterns) => patterns.iter().any(|pattern| {
+                        glob_match(pattern, &value)
+                            || (*field == TextField::Addr
+                                && is_wildcard_addr(pattern)
+                                && is_wildcard_addr(&value))
+                    }),
+                }
+            }
+        }
+    }
+}
+
+/// One term of a query, possibly negated
+#[derive(Debug, Clone)]
+struct Term {
+    negated: bool,
+    predicate: Predicate,
+}
+
+/// A parsed filter query; every term must match
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+impl Query {
+    /// Parse a query, reporting the first problem with its column
+    pub fn parse(input: &str) -> Result<Self, QueryError> {
+        let terms = split_terms(input)?
+            .into_iter()
+            .map(|(offset, text)| parse_term(offset, &text))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { terms })
+    }
+
+    /// Whether an entry satisfies every term (an empty query matches everything)
+    pub fn matches(&self, entry: &PortEntry) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.predicate.matches(entry) != term.negated)
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Split the input on whitespace outside quotes, removing the quotes.
+/// Returns each term with the character offset it starts at.
+fn split_terms(input: &str) -> Result<Vec<(usize, String)>, QueryError> {
+    let mut terms = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    let mut quote_start = None;
+
+    for (offset, c) in input.chars().enumerate() {
+        if c == '"' {
+            quote_start = match quote_start {
+                Some(_) => None,
+                None => Some(offset),
+            };
+            current.get_or_insert_with(|| (offset, String::new()));
+        } else if c.is_whitespace() && quote_start.is_none() {
+            terms.extend(current.take());
+        } else {
+            current
+                .get_or_insert_with(|| (offset, String::new()))
+                .1
+                .push(c);
+        }
+    }
+
+    if let Some(offset) = quote_start {
+        return error(offset, "Unclosed quote");
+    }
+    terms.extend(current);
+    Ok(terms)
+}
+
+/// Comparison operators, longest first so `>=` wins over `>`
+const OPERATORS: [&str; 8] = [">=", "<=", "!=", ":", "=", ">", "<", "~"];
+
+fn parse_term(offset: usize, text: &str) -> Result<Term, QueryError> {
+    let (negated, body, body_offset) = match text.strip_prefix('!') {
+        Some(rest) => (true, rest, offset + 1),
+        None => (false, text, offset),
+    };
+    if body.is_empty() {
+        return error(offset, "Expected a term after '!'");
+    }
+
+    // `word<op>...` is a field test; anything else is a bare word
+    let name_len = body
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(body.len());
+    let rest = &body[name_len..];
+    let Some(op) = OPERATORS.into_iter().find(|op| rest.starts_with(op)) else {
+        return Ok(Term {
+            negated,
+            predicate: Predicate::Any(bare_word(body)),
+        });
+    };
+
+    let name = &body[..name_len];
+    if name.is_empty() {
+        return error(
+            body_offset,
+            format!("Expected a field name before '{}'", op),
+        );
+    }
+    let Some(field) = Field::parse(name) else {
+        return error(
+            body_offset,
+            format!("Unknown field '{}' (expected {})", name, FIELD_NAMES),
+        );
+    };
+
+    let value = &rest[op.len()..];
+    let value_offset = body_offset + name_len + op.len();
+    if value.is_empty() {
+        return error(
+            value_offset,
+            format!("Expected a value after '{}{}'", name, op),
+        );
+    }
+
+    // `field!=value` is shorthand for `!field:value`
+    let (negated, op) = if op == "!=" {
+        (!negated, ":")
+    } else {
+        (negated, op)
+    };
+
+    let predicate = match field {
+        Field::Num(field) => Predicate::Num(field, num_test(field, op, value, value_offset)?),
+        Field::Text(field) => {
+            Predicate::Text(field, text_test(field, name, op, value, value_offset)?)
+        }
+    };
+
+    Ok(Term { negated, predicate })
+}
+
+/// A bare word is a case-insensitive regex, or a substring if it isn't valid regex
+fn bare_word(word: &str) -> TextTest {
+    match Regex::new(&format!("(?i){}", word)) {
+        Ok(regex) => TextTest::Regex(regex),
+        Err(_) => TextTest::Glob(vec![word.to_lowercase()]),
+    }
+}
+
+fn num_test(field: NumField, op: &str, value: &str, offset: usize) -> Result<NumTest, QueryError> {
+    let parse = |raw: &str, offset: usize| {
+        field.parse_value(raw).ok_or_else(|| QueryError {
+            message: format!(
+                "Invalid value '{}' (expected e.g. {})",
+                raw,
+                field.example()
+            ),
+            column: offset + 1,
+        })
+    };
+
+    match op {
+        ">" => parse(value, offset).map(NumTest::Gt),
+        ">=" => parse(value, offset).map(NumTest::Ge),
+        "<" => parse(value, offset).map(NumTest::Lt),
+        "<=" => parse(value, offset).map(NumTest::Le),
+        "~" => error(offset - 1, "'~' only works on text fields"),
+        _ => {
+            let mut ranges = Vec::new();
+            let mut item_offset = offset;
+            for item in value.split(',') {
+                let range = match item.split_once('-') {
+                    Some((lo, hi)) => {
+                        let lo_value = parse(lo, item_offset)?;
+                        let hi_value = parse(hi, item_offset + lo.chars().count() + 1)?;
+                        if lo_value > hi_value {
+                            return error(item_offset, format!("Empty range '{}'", item));
+                        }
+                        (lo_value, hi_value)
+                    }
+                    None => {
+                        let n = parse(item, item_offset)?;
+                        (n, n)
+                    }
+                };
+                ranges.push(range);
+                item_offset += item.chars().count() + 1;
+            }
+            Ok(NumTest::Within(ranges))
+        }
+    }
+}
+
+fn text_test(
+    field: TextField,
+    name: &str,
+    op: &str,
+    value: &str,
+    offset: usize,
+) -> Result<TextTest, QueryError> {
+    match op {
+        "~" => Regex::new(&format!("(?i){}", value))
+            .map(TextTest::Regex)
+            .or_else(|e| {
+                error(
+                    offset,
+                    format!("Invalid regex: {}", first_line(&e.to_string())),
+                )
+            }),
+        ":" | "=" => {
+            let patterns: Vec<String> = value.split(',').map(str::to_lowercase).collect();
+            if field == TextField::Proto {
+                if let Some(bad) = patterns
+                    .iter()
+                    .find(|p| !matches!(p.as_str(), "tcp" | "udp"))
+                {
+                    return error(
+                        offset,
+                        format!("Unknown protocol '{}' (expected tcp or udp)", bad),
+                    );
+                }
+            }
+            Ok(TextTest::Glob(patterns))
+        }
+        _ => error(
+            offset - op.len(),
+            format!("'{}' only works on numeric fields, not '{}'", op, name),
+        ),
+    }
+}
+
+/// Regex errors span several lines; the last one says what went wrong
+fn first_line(message: &str) -> &str {
+    message
+        .lines()
+        .rfind(|line| line.starts_with("error:"))
+        .unwrap_or(message)
+}
+
+/// Case-sensitive match with `*` matching any run of characters
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let Some((first, rest)) = pattern.split_once('*') else {
+        return pattern == text;
+    };
+    let Some(mut remaining) = text.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let last = parts.pop().unwrap_or_default();
+    for part in parts {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.len() >= last.len() && remaining.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{ProcessState, Protocol};
+
+    fn entry(port: u16, protocol: Protocol, pid: u32, name: &str) -> PortEntry {
+        PortEntry {
+            protocol,
+            address: Some("0.0.0.0".into()),
+            user: Some("alice".into()),
+            state: ProcessState::Sleeping,
+            command: Some("node server.js --port 8080".into()),
+            ..PortEntry::test(port, pid, name)
+        }
+    }
+
+    fn matches(query: &str, entry: &PortEntry) -> bool {
+        Query::parse(query).unwrap().matches(entry)
+    }
+
+    #[test]
+    fn test_empty_query_matches_everything() {
+        let query = Query::parse("   ").unwrap();
+        assert!(query.terms.is_empty());
+        assert!(query.matches(&entry(80, Protocol::Tcp, 1, "nginx")));
+    }
+
+    #[test]
+    fn test_field_terms() {
+        let mut node = entry(8080, Protocol::Tcp, 4123, "node");
+        node.cpu_usage = 35.0;
+        node.memory_usage = 600 * 1024 * 1024;
+
+        assert!(matches("proto:tcp port:8000-9000", &node));
+        assert!(matches("port:80,8080 pid=4123", &node));
+        assert!(matches("cpu>20 mem>500MB", &node));
+        assert!(!matches("cpu>=50", &node));
+        assert!(matches("user:alice name~^no addr:0.0.0.0", &node));
+        assert!(matches("addr:::", &node)); // Wildcard addresses are interchangeable
+        assert!(matches("name:NO* state:sleeping", &node));
+        assert!(matches("cmd:\"*--port 8080\"", &node));
+        assert!(!matches("proto:udp", &node));
+        assert!(!matches("uptime>1h", &node)); // Unknown uptime never matches
+        assert!(!matches("host:web1", &node)); // Only fleet rows have a host
+        node.host = Some("web1".into());
+        assert!(matches("host:web*", &node));
+    }
+
+    #[test]
+    fn test_negation() {
+        let sshd = entry(22, Protocol::Tcp, 900, "sshd");
+        assert!(!matches("!name:sshd", &sshd));
+        assert!(!matches("name!=sshd", &sshd));
+        assert!(matches("!port:80", &sshd));
+        assert!(matches("!name!=sshd", &sshd));
+    }
+
+    #[test]
+    fn test_bare_words() {
+        let node = entry(3000, Protocol::Tcp, 42, "node");
+        assert!(matches("NODE", &node));
+        assert!(matches("^300[01]$", &node));
+        assert!(matches("42", &node));
+        assert!(!matches("node !3000", &node));
+
+        // Invalid regex falls back to a substring match
+        let bracketed = entry(53, Protocol::Udp, 7, "[kworker]");
+        assert!(matches("[kwork", &bracketed));
+    }
+
+    #[test]
+    fn test_errors_report_column() {
+        let err = Query::parse("proto:udp colour:red").unwrap_err();
+        assert!(err.message.starts_with("Unknown field 'colour'"));
+        assert_eq!(err.column, 11);
+
+        let err = Query::parse("cpu>").unwrap_err();
+        assert_eq!(err.message, "Expected a value after 'cpu>'");
+        assert_eq!(err.column, 5);
+
+        let err = Query::parse("mem>lots").unwrap_err();
+        assert_eq!(err.message, "Invalid value 'lots' (expected e.g. 500MB)");
+        assert_eq!(err.column, 5);
+
+        let err = Query::parse("port:9000-8000").unwrap_err();
+        assert_eq!(err.message, "Empty range '9000-8000'");
+
+        let err = Query::parse("name>3").unwrap_err();
+        assert_eq!(err.message, "'>' only works on numeric fields, not 'name'");
+        assert_eq!(err.column, 5);
+
+        let err = Query::parse("proto:sctp").unwrap_err();
+        assert_eq!(err.message, "Unknown protocol 'sctp' (expected tcp or udp)");
+
+        let err = Query::parse("name~(").unwrap_err();
+        assert!(err.message.starts_with("Invalid regex"));
+
+        let err = Query::parse("cmd:\"npm run").unwrap_err();
+        assert_eq!(
+            err,
+            QueryError {
+                message: "Unclosed quote".into(),
+                column: 5
+            }
+        );
+        assert_eq!(err.to_string(), "Unclosed quote (column 5)");
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("node", "node"));
+        assert!(!glob_match("node", "nodejs"));
+        assert!(glob_match("node*", "nodejs"));
+        assert!(glob_match("*js", "nodejs"));
+        assert!(glob_match("n*d*s", "nodejs"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("a*a", "a"));
+    }
+}
@@ -2,15 +2,37 @@
 //!
 //! This module provides functionality to scan ports on remote machines via SSH.
 
-use std::io::Read;
-use std::net::TcpStream;
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::fd::OwnedFd;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::process::Child;
+#[cfg(unix)]
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use ssh2::Session;
 
-use crate::app::{PortEntry, Protocol};
+use crate::app::{PortEntry, ProcessState, Protocol};
+use crate::scanner::{self, Signal};
+
+/// How often the session sends a keepalive, in seconds
+const KEEPALIVE_INTERVAL_SECS: u32 = 15;
+
+/// How long any blocking SSH call may take before the session counts as dropped
+const SESSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wait before the first reconnect attempt; doubled after each failure
+const RECONNECT_MIN: Duration = Duration::from_secs(1);
+
+/// Longest wait between reconnect attempts
+const RECONNECT_MAX: Duration = Duration::from_secs(60);
 
 /// Remote host connection configuration
 #[derive(Debug, Clone)]
@@ -23,6 +45,8 @@ pub struct RemoteConfig {
     pub port: u16,
     /// Path to private key (optional, uses ssh-agent if not provided)
     pub key_path: Option<PathBuf>,
+    /// Host to connect through (`ssh -W`), e.g. a bastion
+    pub jump: Option<Box<RemoteConfig>>,
 }
 
 impl RemoteConfig {
@@ -63,6 +87,7 @@ impl RemoteConfig {
             host,
             port,
             key_path: None,
+            jump: None,
         })
     }
 
@@ -78,10 +103,220 @@ impl RemoteConfig {
     }
 }
 
+/// Shell script run by every scan; see its header for the output format
+const PROBE_SCRIPT: &str = include_str!("probe.sh");
+
+/// The output of the probe script, by section
+#[derive(Debug, Default)]
+struct Probe {
+    sections: HashMap<String, String>,
+}
+
+impl Probe {
+    /// Split the output at its `@@ports:<name>` lines
+    fn parse(output: &str) -> Result<Self> {
+        let mut probe = Self::default();
+        let mut current = None;
+        let mut complete = false;
+        for line in output.lines() {
+            if let Some(name) = line.strip_prefix("@@ports:") {
+                complete = name == "end";
+                current = Some(probe.sections.entry(name.to_string()).or_default());
+            } else if let Some(section) = current.as_mut() {
+                section.push_str(line);
+                section.push('\n');
+            }
+        }
+        if !complete {
+            bail!("Remote scan was cut off");
+        }
+        Ok(probe)
+    }
+
+    /// A section's text, empty if the script didn't print it
+    fn section(&self, name: &str) -> &str {
+        self.sections.get(name).map_or("", String::as_str)
+    }
+}
+
+/// Process details gathered per PID on the remote host
+#[derive(Debug, Clone, Default)]
+struct ProcessDetails {
+    command: Option<String>,
+    exe_path: Option<String>,
+    cwd: Option<String>,
+    elapsed_secs: Option<u64>,
+}
+
+/// One row of the remote process table (`ps -e -o pid=,ppid=,stat=,rss=,pcpu=,user=,comm=`)
+#[derive(Debug, Clone)]
+struct ProcessRow {
+    ppid: u32,
+    state: ProcessState,
+    /// Resident memory in bytes
+    memory: u64,
+    /// CPU usage as `ps` reports it (averaged over the process lifetime on Linux)
+    cpu_usage: f32,
+    user: String,
+    name: String,
+}
+
+/// CPU time used by the listening processes at one moment (Linux)
+///
+/// Two samples give the usage between scans, like the local scanner reports.
+#[derive(Debug, Clone, Default)]
+struct CpuSample {
+    /// Seconds since the host booted, so the remote clock is the only one involved
+    uptime: f64,
+    /// User plus system CPU seconds, by PID
+    cpu_secs: HashMap<u32, f64>,
+}
+
+impl CpuSample {
+    /// Parse the `cpu` section: clock ticks per second, `/proc/uptime`, then `/proc/<pid>/stat` lines
+    fn parse(output: &str) -> Option<Self> {
+        let mut lines = output.lines();
+        let ticks: f64 = lines
+            .next()?
+            .trim()
+            .parse()
+            .ok()
+            .filter(|&t: &f64| t > 0.0)?;
+        let uptime = lines.next()?.split_whitespace().next()?.parse().ok()?;
+
+        let mut cpu_secs = HashMap::new();
+        for line in lines {
+            // The name in parentheses may contain spaces, so count fields after it
+            let Some((pid, rest)) = line.split_once(" (") else {
+                continue;
+            };
+            let Some((_, fields)) = rest.rsplit_once(") ") else {
+                continue;
+            };
+            let fields: Vec<&str> = fields.split_whitespace().collect();
+            // utime and stime are fields 14 and 15 of the whole line
+            let (Ok(pid), Some(Ok(utime)), Some(Ok(stime))) = (
+                pid.parse::<u32>(),
+                fields.get(11).map(|f| f.parse::<u64>()),
+                fields.get(12).map(|f| f.parse::<u64>()),
+            ) else {
+                continue;
+            };
+            cpu_secs.insert(pid, (utime + stime) as f64 / ticks);
+        }
+
+        Some(Self { uptime, cpu_secs })
+    }
+
+    /// CPU usage of `pid` since `earlier`, in percent of one core
+    fn usage_since(&self, earlier: &CpuSample, pid: u32) -> Option<f32> {
+        let elapsed = self.uptime - earlier.uptime;
+        let used = self.cpu_secs.get(&pid)? - earlier.cpu_secs.get(&pid)?;
+        if elapsed <= 0.0 || used < 0.0 {
+            return None;
+        }
+        Some((used / elapsed * 100.0) as f32)
+    }
+}
+
+/// Parse `ps -e -o pid=,sid=` output into session IDs by PID (Linux)
+fn parse_sessions(output: &str) -> HashMap<u32, u32> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?.parse().ok()?, fields.next()?.parse().ok()?))
+        })
+        .collect()
+}
+
+/// Convert an optional field into an owned string, treating blank values as missing
+fn non_empty(field: Option<&str>) -> Option<String> {
+    field
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(str::to_string)
+}
+
+/// Parse `ps -o etime` output (`[[dd-]hh:]mm:ss`) into seconds
+fn parse_etime(etime: &str) -> Option<u64> {
+    let etime = etime.trim();
+    if etime.is_empty() {
+        return None;
+    }
+
+    let (days, clock) = match etime.split_once('-') {
+        Some((days, clock)) => (days.parse::<u64>().ok()?, clock),
+        None => (0, etime),
+    };
+
+    let mut secs = 0;
+    for part in clock.split(':') {
+        secs = secs * 60 + part.parse::<u64>().ok()?;
+    }
+
+    Some(days * 86_400 + secs)
+}
+
+/// Start `ssh -W` to the jump host, forwarding a socket to the target's SSH port
+#[cfg(unix)]
+fn jump_stream(jump: &RemoteConfig, target: &RemoteConfig) -> Result<(UnixStream, Child)> {
+    let (ours, theirs) = UnixStream::pair().context("Failed to create a socket pair")?;
+    let mut command = Command::new("ssh");
+    // Never prompt: the terminal belongs to the TUI
+    command
+        .args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=10"])
+        .arg("-W")
+        .arg(format!("{}:{}", target.host, target.port))
+        .arg("-p")
+        .arg(jump.port.to_string())
+        .arg("-l")
+        .arg(&jump.username);
+    if let Some(key_path) = &jump.key_path {
+        command.arg("-i").arg(key_path);
+    }
+    let child = command
+        .arg(&jump.host)
+        .stdin(Stdio::from(OwnedFd::from(theirs.try_clone()?)))
+        .stdout(Stdio::from(OwnedFd::from(theirs)))
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run ssh to reach the jump host")?;
+    Ok((ours, child))
+}
+
+#[cfg(not(unix))]
+fn jump_stream(jump: &RemoteConfig, _target: &RemoteConfig) -> Result<(TcpStream, Child)> {
+    bail!("Connecting through {} needs a Unix system", jump.display())
+}
+
 /// Remote scanner that connects via SSH
 pub struct RemoteScanner {
     config: RemoteConfig,
     session: Option<Session>,
+    /// `ssh -W` process carrying the session through the jump host
+    jump: Option<Child>,
+    /// Set while the session is down
+    retry: Option<Retry>,
+    /// `uname -s` of the host, detected by the first scan of the session
+    os: OnceCell<String>,
+    /// CPU times from the previous scan (Linux)
+    last_cpu: RefCell<Option<CpuSample>>,
+}
+
+/// Why the session dropped and when to try again
+struct Retry {
+    reason: String,
+    /// Failed attempts in a row
+    attempts: u32,
+    at: Instant,
+}
+
+/// Wait before reconnect attempt `attempts + 1`: 1s, 2s, 4s, ... up to a minute
+fn backoff(attempts: u32) -> Duration {
+    RECONNECT_MIN
+        .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+        .min(RECONNECT_MAX)
 }
 
 impl RemoteScanner {
@@ -90,21 +325,72 @@ impl RemoteScanner {
         Self {
             config,
             session: None,
+            jump: None,
+            retry: None,
+            os: OnceCell::new(),
+            last_cpu: RefCell::new(None),
         }
     }
 
-    /// Connect to the remote host
+    /// Connect to the remote host; a failure schedules the next attempt
     pub fn connect(&mut self) -> Result<()> {
-        let addr = format!("{}:{}", self.config.host, self.config.port);
-        let tcp = TcpStream::connect_timeout(
-            &addr.parse().context("Invalid address")?,
-            Duration::from_secs(10),
-        )
-        .context(format!("Failed to connect to {}", addr))?;
+        self.drop_session();
+        match self.open() {
+            Ok(session) => {
+                self.session = Some(session);
+                Ok(())
+            }
+            Err(e) => {
+                self.drop_session();
+                self.schedule_retry(&e);
+                Err(e)
+            }
+        }
+    }
 
+    /// Open and authenticate a session
+    fn open(&mut self) -> Result<Session> {
         let mut session = Session::new().context("Failed to create SSH session")?;
-        session.set_tcp_stream(tcp);
-        session.handshake().context("SSH handshake failed")?;
+        // Without a timeout a dead network blocks the scan forever
+        session.set_timeout(SESSION_TIMEOUT.as_millis() as u32);
+        match &self.config.jump {
+            Some(jump) => {
+                let (stream, mut child) = jump_stream(jump, &self.config)?;
+                session.set_tcp_stream(stream);
+                if let Err(e) = session.handshake() {
+                    // ssh says why the jump failed (e.g. the bastion refused the key)
+                    let _ = child.kill();
+                    let mut stderr = String::new();
+                    if let Some(mut pipe) = child.stderr.take() {
+                        let _ = pipe.read_to_string(&mut stderr);
+                    }
+                    let _ = child.wait();
+                    let reason = stderr.lines().last().unwrap_or_default().trim().to_string();
+                    if reason.is_empty() {
+                        return Err(e)
+                            .context(format!("SSH handshake through {} failed", jump.display()));
+                    }
+                    bail!(
+                        "SSH handshake through {} failed: {}",
+                        jump.display(),
+                        reason
+                    );
+                }
+                self.jump = Some(child);
+            }
+            None => {
+                let addr = format!("{}:{}", self.config.host, self.config.port);
+                let socket_addr = addr
+                    .to_socket_addrs()
+                    .ok()
+                    .and_then(|mut addrs| addrs.next())
+                    .ok_or_else(|| anyhow!("Invalid address {}", addr))?;
+                let tcp = TcpStream::connect_timeout(&socket_addr, Duration::from_secs(10))
+                    .context(format!("Failed to connect to {}", addr))?;
+                session.set_tcp_stream(tcp);
+                session.handshake().context("SSH handshake failed")?;
+            }
+        }
 
         // Try authentication methods
         if let Some(ref key_path) = self.config.key_path {
@@ -147,7 +433,81 @@ impl RemoteScanner {
             return Err(anyhow!("SSH authentication failed"));
         }
 
-        self.session = Some(session);
+        session.set_keepalive(true, KEEPALIVE_INTERVAL_SECS);
+        Ok(session)
+    }
+
+    /// Close the session and the jump process behind it
+    fn drop_session(&mut self) {
+        self.session = None;
+        self.os = OnceCell::new();
+        if let Some(mut child) = self.jump.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    /// Back off before the next reconnect attempt
+    fn schedule_retry(&mut self, error: &anyhow::Error) {
+        let attempts = self.retry.as_ref().map_or(0, |retry| retry.attempts) + 1;
+        self.retry = Some(Retry {
+            reason: format!("{:#}", error),
+            attempts,
+            at: Instant::now() + backoff(attempts),
+        });
+    }
+
+    /// The disconnected state, for the context bar
+    fn disconnected(&self) -> anyhow::Error {
+        let Some(retry) = &self.retry else {
+            return anyhow!("Disconnected");
+        };
+        let wait = retry.at.saturating_duration_since(Instant::now());
+        anyhow!(
+            "Disconnected: {} (retrying in {}s)",
+            retry.reason,
+            wait.as_secs_f32().ceil() as u64
+        )
+    }
+
+    /// Scan, reconnecting first if the session dropped
+    ///
+    /// Any failure drops the session; it's restored on a later call once the
+    /// backoff has passed.
+    pub fn scan_reconnecting(&mut self) -> Result<Vec<PortEntry>> {
+        if self.session.is_none() {
+            if self
+                .retry
+                .as_ref()
+                .is_some_and(|retry| Instant::now() < retry.at)
+            {
+                return Err(self.disconnected());
+            }
+            if self.connect().is_err() {
+                return Err(self.disconnected());
+            }
+        }
+        let result = self.keepalive().and_then(|()| self.scan());
+        match result {
+            Ok(entries) => {
+                self.retry = None;
+                Ok(entries)
+            }
+            Err(e) => {
+                self.drop_session();
+                self.schedule_retry(&e);
+                Err(self.disconnected())
+            }
+        }
+    }
+
+    /// Send a keepalive if one is due, failing if the session is gone
+    fn keepalive(&self) -> Result<()> {
+        if let Some(session) = &self.session {
+            session
+                .keepalive_send()
+                .context("Connection lost (keepalive failed)")?;
+        }
         Ok(())
     }
 
@@ -158,6 +518,11 @@ impl RemoteScanner {
 
     /// Execute a command on the remote host
     fn exec(&self, command: &str) -> Result<String> {
+        self.exec_with_input(command, "")
+    }
+
+    /// Execute a command on the remote host, feeding `input` to its stdin
+    fn exec_with_input(&self, command: &str, input: &str) -> Result<String> {
         let session = self
             .session
             .as_ref()
@@ -167,6 +532,10 @@ impl RemoteScanner {
             .channel_session()
             .context("Failed to open channel")?;
         channel.exec(command).context("Failed to execute command")?;
+        channel
+            .write_all(input.as_bytes())
+            .context("Failed to send input")?;
+        channel.send_eof().context("Failed to send input")?;
 
         let mut output = String::new();
         channel
@@ -184,42 +553,213 @@ impl RemoteScanner {
             return Err(anyhow!("Not connected to remote host"));
         }
 
-        // Detect OS and use appropriate command
-        let os_output = self.exec("uname -s")?;
-        let os = os_output.trim();
+        // One round trip: the OS is only detected on the first scan of a session
+        let command = match self.os.get() {
+            Some(os) => format!("sh -s -- {}", os),
+            None => "sh -s".to_string(),
+        };
+        let probe = Probe::parse(&self.exec_with_input(&command, PROBE_SCRIPT)?)?;
+        let os = probe.section("os").trim();
+        if !os.is_empty() && os.chars().all(|c| c.is_ascii_alphanumeric()) {
+            let _ = self.os.set(os.to_string());
+        }
+
+        let mut entries = match os {
+            "Linux" => self.parse_linux_ports(probe.section("tcp"), probe.section("udp")),
+            "Darwin" => self.parse_macos_ports(probe.section("tcp"), probe.section("udp")),
+            _ => self.parse_generic_ports(probe.section("tcp")),
+        };
+
+        // Process details are best-effort: missing ones leave the fields empty
+        let details = match os {
+            "Linux" => self.parse_linux_details(probe.section("details")),
+            "Darwin" => self.parse_macos_details(
+                probe.section("args"),
+                probe.section("comm"),
+                probe.section("cwd"),
+            ),
+            _ => HashMap::new(),
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        for entry in &mut entries {
+            if let Some(detail) = details.get(&entry.pid) {
+                entry.command = detail.command.clone();
+                entry.exe_path = detail.exe_path.clone();
+                entry.cwd = detail.cwd.clone();
+                // Derive the start time from elapsed time so remote clock skew doesn't matter
+                entry.start_time = detail.elapsed_secs.map(|e| now.saturating_sub(e));
+            }
+        }
 
-        let entries = match os {
-            "Linux" => self.scan_linux()?,
-            "Darwin" => self.scan_macos()?,
-            _ => self.scan_generic()?,
+        let conns = probe.section("conns");
+        let connections = if os == "Linux" {
+            scanner::parse_proc_net_tcp(conns)
+        } else {
+            scanner::parse_netstat_established(conns)
         };
+        for entry in entries.iter_mut().filter(|e| e.protocol == Protocol::Tcp) {
+            entry.connections = Some(connections.get(&entry.port).copied().unwrap_or(0));
+        }
+
+        // Usage between this scan and the last beats ps's lifetime average, when there is one
+        let cpu = CpuSample::parse(probe.section("cpu"));
+        let last_cpu = self.last_cpu.replace(cpu.clone());
+        let cpu_since_last = |pid| cpu.as_ref()?.usage_since(last_cpu.as_ref()?, pid);
+
+        // State and parentage come from the full process table so parent names resolve
+        let table = self.parse_process_table(probe.section("ps"));
+        let sessions = parse_sessions(probe.section("sessions"));
+        for entry in &mut entries {
+            entry.session_leader_alive = sessions
+                .get(&entry.pid)
+                .map(|&sid| sid == entry.pid || table.contains_key(&sid));
+            if let Some(row) = table.get(&entry.pid) {
+                entry.cpu_usage = cpu_since_last(entry.pid).unwrap_or(row.cpu_usage);
+                entry.memory_usage = row.memory;
+                entry.memory_display = scanner::format_memory(row.memory);
+                entry.state = row.state;
+                entry.user = Some(row.user.clone());
+                entry.parent_pid = Some(row.ppid).filter(|&p| p > 0);
+                entry.parent_name = table.get(&row.ppid).map(|parent| parent.name.clone());
+                entry.ancestors = scanner::ancestry(Some(row.ppid), |pid| {
+                    table
+                        .get(&pid)
+                        .map(|row| (Some(row.ppid), row.name.clone()))
+                });
+            }
+            entry.detect_health_flags();
+        }
 
         Ok(entries)
     }
 
-    /// Scan on Linux using ss command
-    fn scan_linux(&self) -> Result<Vec<PortEntry>> {
-        // ss -tlnp for TCP, ss -ulnp for UDP
-        let tcp_output = self.exec("ss -tlnp 2>/dev/null || netstat -tlnp 2>/dev/null")?;
-        let udp_output = self.exec("ss -ulnp 2>/dev/null || netstat -ulnp 2>/dev/null")?;
+    /// Parse `ps -e -o pid=,ppid=,stat=,rss=,pcpu=,user=,comm=` output (same format on Linux
+    /// and macOS)
+    fn parse_process_table(&self, output: &str) -> HashMap<u32, ProcessRow> {
+        let mut table = HashMap::new();
 
-        let mut entries = Vec::new();
+        for line in output.lines() {
+            let mut fields = line.split_whitespace();
+            let (Some(pid), Some(ppid), Some(stat), Some(rss), Some(pcpu), Some(user)) = (
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+                fields.next(),
+            ) else {
+                continue;
+            };
+            let (Ok(pid), Ok(ppid)) = (pid.parse::<u32>(), ppid.parse::<u32>()) else {
+                continue;
+            };
+
+            // comm may contain spaces and, on macOS, is the full executable path;
+            // Linux procps appends " <defunct>" to zombies, which the state already says
+            let comm = fields.collect::<Vec<_>>().join(" ");
+            let comm = comm.strip_suffix(" <defunct>").unwrap_or(&comm);
+            let name = comm.rsplit('/').next().unwrap_or_default().to_string();
+
+            table.insert(
+                pid,
+                ProcessRow {
+                    ppid,
+                    state: ProcessState::from_ps_stat(stat),
+                    // ps reports RSS in KiB
+                    memory: rss.parse::<u64>().unwrap_or(0) * 1024,
+                    cpu_usage: pcpu.parse().unwrap_or(0.0),
+                    user: user.to_string(),
+                    name,
+                },
+            );
+        }
 
-        // Parse TCP
-        for line in tcp_output.lines().skip(1) {
-            if let Some(entry) = self.parse_ss_line(line, Protocol::Tcp) {
-                entries.push(entry);
+        table
+    }
+
+    /// Parse the per-PID detail lines produced by the Linux detail script
+    fn parse_linux_details(&self, output: &str) -> HashMap<u32, ProcessDetails> {
+        let mut details = HashMap::new();
+
+        for line in output.lines() {
+            let mut fields = line.splitn(5, '\t');
+            let pid: u32 = match fields.next().and_then(|p| p.trim().parse().ok()) {
+                Some(pid) => pid,
+                None => continue,
+            };
+
+            details.insert(
+                pid,
+                ProcessDetails {
+                    elapsed_secs: fields.next().and_then(parse_etime),
+                    exe_path: non_empty(fields.next()),
+                    cwd: non_empty(fields.next()),
+                    command: non_empty(fields.next()),
+                },
+            );
+        }
+
+        details
+    }
+
+    /// Parse `ps -o pid=,args=`, `ps -o pid=,etime=,comm=` and `lsof -Fpn` output (macOS)
+    fn parse_macos_details(
+        &self,
+        args_output: &str,
+        comm_output: &str,
+        cwd_output: &str,
+    ) -> HashMap<u32, ProcessDetails> {
+        let mut details: HashMap<u32, ProcessDetails> = HashMap::new();
+
+        for (output, is_args) in [(args_output, true), (comm_output, false)] {
+            for line in output.lines() {
+                let line = line.trim_start();
+                let Some((pid_str, rest)) = line.split_once(char::is_whitespace) else {
+                    continue;
+                };
+                let Ok(pid) = pid_str.parse::<u32>() else {
+                    continue;
+                };
+
+                let detail = details.entry(pid).or_default();
+                if is_args {
+                    detail.command = non_empty(Some(rest));
+                } else {
+                    let rest = rest.trim_start();
+                    let (etime, comm) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                    detail.elapsed_secs = parse_etime(etime);
+                    detail.exe_path = non_empty(Some(comm));
+                }
             }
         }
 
-        // Parse UDP
-        for line in udp_output.lines().skip(1) {
-            if let Some(entry) = self.parse_ss_line(line, Protocol::Udp) {
-                entries.push(entry);
+        // lsof -F output: "p<pid>" starts a process block, "n<path>" is the file name
+        let mut current_pid = None;
+        for line in cwd_output.lines() {
+            if let Some(pid_str) = line.strip_prefix('p') {
+                current_pid = pid_str.parse::<u32>().ok();
+            } else if let (Some(path), Some(pid)) = (line.strip_prefix('n'), current_pid) {
+                details.entry(pid).or_default().cwd = non_empty(Some(path));
             }
         }
 
-        Ok(entries)
+        details
+    }
+
+    /// Parse `ss -tlnp`/`ss -ulnp` output (Linux)
+    fn parse_linux_ports(&self, tcp: &str, udp: &str) -> Vec<PortEntry> {
+        let tcp = tcp
+            .lines()
+            .skip(1)
+            .filter_map(|line| self.parse_ss_line(line, Protocol::Tcp));
+        let udp = udp
+            .lines()
+            .skip(1)
+            .filter_map(|line| self.parse_ss_line(line, Protocol::Udp));
+        tcp.chain(udp).collect()
     }
 
     /// Parse a line from ss output
@@ -229,9 +769,11 @@ impl RemoteScanner {
             return None;
         }
 
-        // Extract local address (format: *:port or 0.0.0.0:port or [::]:port)
-        let local_addr = parts.get(4)?;
+        // Extract local address (format: *:port or 0.0.0.0:port or [::]:port); the columns
+        // are State, Recv-Q, Send-Q, Local, Peer, Process
+        let local_addr = parts.get(3)?;
         let port = self.extract_port(local_addr)?;
+        let address = self.extract_address(local_addr);
 
         // Extract PID and process name from the last column
         // Format: users:(("process",pid=1234,fd=5))
@@ -246,11 +788,25 @@ impl RemoteScanner {
             protocol,
             pid,
             process_name,
-            cpu_usage: 0.0, // Can't get CPU remotely easily
+            cpu_usage: 0.0,
             memory_usage: 0,
             memory_display: "-".to_string(),
-            has_parent: true,
+            address,
+            user: None,
+            parent_pid: None,
+            parent_name: None,
+            session_leader_alive: None,
+            state: ProcessState::Unknown,
             is_zombie: false,
+            is_orphan: false,
+            alerts: Vec::new(),
+            command: None,
+            exe_path: None,
+            cwd: None,
+            start_time: None,
+            ancestors: Vec::new(),
+            connections: None,
+            host: None,
         })
     }
 
@@ -279,26 +835,17 @@ impl RemoteScanner {
         (0, "unknown".to_string())
     }
 
-    /// Scan on macOS using netstat and lsof
-    fn scan_macos(&self) -> Result<Vec<PortEntry>> {
-        let output = self.exec("lsof -iTCP -sTCP:LISTEN -P -n 2>/dev/null")?;
-        let mut entries = Vec::new();
-
-        for line in output.lines().skip(1) {
-            if let Some(entry) = self.parse_lsof_line(line, Protocol::Tcp) {
-                entries.push(entry);
-            }
-        }
-
-        // UDP
-        let udp_output = self.exec("lsof -iUDP -P -n 2>/dev/null")?;
-        for line in udp_output.lines().skip(1) {
-            if let Some(entry) = self.parse_lsof_line(line, Protocol::Udp) {
-                entries.push(entry);
-            }
-        }
-
-        Ok(entries)
+    /// Parse `lsof -iTCP -sTCP:LISTEN`/`lsof -iUDP` output (macOS)
+    fn parse_macos_ports(&self, tcp: &str, udp: &str) -> Vec<PortEntry> {
+        let tcp = tcp
+            .lines()
+            .skip(1)
+            .filter_map(|line| self.parse_lsof_line(line, Protocol::Tcp));
+        let udp = udp
+            .lines()
+            .skip(1)
+            .filter_map(|line| self.parse_lsof_line(line, Protocol::Udp));
+        tcp.chain(udp).collect()
     }
 
     /// Parse a line from lsof output
@@ -313,6 +860,7 @@ impl RemoteScanner {
         let addr = parts[8];
 
         let port = self.extract_port(addr)?;
+        let address = self.extract_address(addr);
 
         Some(PortEntry {
             port,
@@ -322,25 +870,32 @@ impl RemoteScanner {
             cpu_usage: 0.0,
             memory_usage: 0,
             memory_display: "-".to_string(),
-            has_parent: true,
+            address,
+            user: None,
+            parent_pid: None,
+            parent_name: None,
+            session_leader_alive: None,
+            state: ProcessState::Unknown,
             is_zombie: false,
+            is_orphan: false,
+            alerts: Vec::new(),
+            command: None,
+            exe_path: None,
+            cwd: None,
+            start_time: None,
+            ancestors: Vec::new(),
+            connections: None,
+            host: None,
         })
     }
 
-    /// Generic scan using netstat
-    fn scan_generic(&self) -> Result<Vec<PortEntry>> {
-        let output = self.exec("netstat -tlnp 2>/dev/null || netstat -an 2>/dev/null")?;
-        let mut entries = Vec::new();
-
-        for line in output.lines() {
-            if line.contains("LISTEN") || line.contains("tcp") {
-                if let Some(entry) = self.parse_netstat_line(line) {
-                    entries.push(entry);
-                }
-            }
-        }
-
-        Ok(entries)
+    /// Parse `netstat` output on other systems
+    fn parse_generic_ports(&self, output: &str) -> Vec<PortEntry> {
+        output
+            .lines()
+            .filter(|line| line.contains("LISTEN") || line.contains("tcp"))
+            .filter_map(|line| self.parse_netstat_line(line))
+            .collect()
     }
 
     /// Parse a generic netstat line
@@ -353,6 +908,7 @@ impl RemoteScanner {
         // Try to find local address column (usually 4th)
         for part in &parts {
             if let Some(port) = self.extract_port(part) {
+                let address = self.extract_address(part);
                 return Some(PortEntry {
                     port,
                     protocol: Protocol::Tcp,
@@ -361,8 +917,22 @@ impl RemoteScanner {
                     cpu_usage: 0.0,
                     memory_usage: 0,
                     memory_display: "-".to_string(),
-                    has_parent: true,
+                    address,
+                    user: None,
+                    parent_pid: None,
+                    parent_name: None,
+                    session_leader_alive: None,
+                    state: ProcessState::Unknown,
                     is_zombie: false,
+                    is_orphan: false,
+                    alerts: Vec::new(),
+                    command: None,
+                    exe_path: None,
+                    cwd: None,
+                    start_time: None,
+                    ancestors: Vec::new(),
+                    connections: None,
+                    host: None,
                 });
             }
         }
@@ -386,6 +956,18 @@ impl RemoteScanner {
         None
     }
 
+    /// Extract the bind address from "addr:port" ("[::]:22" -> "::", "0.0.0.0%lo:53" -> "0.0.0.0")
+    fn extract_address(&self, addr: &str) -> Option<String> {
+        let (host, _) = addr.rsplit_once(':')?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let host = host.split('%').next().unwrap_or(host);
+        if host.is_empty() {
+            None
+        } else {
+            Some(host.to_string())
+        }
+    }
+
     /// Kill a process on the remote host
     pub fn kill_process(&self, pid: u32) -> Result<()> {
         if !self.is_connected() {
@@ -408,6 +990,27 @@ impl RemoteScanner {
         Ok(())
     }
 
+    /// Send a signal to a process on the remote host
+    pub fn send_signal(&self, pid: u32, signal: Signal) -> Result<()> {
+        if !self.is_connected() {
+            return Err(anyhow!("Not connected to remote host"));
+        }
+
+        let result = self.exec(&format!("kill -s {} {} 2>&1", signal.name(), pid))?;
+
+        if result.contains("No such process") {
+            return Err(anyhow!("Process {} not found", pid));
+        }
+
+        if result.contains("Operation not permitted") || result.contains("Permission denied") {
+            return Err(anyhow!(
+                "Permission denied. Try running with sudo on remote host."
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Force kill a process on the remote host (SIGKILL)
     pub fn kill_process_force(&self, pid: u32) -> Result<()> {
         if !self.is_connected() {
@@ -431,6 +1034,12 @@ impl RemoteScanner {
     }
 }
 
+impl Drop for RemoteScanner {
+    fn drop(&mut self) {
+        self.drop_session();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -472,6 +1081,22 @@ mod tests {
         assert_eq!(scanner.extract_port("*:22"), Some(22));
     }
 
+    #[test]
+    fn test_extract_address() {
+        let scanner = RemoteScanner::new(RemoteConfig::parse("test@localhost").unwrap());
+        assert_eq!(
+            scanner.extract_address("0.0.0.0:8080").as_deref(),
+            Some("0.0.0.0")
+        );
+        assert_eq!(scanner.extract_address("[::]:22").as_deref(), Some("::"));
+        assert_eq!(scanner.extract_address("*:5353").as_deref(), Some("*"));
+        assert_eq!(
+            scanner.extract_address("127.0.0.53%lo:53").as_deref(),
+            Some("127.0.0.53")
+        );
+        assert_eq!(scanner.extract_address("8080"), None);
+    }
+
     #[test]
     fn test_extract_port_ipv6() {
         let scanner = RemoteScanner::new(RemoteConfig::parse("test@localhost").unwrap());
@@ -486,4 +1111,174 @@ mod tests {
         assert_eq!(pid, 1234);
         assert_eq!(name, "node");
     }
+
+    #[test]
+    fn test_parse_linux_details() {
+        let scanner = RemoteScanner::new(RemoteConfig::parse("test@localhost").unwrap());
+        let output = "1234\t   01:02:03\t/usr/bin/node\t/srv/app\tnode server.js --port 3000 \n\
+                      5678\t\t\t\t\n";
+        let details = scanner.parse_linux_details(output);
+
+        let node = details.get(&1234).unwrap();
+        assert_eq!(node.exe_path.as_deref(), Some("/usr/bin/node"));
+        assert_eq!(node.cwd.as_deref(), Some("/srv/app"));
+        assert_eq!(node.command.as_deref(), Some("node server.js --port 3000"));
+        assert_eq!(node.elapsed_secs, Some(3_723));
+
+        // Permission denied on /proc leaves everything unknown
+        let hidden = details.get(&5678).unwrap();
+        assert!(hidden.exe_path.is_none());
+        assert!(hidden.cwd.is_none());
+        assert!(hidden.command.is_none());
+        assert!(hidden.elapsed_secs.is_none());
+    }
+
+    #[test]
+    fn test_parse_macos_details() {
+        let scanner = RemoteScanner::new(RemoteConfig::parse("test@localhost").unwrap());
+        let args = "  412 /usr/local/bin/redis-server 127.0.0.1:6379\n";
+        let comm = "  412 3-04:05:06 /usr/local/bin/redis-server\n";
+        let cwd = "p412\nfcwd\nn/usr/local/var/db/redis\n";
+        let details = scanner.parse_macos_details(args, comm, cwd);
+
+        let redis = details.get(&412).unwrap();
+        assert_eq!(
+            redis.command.as_deref(),
+            Some("/usr/local/bin/redis-server 127.0.0.1:6379")
+        );
+        assert_eq!(
+            redis.exe_path.as_deref(),
+            Some("/usr/local/bin/redis-server")
+        );
+        assert_eq!(redis.cwd.as_deref(), Some("/usr/local/var/db/redis"));
+        assert_eq!(
+            redis.elapsed_secs,
+            Some(3 * 86_400 + 4 * 3_600 + 5 * 60 + 6)
+        );
+    }
+
+    #[test]
+    fn test_parse_process_table() {
+        let scanner = RemoteScanner::new(RemoteConfig::parse("test@localhost").unwrap());
+        let output = "    1     0 Ss   12044  0.0 root     systemd\n\
+                      \x20 812     1 Ss     980  0.0 app      /usr/bin/tini\n\
+                      \x20 900   812 Sl   86312 12.5 app      node\n\
+                      \x20 901   900 Z        0  0.0 app      node <defunct>\n\
+                      garbage line\n";
+        let table = scanner.parse_process_table(output);
+
+        assert_eq!(table.len(), 4);
+        assert_eq!(table[&812].name, "tini");
+        assert_eq!(table[&812].ppid, 1);
+        assert_eq!(table[&1].user, "root");
+        assert_eq!(table[&900].state, ProcessState::Sleeping);
+        assert_eq!(table[&901].state, ProcessState::Zombie);
+        assert_eq!(table[&901].name, "node");
+        assert_eq!(table[&900].memory, 86312 * 1024);
+        assert_eq!(table[&900].cpu_usage, 12.5);
+    }
+
+    #[test]
+    fn test_parse_sessions() {
+        let sessions = parse_sessions("    1     1\n  812   812\n  901   640\nbad line\n");
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(sessions[&901], 640);
+    }
+
+    #[test]
+    fn test_cpu_usage_between_samples() {
+        let earlier = CpuSample::parse(
+            "100\n1000.00 3000.00\n\
+             812 (my server) S 1 812 812 0 -1 4194560 500 0 0 0 150 50 0 0 20 0 1 0 9000 0 0\n",
+        )
+        .unwrap();
+        let now = CpuSample::parse(
+            "100\n1002.00 3003.00\n\
+             812 (my server) S 1 812 812 0 -1 4194560 500 0 0 0 200 100 0 0 20 0 1 0 9000 0 0\n",
+        )
+        .unwrap();
+
+        // One CPU second over two seconds of uptime
+        assert_eq!(now.cpu_secs[&812], 3.0);
+        assert_eq!(now.usage_since(&earlier, 812), Some(50.0));
+        assert_eq!(now.usage_since(&earlier, 900), None);
+        assert!(CpuSample::parse("").is_none());
+    }
+
+    #[test]
+    fn test_parse_etime() {
+        assert_eq!(parse_etime("00:05"), Some(5));
+        assert_eq!(parse_etime("  12:34"), Some(12 * 60 + 34));
+        assert_eq!(parse_etime("01:00:00"), Some(3_600));
+        assert_eq!(parse_etime("2-00:00:01"), Some(2 * 86_400 + 1));
+        assert_eq!(parse_etime(""), None);
+        assert_eq!(parse_etime("garbage"), None);
+    }
+
+    #[test]
+    fn test_probe_splits_sections() {
+        let output = "@@ports:os\nLinux\n@@ports:tcp\nState Local\nLISTEN 0.0.0.0:22\n\
+                      @@ports:udp\n@@ports:end\n";
+        let probe = Probe::parse(output).unwrap();
+        assert_eq!(probe.section("os").trim(), "Linux");
+        assert_eq!(probe.section("tcp"), "State Local\nLISTEN 0.0.0.0:22\n");
+        assert_eq!(probe.section("udp"), "");
+        assert_eq!(probe.section("details"), "");
+    }
+
+    #[test]
+    fn test_probe_rejects_cut_off_output() {
+        let result = Probe::parse("@@ports:os\nLinux\n@@ports:tcp\nState Local\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_linux_ports() {
+        let scanner = RemoteScanner::new(RemoteConfig::parse("test@localhost").unwrap());
+        let tcp = "State  Recv-Q Send-Q Local Address:Port Peer Address:Port Process\n\
+                   LISTEN 0 511 0.0.0.0:80 0.0.0.0:* users:((\"nginx\",pid=812,fd=6))\n\
+                   LISTEN 0 128 [::]:22 [::]:* users:((\"sshd\",pid=640,fd=4))\n";
+        let udp = "State Recv-Q Send-Q Local Address:Port Peer Address:Port Process\n\
+                   UNCONN 0 0 127.0.0.53%lo:53 0.0.0.0:* users:((\"systemd-resolve\",pid=501,fd=13))\n";
+        let entries = scanner.parse_linux_ports(tcp, udp);
+
+        let ports: Vec<_> = entries
+            .iter()
+            .map(|e| (e.port, e.protocol, e.pid))
+            .collect();
+        assert_eq!(
+            ports,
+            vec![
+                (80, Protocol::Tcp, 812),
+                (22, Protocol::Tcp, 640),
+                (53, Protocol::Udp, 501)
+            ]
+        );
+        assert_eq!(entries[2].process_name, "systemd-resolve");
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_a_minute() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(8));
+        assert_eq!(backoff(7), Duration::from_secs(60));
+        assert_eq!(backoff(100), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_waits_out_backoff_before_reconnecting() {
+        let mut scanner = RemoteScanner::new(RemoteConfig::parse("user@web1").unwrap());
+        scanner.retry = Some(Retry {
+            reason: "Failed to read output".to_string(),
+            attempts: 3,
+            at: Instant::now() + Duration::from_secs(30),
+        });
+
+        let error = scanner.scan_reconnecting().unwrap_err().to_string();
+        assert!(error.starts_with("Disconnected: Failed to read output"));
+        assert!(error.ends_with("(retrying in 30s)"));
+        // Waiting doesn't count as another failed attempt
+        assert_eq!(scanner.retry.as_ref().unwrap().attempts, 3);
+    }
 }
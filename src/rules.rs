@@ -0,0 +1,725 @@
+//! Health rules engine
+//!
+//! Rules are small conditions evaluated against every `PortEntry` on each scan.
+//! A rule that matches attaches a named `HealthAlert` with a severity to the entry,
+//! which is rendered as a badge in the table, shown in `describe` and listed by
+//! `lsport health`.
+//!
+//! Rule syntax (clauses are joined with `and`, an optional `for` must come last):
+//!
+//! ```text
+//! cpu > 80% for 30s
+//! mem >= 2GB
+//! addr = 0.0.0.0 and port not in [22, 80, 443]
+//! uptime > 7d and name = node
+//! ```
+//!
+//! Fields: `cpu`, `mem`/`memory`, `uptime`, `port`, `pid`, `name`, `proto`,
+//! `addr`/`address` and `state`. Operators: `>`, `>=`, `<`, `<=`, `=`, `!=`,
+//! `~` (contains, text fields only), `in [..]` and `not in [..]`.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::PortEntry;
+
+/// Severity of a health alert, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    #[default]
+    Warning,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // pad() so width specifiers line up in `lsport health` output
+        f.pad(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+impl FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "info" => Ok(Severity::Info),
+            "warning" | "warn" => Ok(Severity::Warning),
+            "critical" | "crit" => Ok(Severity::Critical),
+            _ => Err(anyhow!(
+                "Invalid severity '{}' (expected info, warning or critical)",
+                s
+            )),
+        }
+    }
+}
+
+/// A rule that matched an entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthAlert {
+    /// Name of the rule that fired
+    pub rule: String,
+    /// Severity configured for the rule
+    pub severity: Severity,
+}
+
+/// Field of a `PortEntry` that a clause tests
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Cpu,
+    Memory,
+    Uptime,
+    Port,
+    Pid,
+    Name,
+    Proto,
+    Addr,
+    State,
+}
+
+impl Field {
+    fn parse(word: &str) -> Result<Self> {
+        match word.to_lowercase().as_str() {
+            "cpu" => Ok(Field::Cpu),
+            "mem" | "memory" | "rss" => Ok(Field::Memory),
+            "uptime" | "age" => Ok(Field::Uptime),
+            "port" => Ok(Field::Port),
+            "pid" => Ok(Field::Pid),
+            "name" | "process" => Ok(Field::Name),
+            "proto" | "protocol" => Ok(Field::Proto),
+            "addr" | "address" => Ok(Field::Addr),
+            "state" => Ok(Field::State),
+            _ => bail!("Unknown field '{}'", word),
+        }
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Field::Cpu | Field::Memory | Field::Uptime | Field::Port | Field::Pid
+        )
+    }
+
+    /// Parse a literal for this field (`80%`, `2GB`, `7d`, ...)
+    fn parse_value(self, raw: &str) -> Result<Value> {
+        let number = match self {
+            Field::Cpu => raw.trim_end_matches('%').parse::<f64>().ok(),
+            Field::Memory => parse_size(raw).map(|b| b as f64),
+            Field::Uptime => parse_duration(raw).map(|d| d.as_secs() as f64),
+            Field::Port | Field::Pid => raw.parse::<u32>().ok().map(f64::from),
+            _ => return Ok(Value::Text(raw.to_lowercase())),
+        };
+        number
+            .map(Value::Number)
+            .ok_or_else(|| anyhow!("Invalid value '{}' for {:?}", raw, self))
+    }
+
+    /// Current value of this field for an entry (None when unknown)
+    fn value_of(self, entry: &PortEntry) -> Option<Value> {
+        let value = match self {
+            Field::Cpu => Value::Number(f64::from(entry.cpu_usage)),
+            Field::Memory => Value::Number(entry.memory_usage as f64),
+            Field::Uptime => Value::Number(entry.uptime_secs()? as f64),
+            Field::Port => Value::Number(f64::from(entry.port)),
+            Field::Pid => Value::Number(f64::from(entry.pid)),
+            Field::Name => Value::Text(entry.process_name.to_lowercase()),
+            Field::Proto => Value::Text(entry.protocol.to_string().to_lowercase()),
+            Field::Addr => Value::Text(entry.address.as_deref()?.to_lowercase()),
+            Field::State => Value::Text(state_keyword(entry)),
+        };
+        Some(value)
+    }
+}
+
+/// Single-word state name used by `state = ...` clauses
+pub fn state_keyword(entry: &PortEntry) -> String {
+    use crate::app::ProcessState;
+    match entry.state {
+        ProcessState::Running => "running",
+        ProcessState::Sleeping => "sleeping",
+        ProcessState::DiskSleep => "disk-sleep",
+        ProcessState::Idle => "idle",
+        ProcessState::Stopped => "stopped",
+        ProcessState::Zombie => "zombie",
+        ProcessState::Dead => "dead",
+        ProcessState::Unknown => "unknown",
+    }
+    .to_string()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl Value {
+    /// Equality with wildcard bind addresses (0.0.0.0, :: and *) treated as one
+    fn matches(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Text(a), Value::Text(b)) => {
+                a == b || (is_wildcard_addr(a) && is_wildcard_addr(b))
+            }
+            _ => self == other,
+        }
+    }
+}
+
+/// Whether an address means "every interface"
+pub fn is_wildcard_addr(addr: &str) -> bool {
+    matches!(addr, "0.0.0.0" | "::" | "*")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Contains,
+    In,
+    NotIn,
+}
+
+/// One `field op value` test
+#[derive(Debug, Clone)]
+struct Clause {
+    field: Field,
+    op: Op,
+    values: Vec<Value>,
+}
+
+impl Clause {
+    fn matches(&self, entry: &PortEntry) -> bool {
+        let Some(actual) = self.field.value_of(entry) else {
+            return false;
+        };
+        let any_equal = || self.values.iter().any(|v| actual.matches(v));
+
+        match (self.op, &actual, &self.values[0]) {
+            (Op::Eq | Op::In, _, _) => any_equal(),
+            (Op::Ne | Op::NotIn, _, _) => !any_equal(),
+            (Op::Contains, Value::Text(a), Value::Text(b)) => a.contains(b.as_str()),
+            (Op::Gt, Value::Number(a), Value::Number(b)) => a > b,
+            (Op::Ge, Value::Number(a), Value::Number(b)) => a >= b,
+            (Op::Lt, Value::Number(a), Value::Number(b)) => a < b,
+            (Op::Le, Value::Number(a), Value::Number(b)) => a <= b,
+            _ => false,
+        }
+    }
+}
+
+/// A named health rule
+#[derive(Debug, Clone)]
+pub struct Rule {
+    /// Rule name shown in badges
+    pub name: String,
+    /// Severity of alerts raised by this rule
+    pub severity: Severity,
+    /// How long the condition must hold before the rule fires
+    pub sustain: Option<Duration>,
+    clauses: Vec<Clause>,
+}
+
+impl Rule {
+    /// Parse a rule from its condition text
+    pub fn parse(name: &str, condition: &str, severity: Severity) -> Result<Self> {
+        let tokens = tokenize(condition);
+        let mut pos = 0;
+        let mut clauses = Vec::new();
+        let mut sustain = None;
+
+        loop {
+            clauses.push(parse_clause(&tokens, &mut pos)?);
+
+            match tokens.get(pos).map(|t| t.to_lowercase()) {
+                None => break,
+                Some(word) if word == "and" || word == "&&" => pos += 1,
+                Some(word) if word == "for" => {
+                    let raw = tokens
+                        .get(pos + 1)
+                        .ok_or_else(|| anyhow!("Expected a duration after 'for'"))?;
+                    sustain = Some(
+                        parse_duration(raw).ok_or_else(|| anyhow!("Invalid duration '{}'", raw))?,
+                    );
+                    if let Some(extra) = tokens.get(pos + 2) {
+                        bail!("Unexpected '{}' after 'for {}'", extra, raw);
+                    }
+                    break;
+                }
+                Some(_) => bail!("Expected 'and' or 'for', found '{}'", tokens[pos]),
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            severity,
+            sustain,
+            clauses,
+        })
+    }
+
+    /// Whether every clause matches the entry (ignores `for`)
+    pub fn matches(&self, entry: &PortEntry) -> bool {
+        self.clauses.iter().all(|clause| clause.matches(entry))
+    }
+
+    fn alert(&self) -> HealthAlert {
+        HealthAlert {
+            rule: self.name.clone(),
+            severity: self.severity,
+        }
+    }
+}
+
+/// Split a condition into words, operators and list punctuation
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if matches!(c, '[' | ']' | ',') {
+            tokens.push(c.to_string());
+            chars.next();
+        } else if matches!(c, '<' | '>' | '=' | '!' | '~') {
+            let mut op = String::new();
+            while let Some(&c) = chars.peek() {
+                if !matches!(c, '<' | '>' | '=' | '!' | '~') {
+                    break;
+                }
+                op.push(c);
+                chars.next();
+            }
+            tokens.push(op);
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || "[],<>=!~".contains(c) {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(word);
+        }
+    }
+
+    tokens
+}
+
+fn parse_clause(tokens: &[String], pos: &mut usize) -> Result<Clause> {
+    let field_word = tokens
+        .get(*pos)
+        .ok_or_else(|| anyhow!("Expected a condition"))?;
+    let field = Field::parse(field_word)?;
+    *pos += 1;
+
+    let op_word = tokens
+        .get(*pos)
+        .ok_or_else(|| anyhow!("Expected an operator after '{}'", field_word))?
+        .to_lowercase();
+    *pos += 1;
+
+    let op = match op_word.as_str() {
+        ">" => Op::Gt,
+        ">=" => Op::Ge,
+        "<" => Op::Lt,
+        "<=" => Op::Le,
+        "=" | "==" => Op::Eq,
+        "!=" => Op::Ne,
+        "~" => Op::Contains,
+        "in" => Op::In,
+        "not"
+            if tokens
+                .get(*pos)
+                .is_some_and(|t| t.eq_ignore_ascii_case("in")) =>
+        {
+            *pos += 1;
+            Op::NotIn
+        }
+        _ => bail!("Unknown operator '{}' after '{}'", op_word, field_word),
+    };
+
+    match (op, field.is_numeric()) {
+        (Op::Contains, true) => bail!("'~' only works on text fields, not '{}'", field_word),
+        (Op::Gt | Op::Ge | Op::Lt | Op::Le, false) => {
+            bail!(
+                "'{}' only works on numeric fields, not '{}'",
+                op_word,
+                field_word
+            )
+        }
+        _ => {}
+    }
+
+    let raw_values = if matches!(op, Op::In | Op::NotIn) {
+        parse_list(tokens, pos)?
+    } else {
+        let raw = tokens
+            .get(*pos)
+            .ok_or_else(|| anyhow!("Expected a value after '{} {}'", field_word, op_word))?;
+        *pos += 1;
+        vec![raw.as_str()]
+    };
+
+    let values = raw_values
+        .into_iter()
+        .map(|raw| field.parse_value(raw))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Clause { field, op, values })
+}
+
+/// Parse `[a, b, c]` (brackets are optional for a single value)
+fn parse_list<'a>(tokens: &'a [String], pos: &mut usize) -> Result<Vec<&'a str>> {
+    if tokens.get(*pos).map(String::as_str) != Some("[") {
+        let raw = tokens
+            .get(*pos)
+            .ok_or_else(|| anyhow!("Expected a list after 'in'"))?;
+        *pos += 1;
+        return Ok(vec![raw.as_str()]);
+    }
+    *pos += 1;
+
+    let mut values = Vec::new();
+    loop {
+        match tokens.get(*pos).map(String::as_str) {
+            Some("]") if !values.is_empty() => {
+                *pos += 1;
+                return Ok(values);
+            }
+            Some("," | "]") | None => bail!("Malformed list, expected '[value, ...]'"),
+            Some(value) => {
+                values.push(value);
+                *pos += 1;
+                if tokens.get(*pos).map(String::as_str) == Some(",") {
+                    *pos += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Parse a size like "512", "64KB", "2GB" or "1.5G" into bytes (binary units)
+pub fn parse_size(raw: &str) -> Option<u64> {
+    let raw = raw.trim();
+    let split = raw
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(raw.len());
+    let (number, unit) = raw.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier = match unit
+        .to_uppercase()
+        .trim_end_matches("IB")
+        .trim_end_matches('B')
+    {
+        "" => 1u64,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return None,
+    };
+
+    Some((number * multiplier as f64) as u64)
+}
+
+/// Parse a duration like "30s", "5m", "2h", "7d" or "90" (seconds)
+pub fn parse_duration(raw: &str) -> Option<Duration> {
+    let raw = raw.trim();
+    let split = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let (number, unit) = raw.split_at(split);
+    let number: u64 = number.parse().ok()?;
+
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return None,
+    };
+
+    number.checked_mul(multiplier).map(Duration::from_secs)
+}
+
+/// Name of the built-in rule that flags runaway CPU
+pub const RUNAWAY_RULE: &str = "runaway";
+
+/// Built-in rules, always evaluated unless the config defines a rule of the same name
+pub fn default_rules() -> Vec<Rule> {
+    vec![Rule::parse(RUNAWAY_RULE, "cpu > 40%", Severity::Warning).expect("valid default rule")]
+}
+
+/// The configured rules followed by every built-in rule they don't override
+pub fn with_default_rules(mut rules: Vec<Rule>) -> Vec<Rule> {
+    let builtin: Vec<Rule> = default_rules()
+        .into_iter()
+        .filter(|default| !rules.iter().any(|rule| rule.name == default.name))
+        .collect();
+    rules.extend(builtin);
+    rules
+}
+
+/// Evaluates rules against scan results and tracks sustained conditions
+#[derive(Debug, Clone)]
+pub struct RuleEngine {
+    rules: Vec<Rule>,
+    /// When each (rule, pid, port) condition started holding
+    since: HashMap<(usize, u32, u16), Instant>,
+}
+
+impl Default for RuleEngine {
+    fn default() -> Self {
+        Self::new(default_rules())
+    }
+}
+
+impl RuleEngine {
+    /// Create an engine from parsed rules
+    pub fn new(rules: Vec<Rule>) -> Self {
+        Self {
+            rules,
+            since: HashMap::new(),
+        }
+    }
+
+    /// The rules this engine evaluates
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Evaluate rules for a live scan, honouring `for` durations
+    pub fn evaluate(&mut self, entries: &mut [PortEntry]) {
+        self.evaluate_at(entries, Instant::now());
+    }
+
+    fn evaluate_at(&mut self, entries: &mut [PortEntry], now: Instant) {
+        let mut seen = HashSet::new();
+
+        for entry in entries.iter_mut() {
+            entry.alerts.clear();
+            for (idx, rule) in self.rules.iter().enumerate() {
+                if !rule.matches(entry) {
+                    continue;
+                }
+
+                let key = (idx, entry.pid, entry.port);
+                seen.insert(key);
+                let since = *self.since.entry(key).or_insert(now);
+
+                if rule
+                    .sustain
+                    .is_none_or(|sustain| now.duration_since(since) >= sustain)
+                {
+                    entry.alerts.push(rule.alert());
+                }
+            }
+        }
+
+        // Conditions that stopped holding start over next time
+        self.since.retain(|key, _| seen.contains(key));
+    }
+
+    /// Evaluate rules for a one-shot snapshot (CLI)
+    ///
+    /// A single sample can't show that a condition held for a while, so rules
+    /// with a `for` duration are skipped rather than fired on a momentary spike.
+    pub fn evaluate_once(&self, entries: &mut [PortEntry]) {
+        for entry in entries.iter_mut() {
+            entry.alerts = self
+                .rules
+                .iter()
+                .filter(|rule| rule.sustain.is_none() && rule.matches(entry))
+                .map(Rule::alert)
+                .collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::ProcessState;
+
+    fn entry(port: u16, pid: u32) -> PortEntry {
+        PortEntry {
+            address: Some("127.0.0.1".into()),
+            state: ProcessState::Sleeping,
+            ..PortEntry::test(port, pid, "node")
+        }
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("64KB"), Some(64 * 1024));
+        assert_eq!(parse_size("2GB"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("1.5G"), Some(1536 * 1024 * 1024));
+        assert_eq!(parse_size("10MiB"), Some(10 * 1024 * 1024));
+        assert_eq!(parse_size("2XB"), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("7d"), Some(Duration::from_secs(7 * 86_400)));
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1y"), None);
+        // Too large to count in seconds
+        assert_eq!(parse_duration("99999999999999999w"), None);
+        assert_eq!(parse_duration("99999999999999999999"), None);
+    }
+
+    #[test]
+    fn test_rule_cpu_threshold() {
+        let rule = Rule::parse("hot", "cpu > 40%", Severity::Warning).unwrap();
+        let mut e = entry(3000, 1);
+
+        e.cpu_usage = 40.0;
+        assert!(!rule.matches(&e), "Exactly at threshold should not match");
+        e.cpu_usage = 40.1;
+        assert!(rule.matches(&e));
+    }
+
+    #[test]
+    fn test_rule_memory_and_name() {
+        let rule = Rule::parse("fat-node", "mem>=2GB and name=NODE", Severity::Critical).unwrap();
+        let mut e = entry(3000, 1);
+        e.memory_usage = 3 * 1024 * 1024 * 1024;
+        assert!(rule.matches(&e));
+
+        e.process_name = "python".into();
+        assert!(!rule.matches(&e));
+    }
+
+    #[test]
+    fn test_rule_public_port_allowlist() {
+        let rule = Rule::parse(
+            "exposed",
+            "addr = 0.0.0.0 and port not in [22, 80, 443]",
+            Severity::Critical,
+        )
+        .unwrap();
+
+        let mut e = entry(8080, 1);
+        assert!(!rule.matches(&e), "Loopback is not exposed");
+
+        e.address = Some("::".into());
+        assert!(rule.matches(&e), ":: is a wildcard like 0.0.0.0");
+
+        e.port = 443;
+        assert!(!rule.matches(&e), "Allowlisted port");
+    }
+
+    #[test]
+    fn test_rule_uptime_unknown_never_matches() {
+        let rule = Rule::parse("stale", "uptime > 7d", Severity::Info).unwrap();
+        let mut e = entry(3000, 1);
+        assert!(!rule.matches(&e));
+
+        e.start_time = Some(1);
+        assert!(rule.matches(&e));
+    }
+
+    #[test]
+    fn test_rule_parse_errors() {
+        assert!(Rule::parse("x", "", Severity::Info).is_err());
+        assert!(Rule::parse("x", "bogus > 1", Severity::Info).is_err());
+        assert!(Rule::parse("x", "cpu ~ 5", Severity::Info).is_err());
+        assert!(Rule::parse("x", "name > node", Severity::Info).is_err());
+        assert!(Rule::parse("x", "mem > lots", Severity::Info).is_err());
+        assert!(Rule::parse("x", "cpu > 5 or mem > 1GB", Severity::Info).is_err());
+        assert!(Rule::parse("x", "cpu > 5 for", Severity::Info).is_err());
+        assert!(Rule::parse("x", "port in [22,", Severity::Info).is_err());
+    }
+
+    #[test]
+    fn test_engine_sustained_rule() {
+        let rule = Rule::parse("hog", "cpu > 80% for 30s", Severity::Critical).unwrap();
+        assert_eq!(rule.sustain, Some(Duration::from_secs(30)));
+
+        let mut engine = RuleEngine::new(vec![rule]);
+        let mut entries = vec![entry(3000, 1)];
+        entries[0].cpu_usage = 95.0;
+
+        let start = Instant::now();
+        engine.evaluate_at(&mut entries, start);
+        assert!(entries[0].alerts.is_empty(), "Not sustained yet");
+
+        engine.evaluate_at(&mut entries, start + Duration::from_secs(31));
+        assert_eq!(entries[0].alerts.len(), 1);
+        assert_eq!(entries[0].alerts[0].severity, Severity::Critical);
+
+        // Dropping below the threshold resets the timer
+        entries[0].cpu_usage = 5.0;
+        engine.evaluate_at(&mut entries, start + Duration::from_secs(32));
+        entries[0].cpu_usage = 95.0;
+        engine.evaluate_at(&mut entries, start + Duration::from_secs(33));
+        assert!(entries[0].alerts.is_empty());
+    }
+
+    #[test]
+    fn test_engine_evaluate_once_skips_sustained_rules() {
+        let sustained = Rule::parse("hog", "cpu > 80% for 30s", Severity::Warning).unwrap();
+        let instant = Rule::parse("hot", "cpu > 80%", Severity::Warning).unwrap();
+        let engine = RuleEngine::new(vec![sustained, instant]);
+        let mut entries = vec![entry(3000, 1)];
+        entries[0].cpu_usage = 95.0;
+
+        engine.evaluate_once(&mut entries);
+        assert_eq!(entries[0].alerts.len(), 1);
+        assert_eq!(entries[0].alerts[0].rule, "hot");
+    }
+
+    #[test]
+    fn test_default_rules_flag_runaway_cpu() {
+        let mut engine = RuleEngine::default();
+        let mut entries = vec![entry(3000, 1), entry(3001, 2)];
+        entries[1].cpu_usage = 99.0;
+
+        engine.evaluate(&mut entries);
+        assert!(entries[0].alerts.is_empty());
+        assert_eq!(entries[1].alerts[0].rule, "runaway");
+    }
+
+    #[test]
+    fn test_default_rules_merge_into_configured_ones() {
+        let hog = Rule::parse("hog", "cpu > 80%", Severity::Critical).unwrap();
+        let rules = with_default_rules(vec![hog]);
+        let names: Vec<_> = rules.iter().map(|rule| rule.name.as_str()).collect();
+        assert_eq!(names, ["hog", RUNAWAY_RULE]);
+
+        // A rule with the same name replaces the built-in one
+        let stricter = Rule::parse(RUNAWAY_RULE, "cpu > 90%", Severity::Critical).unwrap();
+        let rules = with_default_rules(vec![stricter]);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].severity, Severity::Critical);
+    }
+
+    #[test]
+    fn test_severity_from_str_and_order() {
+        assert_eq!("CRITICAL".parse::<Severity>().unwrap(), Severity::Critical);
+        assert_eq!("warn".parse::<Severity>().unwrap(), Severity::Warning);
+        assert!("loud".parse::<Severity>().is_err());
+        assert!(Severity::Critical > Severity::Warning);
+        assert!(Severity::Warning > Severity::Info);
+    }
+}
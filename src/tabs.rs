@@ -0,0 +1,572 @@
+//! Host tabs
+//!
+//! Several hosts can be monitored at once, each in its own tab. A tab pairs a
+//! scanner with its own [`App`], so filter, sort, selection, history and
+//! events are kept per host; every tab keeps scanning while another is shown.
+//!
+//! Scans run on a worker thread per host tab and come back over a channel, so
+//! a slow or unreachable host never holds up input or drawing.
+//!
+//! The fleet view is a tab of its own that merges the rows of every host tab.
+//! It doesn't scan by itself: the latest rows of every host tab are copied into
+//! the fleet, tagged with the host.
+
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::app::{App, PortEntry};
+use crate::remote::RemoteScanner;
+use crate::scanner::{Scanner, Signal};
+
+/// A scanner shared between its host's tab and the fleet view
+pub type SharedScanner = Arc<Mutex<ScannerMode>>;
+
+/// Scanner mode - either local or remote
+pub enum ScannerMode {
+    Local(Box<Scanner>),
+    Remote(Box<RemoteScanner>),
+    /// The fleet view: the scanner of every host tab, by tab label
+    Fleet(Vec<(String, SharedScanner)>),
+}
+
+impl ScannerMode {
+    pub fn scan(&mut self) -> Result<Vec<PortEntry>> {
+        match self {
+            ScannerMode::Local(scanner) => Ok(scanner.scan()),
+            ScannerMode::Remote(scanner) => scanner.scan_reconnecting(),
+            // The fleet's rows come from the host tabs
+            ScannerMode::Fleet(_) => Ok(Vec::new()),
+        }
+    }
+
+    /// Kill a process; `host` picks the host in the fleet view
+    pub fn kill_process(&mut self, pid: u32, host: Option<&str>) -> Result<()> {
+        match self {
+            ScannerMode::Local(scanner) => scanner.kill_process(pid),
+            ScannerMode::Remote(scanner) => scanner.kill_process(pid),
+            ScannerMode::Fleet(members) => {
+                lock_idle(member(members, host)?)?.kill_process(pid, None)
+            }
+        }
+    }
+
+    /// Send a signal to a process; `host` picks the host in the fleet view
+    pub fn send_signal(&mut self, pid: u32, signal: Signal, host: Option<&str>) -> Result<()> {
+        match self {
+            ScannerMode::Local(scanner) => scanner.send_signal(pid, signal),
+            ScannerMode::Remote(scanner) => scanner.send_signal(pid, signal),
+            ScannerMode::Fleet(members) => {
+                lock_idle(member(members, host)?)?.send_signal(pid, signal, None)
+            }
+        }
+    }
+}
+
+/// The fleet member scanning `host`
+fn member<'a>(
+    members: &'a [(String, SharedScanner)],
+    host: Option<&str>,
+) -> Result<&'a SharedScanner> {
+    let host = host.ok_or_else(|| anyhow!("No host for this process"))?;
+    members
+        .iter()
+        .find(|(label, _)| label == host)
+        .map(|(_, scanner)| scanner)
+        .ok_or_else(|| anyhow!("No longer monitoring {}", host))
+}
+
+/// How long a key waits for a scan in progress to let go of the scanner
+const BUSY_WAIT: Duration = Duration::from_millis(500);
+
+/// Lock a scanner, even if a scan panicked while holding it
+pub fn lock(scanner: &SharedScanner) -> MutexGuard<'_, ScannerMode> {
+    scanner.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Lock a scanner for a kill or signal, giving up if a scan (or a reconnect) is
+/// still holding it after a moment
+pub fn lock_idle(scanner: &SharedScanner) -> Result<MutexGuard<'_, ScannerMode>> {
+    let start = Instant::now();
+    loop {
+        match scanner.try_lock() {
+            Ok(guard) => return Ok(guard),
+            Err(TryLockError::Poisoned(e)) => return Ok(e.into_inner()),
+            Err(TryLockError::WouldBlock) if start.elapsed() < BUSY_WAIT => {
+                thread::sleep(Duration::from_millis(10));
+            }
+            Err(TryLockError::WouldBlock) => bail!("Still scanning; try again in a moment"),
+        }
+    }
+}
+
+/// A thread that scans a host whenever its tab asks, and sends back the result
+struct Worker {
+    requests: Sender<()>,
+    results: Receiver<Result<Vec<PortEntry>>>,
+    /// The tab label a scan in progress was asked for
+    pending: Option<String>,
+}
+
+impl Worker {
+    fn spawn(scanner: SharedScanner) -> Self {
+        let (requests, asked) = mpsc::channel();
+        let (done, results) = mpsc::channel();
+        // Runs until the tab is closed and drops its end of the channel
+        thread::spawn(move || {
+            for () in asked {
+                let result = lock(&scanner).scan();
+                if done.send(result).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            results,
+            pending: None,
+        }
+    }
+}
+
+/// One monitored host
+pub struct Tab {
+    pub app: App,
+    pub scanner: SharedScanner,
+    /// When this tab last scanned
+    last_scan: Instant,
+    /// Scans in the background; the fleet view has none
+    worker: Option<Worker>,
+}
+
+impl Tab {
+    /// A tab that starts scanning its host in the background
+    pub fn new(app: App, scanner: ScannerMode) -> Self {
+        let is_fleet = matches!(scanner, ScannerMode::Fleet(_));
+        let scanner = Arc::new(Mutex::new(scanner));
+        let worker = (!is_fleet).then(|| Worker::spawn(Arc::clone(&scanner)));
+        let mut tab = Self {
+            app,
+            scanner,
+            last_scan: Instant::now(),
+            worker,
+        };
+        tab.request_scan();
+        tab
+    }
+
+    /// Whether the tab's scan interval has passed
+    fn is_due(&self) -> bool {
+        !self.app.is_fleet && self.last_scan.elapsed() >= self.app.scan_interval
+    }
+
+    /// Ask the worker for a scan, unless one is already running
+    fn request_scan(&mut self) {
+        let label = self.app.tab_label();
+        if let Some(worker) = &mut self.worker {
+            if worker.pending.is_none() && worker.requests.send(()).is_ok() {
+                worker.pending = Some(label);
+            }
+        }
+    }
+
+    /// Show the result of a finished scan, if there is one; returns whether rows changed
+    fn poll(&mut self) -> bool {
+        let Some(worker) = &mut self.worker else {
+            return false;
+        };
+        let Some(label) = worker.pending.clone() else {
+            return false;
+        };
+        let result = match worker.results.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Disconnected) => {
+                *worker = Worker::spawn(Arc::clone(&self.scanner));
+                Err(anyhow!("The scan crashed"))
+            }
+        };
+        worker.pending = None;
+        // Asked for before a disconnect switched the tab to another host
+        if label != self.app.tab_label() {
+            return false;
+        }
+        self.apply_scan(result);
+        true
+    }
+
+    /// Show the rows of a scan, or why it failed
+    fn apply_scan(&mut self, result: Result<Vec<PortEntry>>) {
+        match result {
+            Ok(entries) => {
+                if self.app.scan_error.take().is_some() && self.app.remote_host.is_some() {
+                    let host = self.app.tab_label();
+                    self.app.set_success(format!("Reconnected to {}", host));
+                }
+                self.app.update_entries(entries);
+            }
+            Err(e) => self.app.scan_error = Some(e.to_string()),
+        }
+        self.last_scan = Instant::now();
+    }
+}
+
+/// The open tabs and which one is shown
+pub struct Tabs {
+    tabs: Vec<Tab>,
+    active: usize,
+}
+
+impl Tabs {
+    /// Start with a single tab
+    pub fn new(first: Tab) -> Self {
+        let mut tabs = Self {
+            tabs: vec![first],
+            active: 0,
+        };
+        tabs.sync_labels();
+        tabs
+    }
+
+    /// The tab being shown
+    pub fn active(&self) -> &Tab {
+        &self.tabs[self.active]
+    }
+
+    pub fn active_mut(&mut self) -> &mut Tab {
+        &mut self.tabs[self.active]
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Tab> {
+        self.tabs.iter_mut()
+    }
+
+    /// Index of the tab monitoring `host`, as shown in the tab bar
+    pub fn find(&self, host: &str) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.app.tab_label() == host)
+    }
+
+    /// Add a tab after the others and show it
+    pub fn push(&mut self, tab: Tab) {
+        self.tabs.push(tab);
+        let index = self.tabs.len() - 1;
+        self.select(index);
+        self.refresh_fleet();
+    }
+
+    /// Show the tab at `index`; false if there's no such tab
+    pub fn select(&mut self, index: usize) -> bool {
+        if index >= self.tabs.len() {
+            return false;
+        }
+        // Pins, views and recent hosts are saved to disk on change; carry the latest over
+        let (pins, views, inventory) = {
+            let app = &self.tabs[self.active].app;
+            (app.pins.clone(), app.views.clone(), app.inventory.clone())
+        };
+        self.active = index;
+        let app = &mut self.tabs[index].app;
+        app.pins = pins;
+        app.views = views;
+        app.inventory = inventory;
+        self.sync_labels();
+        true
+    }
+
+    /// Show the next tab, wrapping around
+    pub fn next(&mut self) {
+        self.select((self.active + 1) % self.tabs.len());
+    }
+
+    /// Show the previous tab, wrapping around
+    pub fn previous(&mut self) {
+        self.select((self.active + self.tabs.len() - 1) % self.tabs.len());
+    }
+
+    /// Close the shown tab and show its neighbour; the last host's tab can't be closed
+    pub fn close(&mut self) -> Option<Tab> {
+        let hosts = self.tabs.iter().filter(|tab| !tab.app.is_fleet).count();
+        if self.tabs.len() == 1 || (hosts == 1 && !self.active().app.is_fleet) {
+            return None;
+        }
+        let closed = self.tabs.remove(self.active);
+        let index = self.active.min(self.tabs.len() - 1);
+        // The closed tab's pins, views and recent hosts are already on disk; start from them
+        self.tabs[index].app.pins = closed.app.pins.clone();
+        self.tabs[index].app.views = closed.app.views.clone();
+        self.tabs[index].app.inventory = closed.app.inventory.clone();
+        self.active = index;
+        self.sync_labels();
+        self.refresh_fleet();
+        Some(closed)
+    }
+
+    /// Open the fleet view as the first tab and show it, or close it if it's open
+    pub fn toggle_fleet(&mut self, mut app: App) -> bool {
+        if let Some(index) = self.tabs.iter().position(|tab| tab.app.is_fleet) {
+            self.select(index);
+            self.close();
+            return false;
+        }
+        app.is_fleet = true;
+        self.tabs
+            .insert(0, Tab::new(app, ScannerMode::Fleet(Vec::new())));
+        self.active += 1;
+        self.select(0);
+        self.refresh_fleet();
+        true
+    }
+
+    /// Show the scans that finished, ask every host whose interval has passed for
+    /// another, and refresh the fleet view when rows came in; never waits on a host
+    pub fn scan_due(&mut self) {
+        let mut scanned = false;
+        for tab in &mut self.tabs {
+            scanned |= tab.poll();
+            if tab.is_due() {
+                tab.request_scan();
+            }
+        }
+        if scanned {
+            self.refresh_fleet();
+        }
+    }
+
+    /// Copy every host's latest rows and errors into the fleet view, if it's open
+    fn refresh_fleet(&mut self) {
+        let Some(fleet) = self.tabs.iter().position(|tab| tab.app.is_fleet) else {
+            return;
+        };
+        let mut members = Vec::new();
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+        for tab in self.tabs.iter().filter(|tab| !tab.app.is_fleet) {
+            let host = tab.app.tab_label();
+            members.push((host.clone(), Arc::clone(&tab.scanner)));
+            if let Some(error) = &tab.app.scan_error {
+                errors.push((host, error.clone()));
+                continue;
+            }
+            let rows = tab.app.last_scan.iter().flatten().map(|entry| PortEntry {
+                host: Some(host.clone()),
+                ..entry.clone()
+            });
+            entries.extend(rows);
+        }
+        let tab = &mut self.tabs[fleet];
+        *lock(&tab.scanner) = ScannerMode::Fleet(members);
+        tab.app.update_fleet(entries, errors);
+    }
+
+    /// Tell every tab's app about the tab bar (empty while only one host is open)
+    pub fn sync_labels(&mut self) {
+        let labels: Vec<String> = if self.tabs.len() > 1 {
+            self.tabs.iter().map(|tab| tab.app.tab_label()).collect()
+        } else {
+            Vec::new()
+        };
+        for (index, tab) in self.tabs.iter_mut().enumerate() {
+            tab.app.tab_labels = labels.clone();
+            tab.app.active_tab = index;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{ProcessState, StatusMessage};
+
+    fn tab(host: Option<&str>) -> Tab {
+        let mut app = App::new();
+        app.set_remote_host(host.map(str::to_string));
+        Tab {
+            app,
+            scanner: Arc::new(Mutex::new(ScannerMode::Local(Box::default()))),
+            last_scan: Instant::now(),
+            worker: None,
+        }
+    }
+
+    /// Poll a tab until its scan in progress is done
+    fn wait_for_scan(tab: &mut Tab) {
+        let start = Instant::now();
+        while tab.worker.as_ref().unwrap().pending.is_some() {
+            assert!(start.elapsed() < Duration::from_secs(30), "scan timed out");
+            tab.poll();
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn entry(port: u16) -> PortEntry {
+        PortEntry {
+            state: ProcessState::Sleeping,
+            ..PortEntry::test(port, 100, "node")
+        }
+    }
+
+    fn tabs(hosts: &[Option<&str>]) -> Tabs {
+        let mut tabs = Tabs::new(tab(hosts[0]));
+        for host in &hosts[1..] {
+            tabs.push(tab(*host));
+        }
+        tabs
+    }
+
+    #[test]
+    fn test_single_tab_has_no_tab_bar() {
+        let tabs = tabs(&[None]);
+        assert!(tabs.active().app.tab_labels.is_empty());
+    }
+
+    #[test]
+    fn test_push_selects_new_tab() {
+        let tabs = tabs(&[None, Some("web1"), Some("web2")]);
+        assert_eq!(tabs.active().app.tab_label(), "web2");
+        assert_eq!(tabs.active().app.active_tab, 2);
+        assert_eq!(
+            tabs.active().app.tab_labels,
+            vec!["localhost", "web1", "web2"]
+        );
+        assert_eq!(tabs.find("web1"), Some(1));
+        assert_eq!(tabs.find("db1"), None);
+    }
+
+    #[test]
+    fn test_next_and_previous_wrap() {
+        let mut tabs = tabs(&[None, Some("web1"), Some("web2")]);
+        tabs.next();
+        assert_eq!(tabs.active().app.tab_label(), "localhost");
+        tabs.previous();
+        assert_eq!(tabs.active().app.tab_label(), "web2");
+        assert!(!tabs.select(3));
+        assert!(tabs.select(1));
+        assert_eq!(tabs.active().app.tab_label(), "web1");
+    }
+
+    #[test]
+    fn test_tabs_keep_their_own_filter() {
+        let mut tabs = tabs(&[None, Some("web1")]);
+        tabs.active_mut().app.filter = "proto:udp".to_string();
+        tabs.select(0);
+        assert_eq!(tabs.active().app.filter, "");
+        tabs.select(1);
+        assert_eq!(tabs.active().app.filter, "proto:udp");
+    }
+
+    #[test]
+    fn test_dropped_host_keeps_rows_until_reconnected() {
+        let mut tab = tab(Some("web1"));
+        tab.apply_scan(Ok(vec![entry(3000)]));
+        tab.apply_scan(Err(anyhow::anyhow!("Disconnected: Connection reset")));
+        assert_eq!(tab.app.entries.len(), 1);
+        assert!(tab.app.scan_error.is_some());
+
+        tab.apply_scan(Ok(vec![entry(3000), entry(8080)]));
+        assert!(tab.app.scan_error.is_none());
+        assert_eq!(tab.app.entries.len(), 2);
+        assert!(matches!(
+            &tab.app.status_message,
+            StatusMessage::Success(text) if text == "Reconnected to web1"
+        ));
+    }
+
+    #[test]
+    fn test_scans_in_the_background() {
+        let mut tab = Tab::new(App::new(), ScannerMode::Local(Box::default()));
+        wait_for_scan(&mut tab);
+        assert!(tab.app.last_scan.is_some());
+
+        // A busy scanner is reported instead of waited on
+        let held = lock(&tab.scanner);
+        let err = lock_idle(&tab.scanner).map(|_| ()).unwrap_err();
+        assert_eq!(err.to_string(), "Still scanning; try again in a moment");
+        drop(held);
+        assert!(lock_idle(&tab.scanner).is_ok());
+    }
+
+    #[test]
+    fn test_scan_from_before_disconnect_is_dropped() {
+        let mut app = App::new();
+        app.set_remote_host(Some("web1".to_string()));
+        let mut tab = Tab::new(app, ScannerMode::Local(Box::default()));
+        tab.app.disconnect();
+        wait_for_scan(&mut tab);
+        assert!(tab.app.last_scan.is_none());
+    }
+
+    #[test]
+    fn test_fleet_merges_hosts() {
+        let mut tabs = tabs(&[None, Some("web1"), Some("web2")]);
+        tabs.tabs[0].app.last_scan = Some(vec![entry(3000)]);
+        tabs.tabs[1].app.last_scan = Some(vec![entry(3000), entry(8080)]);
+        tabs.tabs[2].app.scan_error = Some("Connection reset".to_string());
+
+        assert!(tabs.toggle_fleet(App::new()));
+        let fleet = &tabs.active().app;
+        assert_eq!(fleet.tab_labels[0], "all hosts");
+        assert_eq!(fleet.active_tab, 0);
+        let rows: Vec<_> = fleet
+            .last_scan
+            .iter()
+            .flatten()
+            .map(|entry| (entry.host.as_deref(), entry.port))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (Some("localhost"), 3000),
+                (Some("web1"), 3000),
+                (Some("web1"), 8080)
+            ]
+        );
+        assert_eq!(
+            fleet.host_errors,
+            vec![("web2".to_string(), "Connection reset".to_string())]
+        );
+
+        // Toggling again closes the fleet view
+        assert!(!tabs.toggle_fleet(App::new()));
+        assert_eq!(tabs.find("all hosts"), None);
+        assert_eq!(tabs.active().app.tab_labels.len(), 3);
+    }
+
+    #[test]
+    fn test_fleet_routes_by_host() {
+        let mut tabs = tabs(&[None, Some("web1")]);
+        tabs.toggle_fleet(App::new());
+        let mut scanner = lock(&tabs.active().scanner);
+        let err = scanner.kill_process(1, Some("db1")).unwrap_err();
+        assert_eq!(err.to_string(), "No longer monitoring db1");
+        let err = scanner.kill_process(1, None).unwrap_err();
+        assert_eq!(err.to_string(), "No host for this process");
+    }
+
+    #[test]
+    fn test_last_host_stays_open_beside_fleet() {
+        let mut tabs = tabs(&[None]);
+        tabs.toggle_fleet(App::new());
+        tabs.select(1);
+        assert!(tabs.close().is_none());
+        tabs.select(0);
+        assert!(tabs.close().is_some());
+        assert!(tabs.active().app.tab_labels.is_empty());
+    }
+
+    #[test]
+    fn test_close() {
+        let mut tabs = tabs(&[None, Some("web1"), Some("web2")]);
+        let closed = tabs.close().unwrap();
+        assert_eq!(closed.app.tab_label(), "web2");
+        assert_eq!(tabs.active().app.tab_label(), "web1");
+
+        tabs.select(0);
+        tabs.close();
+        assert_eq!(tabs.active().app.tab_label(), "web1");
+        assert!(tabs.active().app.tab_labels.is_empty());
+        assert!(tabs.close().is_none());
+    }
+}
@@ -0,0 +1,463 @@
+//! Color themes
+//!
+//! The UI draws with a [`Theme`] instead of fixed colors. Built-in themes are
+//! `dark` (the default), `light`, `high-contrast` and `16-color`; more can be
+//! defined in the config file on top of a built-in one:
+//!
+//! ```toml
+//! [defaults]
+//! theme = "mine"
+//!
+//! [themes.mine]
+//! base = "light"
+//! accent = "#1e66f5"
+//! error = "red"
+//! ```
+//!
+//! Truecolor themes are reduced to the 256- or 16-color palette when the
+//! terminal doesn't advertise truecolor support, and `NO_COLOR` turns colors
+//! off unless a theme is picked with `--theme`.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+/// Names of the built-in themes
+pub const BUILTIN_THEMES: &[&str] = &["dark", "light", "high-contrast", "16-color"];
+
+/// Colors used by the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Background
+    pub bg: Color,
+    /// Top bar, table header and command bar background
+    pub header_bg: Color,
+    /// Borders and separators
+    pub border: Color,
+    /// Main text
+    pub text: Color,
+    /// Dimmed text and labels
+    pub text_dim: Color,
+    /// Primary accent (keys, TCP, info)
+    pub accent: Color,
+    /// Secondary accent (UDP, success, opened ports)
+    pub accent2: Color,
+    /// Warnings, stale uptime, sort indicator
+    pub warning: Color,
+    /// Errors, defunct processes, closed ports
+    pub error: Color,
+    /// Selected row background
+    pub selected_bg: Color,
+    /// Alternating row background
+    pub row_alt: Color,
+    /// Orphaned processes
+    pub orphan: Color,
+    /// Processes flagged by the built-in runaway CPU rule
+    pub runaway: Color,
+    /// Warning-level health badges
+    pub alert: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// K9s-inspired dark palette
+    pub fn dark() -> Self {
+        Self {
+            bg: Color::Rgb(30, 30, 46),
+            header_bg: Color::Rgb(49, 50, 68),
+            border: Color::Rgb(88, 91, 112),
+            text: Color::Rgb(205, 214, 244),
+            text_dim: Color::Rgb(108, 112, 134),
+            accent: Color::Rgb(137, 180, 250),
+            accent2: Color::Rgb(166, 227, 161),
+            warning: Color::Rgb(249, 226, 175),
+            error: Color::Rgb(243, 139, 168),
+            selected_bg: Color::Rgb(69, 71, 90),
+            row_alt: Color::Rgb(39, 39, 55),
+            orphan: Color::Rgb(203, 166, 247),
+            runaway: Color::Rgb(235, 160, 172),
+            alert: Color::Rgb(250, 179, 135),
+        }
+    }
+
+    /// Palette for terminals with a light background
+    pub fn light() -> Self {
+        Self {
+            bg: Color::Rgb(239, 241, 245),
+            header_bg: Color::Rgb(220, 224, 232),
+            border: Color::Rgb(172, 176, 190),
+            text: Color::Rgb(76, 79, 105),
+            text_dim: Color::Rgb(124, 127, 147),
+            accent: Color::Rgb(30, 102, 245),
+            accent2: Color::Rgb(64, 160, 43),
+            warning: Color::Rgb(223, 142, 29),
+            error: Color::Rgb(210, 15, 57),
+            selected_bg: Color::Rgb(204, 208, 218),
+            row_alt: Color::Rgb(230, 233, 239),
+            orphan: Color::Rgb(136, 57, 239),
+            runaway: Color::Rgb(230, 69, 83),
+            alert: Color::Rgb(254, 100, 11),
+        }
+    }
+
+    /// Pure black background with saturated colors
+    pub fn high_contrast() -> Self {
+        Self {
+            bg: Color::Rgb(0, 0, 0),
+            header_bg: Color::Rgb(38, 38, 38),
+            border: Color::Rgb(255, 255, 255),
+            text: Color::Rgb(255, 255, 255),
+            text_dim: Color::Rgb(200, 200, 200),
+            accent: Color::Rgb(0, 215, 255),
+            accent2: Color::Rgb(0, 255, 95),
+            warning: Color::Rgb(255, 255, 0),
+            error: Color::Rgb(255, 85, 85),
+            selected_bg: Color::Rgb(0, 95, 175),
+            row_alt: Color::Rgb(18, 18, 18),
+            orphan: Color::Rgb(255, 135, 255),
+            runaway: Color::Rgb(255, 95, 135),
+            alert: Color::Rgb(255, 175, 0),
+        }
+    }
+
+    /// The terminal's own 16 ANSI colors (follows its light or dark scheme)
+    pub fn ansi16() -> Self {
+        Self {
+            bg: Color::Reset,
+            header_bg: Color::DarkGray,
+            border: Color::DarkGray,
+            text: Color::Reset,
+            text_dim: Color::DarkGray,
+            accent: Color::Blue,
+            accent2: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            selected_bg: Color::DarkGray,
+            row_alt: Color::Reset,
+            orphan: Color::Magenta,
+            runaway: Color::Cyan,
+            alert: Color::LightRed,
+        }
+    }
+
+    /// Terminal defaults everywhere (for `NO_COLOR`)
+    pub fn no_color() -> Self {
+        Self {
+            bg: Color::Reset,
+            header_bg: Color::Reset,
+            border: Color::Reset,
+            text: Color::Reset,
+            text_dim: Color::Reset,
+            accent: Color::Reset,
+            accent2: Color::Reset,
+            warning: Color::Reset,
+            error: Color::Reset,
+            selected_bg: Color::Reset,
+            row_alt: Color::Reset,
+            orphan: Color::Reset,
+            runaway: Color::Reset,
+            alert: Color::Reset,
+        }
+    }
+
+    /// A built-in theme by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "16-color" => Some(Self::ansi16()),
+            _ => None,
+        }
+    }
+
+    /// The color slot with a config name (e.g. "text_dim")
+    fn slot_mut(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "bg" => &mut self.bg,
+            "header_bg" => &mut self.header_bg,
+            "border" => &mut self.border,
+            "text" => &mut self.text,
+            "text_dim" => &mut self.text_dim,
+            "accent" => &mut self.accent,
+            "accent2" => &mut self.accent2,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "selected_bg" => &mut self.selected_bg,
+            "row_alt" => &mut self.row_alt,
+            "orphan" => &mut self.orphan,
+            "runaway" => &mut self.runaway,
+            "alert" => &mut self.alert,
+            _ => return None,
+        })
+    }
+
+    fn slots_mut(&mut self) -> [&mut Color; 14] {
+        [
+            &mut self.bg,
+            &mut self.header_bg,
+            &mut self.border,
+            &mut self.text,
+            &mut self.text_dim,
+            &mut self.accent,
+            &mut self.accent2,
+            &mut self.warning,
+            &mut self.error,
+            &mut self.selected_bg,
+            &mut self.row_alt,
+            &mut self.orphan,
+            &mut self.runaway,
+            &mut self.alert,
+        ]
+    }
+
+    /// Reduce RGB colors to what the terminal can display
+    pub fn for_terminal(mut self, support: ColorSupport) -> Self {
+        for slot in self.slots_mut() {
+            if let Color::Rgb(r, g, b) = *slot {
+                *slot = match support {
+                    ColorSupport::TrueColor => continue,
+                    ColorSupport::Ansi256 => Color::Indexed(nearest_256(r, g, b)),
+                    ColorSupport::Ansi16 => nearest_16(r, g, b),
+                };
+            }
+        }
+        self
+    }
+}
+
+/// A `[themes.NAME]` table: a built-in base plus color overrides
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Built-in theme to start from (default: dark)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// Colors by slot name: a name ("red"), "#rrggbb" or a 256-color index
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+/// Look up a built-in or user-defined theme
+pub fn resolve(name: &str, themes: &BTreeMap<String, ThemeConfig>) -> Result<Theme> {
+    let Some(config) = themes.get(name) else {
+        return Theme::builtin(name).ok_or_else(|| {
+            let mut names: Vec<&str> = BUILTIN_THEMES.to_vec();
+            names.extend(themes.keys().map(String::as_str));
+            anyhow!("Unknown theme '{}' (available: {})", name, names.join(", "))
+        });
+    };
+
+    let base = config.base.as_deref().unwrap_or("dark");
+    let mut theme = Theme::builtin(base).ok_or_else(|| {
+        anyhow!(
+            "Theme '{}' has unknown base '{}' (built-in themes: {})",
+            name,
+            base,
+            BUILTIN_THEMES.join(", ")
+        )
+    })?;
+    for (slot, value) in &config.colors {
+        let Some(color) = theme.slot_mut(slot) else {
+            bail!("Unknown color '{}' in theme '{}'", slot, name);
+        };
+        *color = Color::from_str(value).map_err(|_| {
+            anyhow!(
+                "Invalid color \"{}\" for {} in theme '{}'",
+                value,
+                slot,
+                name
+            )
+        })?;
+    }
+    Ok(theme)
+}
+
+/// How many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorSupport {
+    /// Detect support from `COLORTERM` and `TERM`
+    pub fn detect() -> Self {
+        Self::from_env(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            ColorSupport::TrueColor
+        } else if term.is_some_and(|term| term.contains("256color")) {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+}
+
+/// Whether the user asked for no colors (`NO_COLOR` set and non-empty)
+pub fn no_color_requested() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// Closest xterm 256-color index (from the 6x6x6 cube or the gray ramp)
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    const STEPS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let cube_index = |v: u8| {
+        (0..6)
+            .min_by_key(|&i| (i32::from(STEPS[i]) - i32::from(v)).abs())
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = (STEPS[ri], STEPS[gi], STEPS[bi]);
+
+    let average = (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    let gray_index = (average.saturating_sub(8) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray_index;
+    let gray = (gray_level, gray_level, gray_level);
+
+    if distance((r, g, b), gray) < distance((r, g, b), cube) {
+        232 + gray_index
+    } else {
+        16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8
+    }
+}
+
+/// Closest of the 16 ANSI colors (using xterm's default values)
+fn nearest_16(r: u8, g: u8, b: u8) -> Color {
+    const PALETTE: [(Color, (u8, u8, u8)); 16] = [
+        (Color::Black, (0, 0, 0)),
+        (Color::Red, (205, 0, 0)),
+        (Color::Green, (0, 205, 0)),
+        (Color::Yellow, (205, 205, 0)),
+        (Color::Blue, (0, 0, 238)),
+        (Color::Magenta, (205, 0, 205)),
+        (Color::Cyan, (0, 205, 205)),
+        (Color::Gray, (229, 229, 229)),
+        (Color::DarkGray, (127, 127, 127)),
+        (Color::LightRed, (255, 0, 0)),
+        (Color::LightGreen, (0, 255, 0)),
+        (Color::LightYellow, (255, 255, 0)),
+        (Color::LightBlue, (92, 92, 255)),
+        (Color::LightMagenta, (255, 0, 255)),
+        (Color::LightCyan, (0, 255, 255)),
+        (Color::White, (255, 255, 255)),
+    ];
+    PALETTE
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
+
+/// Squared distance between two RGB colors
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).unsigned_abs().pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn themes(toml: &str) -> BTreeMap<String, ThemeConfig> {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_themes() {
+        for name in BUILTIN_THEMES {
+            assert!(resolve(name, &BTreeMap::new()).is_ok(), "{}", name);
+        }
+        let err = resolve("solarized", &BTreeMap::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown theme 'solarized' (available: dark, light, high-contrast, 16-color)"
+        );
+    }
+
+    #[test]
+    fn test_user_theme() {
+        let themes = themes(
+            r##"
+            [mine]
+            base = "light"
+            accent = "#ff0000"
+            error = "magenta"
+            bg = "17"
+            "##,
+        );
+        let theme = resolve("mine", &themes).unwrap();
+        assert_eq!(theme.accent, Color::Rgb(255, 0, 0));
+        assert_eq!(theme.error, Color::Magenta);
+        assert_eq!(theme.bg, Color::Indexed(17));
+        assert_eq!(theme.text, Theme::light().text);
+
+        // A user theme can shadow a built-in one
+        let themes = self::themes("[dark]\naccent = \"blue\"");
+        assert_eq!(resolve("dark", &themes).unwrap().accent, Color::Blue);
+    }
+
+    #[test]
+    fn test_invalid_user_theme() {
+        let err = resolve("x", &themes("[x]\nbase = \"neon\"")).unwrap_err();
+        assert!(err.to_string().contains("unknown base 'neon'"));
+
+        let err = resolve("x", &themes("[x]\nforeground = \"red\"")).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown color 'foreground' in theme 'x'");
+
+        let err = resolve("x", &themes("[x]\naccent = \"#12345\"")).unwrap_err();
+        assert!(err.to_string().contains("Invalid color \"#12345\""));
+    }
+
+    #[test]
+    fn test_color_support_detection() {
+        assert_eq!(
+            ColorSupport::from_env(Some("truecolor"), Some("xterm")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, Some("xterm-256color")),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, Some("linux")),
+            ColorSupport::Ansi16
+        );
+        assert_eq!(ColorSupport::from_env(None, None), ColorSupport::Ansi16);
+    }
+
+    #[test]
+    fn test_downsampling() {
+        assert_eq!(nearest_256(255, 0, 0), 196);
+        assert_eq!(nearest_256(0, 0, 0), 16);
+        assert_eq!(nearest_256(128, 128, 128), 244);
+        assert_eq!(nearest_16(250, 10, 10), Color::LightRed);
+        assert_eq!(nearest_16(30, 30, 46), Color::Black);
+
+        let dark = Theme::dark();
+        assert_eq!(dark.for_terminal(ColorSupport::TrueColor), dark);
+        let reduced = dark.for_terminal(ColorSupport::Ansi256);
+        assert!(matches!(reduced.accent, Color::Indexed(_)));
+        let reduced = dark.for_terminal(ColorSupport::Ansi16);
+        assert_eq!(reduced.bg, Color::Black);
+
+        // Named colors are left alone
+        assert_eq!(
+            Theme::ansi16().for_terminal(ColorSupport::Ansi16),
+            Theme::ansi16()
+        );
+    }
+}
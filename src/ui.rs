@@ -3,35 +3,87 @@
 //! This module implements the "View" part of the Model-View-Update pattern.
 //! It handles all ratatui rendering logic with a k9s-like aesthetic.
 
+use std::ops::Range;
+
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap},
+    widgets::{
+        Block, Borders, Cell, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, Row,
+        Sparkline, Table, TableState, Wrap,
+    },
     Frame,
 };
 
-use crate::app::{App, PortEntry, SortColumn, SortOrder, StatusMessage};
-
-// K9s-inspired color palette
-const COLOR_BG: Color = Color::Rgb(30, 30, 46); // Dark background
-const COLOR_HEADER_BG: Color = Color::Rgb(49, 50, 68); // Header background
-const COLOR_BORDER: Color = Color::Rgb(88, 91, 112); // Border color
-const COLOR_TEXT: Color = Color::Rgb(205, 214, 244); // Main text
-const COLOR_TEXT_DIM: Color = Color::Rgb(108, 112, 134); // Dimmed text
-const COLOR_ACCENT: Color = Color::Rgb(137, 180, 250); // Blue accent (like k9s)
-const COLOR_ACCENT2: Color = Color::Rgb(166, 227, 161); // Green accent
-const COLOR_WARNING: Color = Color::Rgb(249, 226, 175); // Yellow/warning
-const COLOR_ERROR: Color = Color::Rgb(243, 139, 168); // Red/error
-const COLOR_SELECTED_BG: Color = Color::Rgb(69, 71, 90); // Selected row bg
-const COLOR_ROW_ALT: Color = Color::Rgb(39, 39, 55); // Alternating row
+use crate::app::{
+    format_uptime, App, DetailsLayout, PendingBulk, PortEntry, SortColumn, SortOrder,
+    StatusMessage, LOCAL_LABEL,
+};
+use crate::events::{format_clock, PortEventKind};
+use crate::group::{Group, GroupKey, TableRow};
+use crate::history::{PidHistory, Trend};
+use crate::keymap::{Action, KeyContext, Section};
+use crate::palette;
+use crate::rules::Severity;
+use crate::scanner::format_memory;
+use crate::theme::Theme;
 
-/// Main UI rendering function
-pub fn render(frame: &mut Frame, app: &App) {
-    // Fill background
-    let bg_block = Block::default().style(Style::default().bg(COLOR_BG));
-    frame.render_widget(bg_block, frame.area());
+/// Sortable columns, in table order (HEALTH follows them)
+const HEADERS: [(&str, SortColumn); 7] = [
+    ("PORT", SortColumn::Port),
+    ("PROTO", SortColumn::Protocol),
+    ("PID", SortColumn::Pid),
+    ("NAME", SortColumn::ProcessName),
+    ("CPU%", SortColumn::CpuUsage),
+    ("MEM", SortColumn::MemoryUsage),
+    ("UPTIME", SortColumn::Uptime),
+];
+
+/// Column widths (accounting for [key] indicators in headers)
+const COLUMN_WIDTHS: [Constraint; 8] = [
+    Constraint::Length(12), // PORT[P/1]▲
+    Constraint::Length(12), // PROTO[O/2]
+    Constraint::Length(11), // PID[I/3]
+    Constraint::Min(15),    // NAME[N/4] + process name
+    Constraint::Length(12), // CPU%[C/5]
+    Constraint::Length(14), // MEM[M/6] + trend arrow
+    Constraint::Length(13), // UPTIME[U/7]
+    Constraint::Length(16), // HEALTH badges
+];
+
+/// Width of the HOST column shown first in the fleet view
+const HOST_WIDTH: u16 = 16;
+
+/// Width of the detail pane when it sits beside the table
+const SIDE_DETAILS_WIDTH: u16 = 56;
+
+/// Narrowest terminal that fits the table with the detail pane beside it
+const SIDE_DETAILS_MIN_WIDTH: u16 = 120 + SIDE_DETAILS_WIDTH;
+
+/// Completions listed after the command palette prompt
+const MAX_COMPLETIONS_SHOWN: usize = 8;
+
+/// Marker in front of the selected row (one column per character)
+const HIGHLIGHT_SYMBOL: &str = "▶ ";
+
+/// Uptime after which a process is highlighted as potentially stale (7 days)
+const STALE_UPTIME_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Areas of the main screen
+struct ScreenLayout {
+    top_bar: Rect,
+    context_bar: Rect,
+    table: Rect,
+    events: Option<Rect>,
+    details: Option<Rect>,
+    /// Whether the detail pane sits beside the table rather than below it
+    details_beside: bool,
+    command_bar: Rect,
+}
 
+/// Split the screen into bars, table and the optional panes around it
+fn screen_layout(app: &App, area: Rect) -> ScreenLayout {
     // Create the main layout
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -41,124 +93,355 @@ pub fn render(frame: &mut Frame, app: &App) {
             Constraint::Min(10),   // Table
             Constraint::Length(1), // Command bar
         ])
-        .split(frame.area());
+        .split(area);
+
+    // The detail panel goes beside the table and event log when there's room
+    let details_beside = app.show_details
+        && app.details_layout == DetailsLayout::Right
+        && area.width >= SIDE_DETAILS_MIN_WIDTH;
+    let (main, side) = if details_beside {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(SIDE_DETAILS_WIDTH)])
+            .split(chunks[2]);
+        (columns[0], Some(columns[1]))
+    } else {
+        (chunks[2], None)
+    };
+
+    // Optional panes stacked below the table
+    let mut constraints = vec![Constraint::Min(10)]; // Table
+    if app.show_events {
+        constraints.push(Constraint::Length(8)); // Event log
+    }
+    if app.show_details && !details_beside {
+        constraints.push(Constraint::Length(12)); // Detail panel
+    }
+    let table_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(main);
+
+    let mut panes = table_chunks.iter().skip(1).copied();
+    let events = app.show_events.then(|| panes.next()).flatten();
+    ScreenLayout {
+        top_bar: chunks[0],
+        context_bar: chunks[1],
+        table: table_chunks[0],
+        events,
+        details: side.or_else(|| app.show_details.then(|| panes.next()).flatten()),
+        details_beside,
+        command_bar: chunks[3],
+    }
+}
+
+/// Main UI rendering function
+pub fn render(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    // Fill background
+    let bg_block = Block::default().style(Style::default().bg(theme.bg));
+    frame.render_widget(bg_block, frame.area());
+
+    let layout = screen_layout(app, frame.area());
+    render_top_bar(frame, app, layout.top_bar);
+    render_context_bar(frame, app, layout.context_bar);
+    render_table(frame, app, layout.table);
+    if let Some(area) = layout.events {
+        render_event_log(frame, app, area);
+    }
+    if let Some(area) = layout.details {
+        render_detail_panel(frame, app, area, layout.details_beside);
+    }
+    render_command_bar(frame, app, layout.command_bar);
+
+    if app.show_view_picker {
+        render_view_picker(frame, app);
+    }
 
-    render_top_bar(frame, chunks[0]);
-    render_context_bar(frame, app, chunks[1]);
-    render_table(frame, app, chunks[2]);
-    render_command_bar(frame, app, chunks[3]);
+    if app.connect_mode && !app.connect_key_mode {
+        render_host_picker(frame, app);
+    }
+
+    if let Some(bulk) = &app.pending_bulk {
+        render_confirm_popup(frame, app, bulk);
+    }
 
     // Render help popup if active
     if app.show_help {
-        render_help_popup(frame);
+        render_help_popup(frame, app);
+    }
+}
+
+/// Something on screen that reacts to a mouse click
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickTarget {
+    /// A table row, by index into `app.entries` (`app.rows` while grouped)
+    Row(usize),
+    /// A sortable column header
+    Header(SortColumn),
+    /// A key hint in the top bar
+    Hint(Action),
+}
+
+/// What is drawn at `column`, `row` when the UI fills `area`
+pub fn click_target(app: &App, area: Rect, column: u16, row: u16) -> Option<ClickTarget> {
+    let layout = screen_layout(app, area);
+    let position = Position::new(column, row);
+
+    if layout.top_bar.contains(position) {
+        let (_, hints) = top_bar_line(app, layout.top_bar.width);
+        let x = column - layout.top_bar.x;
+        return hints
+            .into_iter()
+            .find(|(_, range)| range.contains(&x))
+            .map(|(action, _)| ClickTarget::Hint(action));
+    }
+
+    // Inside the table's border
+    let inner = Block::default().borders(Borders::ALL).inner(layout.table);
+    if !inner.contains(position) {
+        return None;
     }
+
+    if row == inner.y {
+        let columns = Layout::horizontal(table_widths(app))
+            .flex(Flex::Start)
+            .spacing(1)
+            .split(Rect {
+                x: inner.x + HIGHLIGHT_SYMBOL.len() as u16,
+                width: inner.width.saturating_sub(HIGHLIGHT_SYMBOL.len() as u16),
+                ..inner
+            });
+        return columns
+            .iter()
+            .skip(usize::from(app.is_fleet))
+            .zip(HEADERS)
+            .find(|(rect, _)| rect.contains(position))
+            .map(|(_, (_, column))| ClickTarget::Header(column));
+    }
+
+    // The table scrolls just far enough to keep the selection visible
+    let placeholders = app.placeholder_count();
+    let visible = usize::from(inner.height - 1);
+    let offset = if app.row_count() == 0 {
+        0
+    } else {
+        (placeholders + app.selected_index + 1).saturating_sub(visible)
+    };
+    let index = (offset + usize::from(row - inner.y - 1)).checked_sub(placeholders)?;
+    (index < app.row_count()).then_some(ClickTarget::Row(index))
 }
 
 /// Render the top bar with logo and hints
-fn render_top_bar(frame: &mut Frame, area: Rect) {
-    let bar = Paragraph::new(Line::from(vec![
-        Span::styled(" ⚓ ", Style::default().fg(COLOR_ACCENT).bold()),
-        Span::styled("Lsport", Style::default().fg(COLOR_ACCENT).bold()),
-        Span::styled(" │ ", Style::default().fg(COLOR_BORDER)),
-        Span::styled(
+fn render_top_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let (line, _) = top_bar_line(app, area.width);
+    let bar = Paragraph::new(line).style(Style::default().bg(app.theme.header_bg));
+    frame.render_widget(bar, area);
+}
+
+/// The top bar's text and the columns each clickable hint covers
+fn top_bar_line(app: &App, width: u16) -> (Line<'static>, Vec<(Action, Range<u16>)>) {
+    let theme = &app.theme;
+    let mut spans = vec![
+        Span::styled(" ⚓ ", Style::default().fg(theme.accent).bold()),
+        Span::styled("Lsport", Style::default().fg(theme.accent).bold()),
+        Span::styled(" │ ", Style::default().fg(theme.border)),
+    ];
+    let mut hints = Vec::new();
+
+    // The host tabs (clickable), or the subtitle while only one host is open
+    if app.tab_labels.is_empty() {
+        spans.push(Span::styled(
             "Localhost Port Monitor",
-            Style::default().fg(COLOR_TEXT_DIM),
-        ),
-        Span::raw(" ".repeat(area.width.saturating_sub(60) as usize)),
-        Span::styled("<?>", Style::default().fg(COLOR_ACCENT)),
-        Span::styled(" Help ", Style::default().fg(COLOR_TEXT_DIM)),
-        Span::styled("<q>", Style::default().fg(COLOR_ACCENT)),
-        Span::styled(" Quit", Style::default().fg(COLOR_TEXT_DIM)),
-    ]))
-    .style(Style::default().bg(COLOR_HEADER_BG));
+            Style::default().fg(theme.text_dim),
+        ));
+    }
+    for (index, label) in app.tab_labels.iter().enumerate() {
+        let start = spans.iter().map(Span::width).sum::<usize>() as u16;
+        let style = if index == app.active_tab {
+            Style::default().fg(theme.bg).bg(theme.accent).bold()
+        } else {
+            Style::default().fg(theme.text_dim)
+        };
+        spans.push(Span::styled(format!(" {} {} ", index + 1, label), style));
+        let end = spans.iter().map(Span::width).sum::<usize>() as u16;
+        if let Ok(number) = u8::try_from(index + 1) {
+            hints.push((Action::SelectTab(number), start..end));
+        }
+    }
 
-    frame.render_widget(bar, area);
+    let mut right = Vec::new();
+    for (action, label) in [(Action::Help, " Help "), (Action::Quit, " Quit")] {
+        let key = app
+            .keymap
+            .first_label(KeyContext::Table, action)
+            .unwrap_or_default();
+        right.push((
+            action,
+            Span::styled(format!("<{}>", key), Style::default().fg(theme.accent)),
+            Span::styled(label, Style::default().fg(theme.text_dim)),
+        ));
+    }
+    let left_width = spans.iter().map(Span::width).sum::<usize>();
+    let right_width: usize = right.iter().map(|(_, k, l)| k.width() + l.width()).sum();
+    spans.push(Span::raw(" ".repeat(
+        (width as usize).saturating_sub(left_width + right_width + 8),
+    )));
+
+    for (action, key, label) in right {
+        let start = spans.iter().map(Span::width).sum::<usize>() as u16;
+        spans.push(key);
+        spans.push(label);
+        let end = spans.iter().map(Span::width).sum::<usize>() as u16;
+        hints.push((action, start..end));
+    }
+
+    (Line::from(spans), hints)
 }
 
 /// Render the context/breadcrumb bar with sort and filter info
 fn render_context_bar(frame: &mut Frame, app: &App, area: Rect) {
-    let sort_col = match app.sort_column {
-        SortColumn::Port => "Port",
-        SortColumn::Protocol => "Protocol",
-        SortColumn::Pid => "PID",
-        SortColumn::ProcessName => "Name",
-        SortColumn::CpuUsage => "CPU%",
-        SortColumn::MemoryUsage => "Memory",
-    };
-    let sort_dir = match app.sort_order {
-        SortOrder::Ascending => "↑",
-        SortOrder::Descending => "↓",
-    };
+    let theme = &app.theme;
+    let sort_col = sort_column_label(app.sort_column);
+    let sort_dir = sort_order_arrow(app.sort_order);
 
-    let mut spans = vec![Span::styled(" 📡 ", Style::default().fg(COLOR_ACCENT2))];
+    let mut spans = vec![Span::styled(" 📡 ", Style::default().fg(theme.accent2))];
 
-    // Show remote host or localhost
-    if let Some(ref host) = app.remote_host {
-        spans.push(Span::styled("Remote: ", Style::default().fg(COLOR_WARNING)));
+    // Show the fleet, the remote host or localhost
+    if app.is_fleet {
+        spans.push(Span::styled(
+            format!("Fleet: {} hosts", app.tab_labels.len().saturating_sub(1)),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+    } else if let Some(ref host) = app.remote_host {
+        spans.push(Span::styled("Remote: ", Style::default().fg(theme.warning)));
         spans.push(Span::styled(
             host.clone(),
             Style::default()
-                .fg(COLOR_ACCENT)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ));
     } else {
         spans.push(Span::styled(
             "localhost",
-            Style::default().fg(COLOR_TEXT).add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Show the active saved view (* once its filter or sort has been changed)
+    if let Some(view) = &app.active_view {
+        let modified = if app.active_view_modified() { "*" } else { "" };
+        spans.push(Span::styled(" │ ", Style::default().fg(theme.border)));
+        spans.push(Span::styled("View: ", Style::default().fg(theme.text_dim)));
+        spans.push(Span::styled(
+            format!("{}{}", view, modified),
+            Style::default()
+                .fg(theme.accent2)
+                .add_modifier(Modifier::BOLD),
         ));
     }
 
     spans.extend(vec![
-        Span::styled(" │ ", Style::default().fg(COLOR_BORDER)),
+        Span::styled(" │ ", Style::default().fg(theme.border)),
         Span::styled(
             format!("{} ", app.entries.len()),
-            Style::default().fg(COLOR_ACCENT),
+            Style::default().fg(theme.accent),
         ),
-        Span::styled("listening", Style::default().fg(COLOR_TEXT_DIM)),
-        Span::styled(" │ ", Style::default().fg(COLOR_BORDER)),
-        Span::styled("Sort: ", Style::default().fg(COLOR_TEXT_DIM)),
+        Span::styled("listening", Style::default().fg(theme.text_dim)),
+        Span::styled(" │ ", Style::default().fg(theme.border)),
+        Span::styled("Sort: ", Style::default().fg(theme.text_dim)),
         Span::styled(
             format!("{}{}", sort_col, sort_dir),
-            Style::default().fg(COLOR_WARNING),
+            Style::default().fg(theme.warning),
         ),
     ]);
 
     // Add filter indicator if active
     if !app.filter.is_empty() {
-        spans.push(Span::styled(" │ ", Style::default().fg(COLOR_BORDER)));
-        if app.filter_is_regex {
-            spans.push(Span::styled("Regex: ", Style::default().fg(COLOR_WARNING)));
-        } else {
-            spans.push(Span::styled(
-                "Filter: ",
-                Style::default().fg(COLOR_TEXT_DIM),
-            ));
-        }
+        spans.push(Span::styled(" │ ", Style::default().fg(theme.border)));
+        spans.push(Span::styled(
+            "Filter: ",
+            Style::default().fg(theme.text_dim),
+        ));
         spans.push(Span::styled(
             format!("\"{}\"", app.filter),
-            Style::default().fg(COLOR_ACCENT),
+            Style::default().fg(if app.filter_error.is_some() {
+                theme.error
+            } else {
+                theme.accent
+            }),
+        ));
+    }
+
+    if let Some(by) = app.group_by {
+        spans.push(Span::styled(" │ ", Style::default().fg(theme.border)));
+        spans.push(Span::styled("Group: ", Style::default().fg(theme.text_dim)));
+        spans.push(Span::styled(by.label(), Style::default().fg(theme.accent2)));
+    }
+
+    // The rows are from the last scan that worked
+    if let Some(error) = &app.scan_error {
+        spans.push(Span::styled(" │ ", Style::default().fg(theme.border)));
+        spans.push(Span::styled(
+            format!("⚠ {}", error),
+            Style::default().fg(theme.error),
+        ));
+    }
+    if !app.host_errors.is_empty() {
+        // The table row only has room for the start of the error
+        let text = match app.host_errors.as_slice() {
+            [(host, error)] => format!("⚠ {}: {}", host, error),
+            errors => format!("⚠ {} hosts unreachable", errors.len()),
+        };
+        spans.push(Span::styled(" │ ", Style::default().fg(theme.border)));
+        spans.push(Span::styled(text, Style::default().fg(theme.error)));
+    }
+
+    // Show how many rows bulk actions will apply to
+    if !app.marked.is_empty() {
+        spans.push(Span::styled(" │ ", Style::default().fg(theme.border)));
+        spans.push(Span::styled(
+            format!("✓ {} marked", app.marked.len()),
+            Style::default()
+                .fg(theme.accent2)
+                .add_modifier(Modifier::BOLD),
         ));
     }
 
-    let bar = Paragraph::new(Line::from(spans)).style(Style::default().bg(COLOR_BG));
+    let bar = Paragraph::new(Line::from(spans)).style(Style::default().bg(theme.bg));
 
     frame.render_widget(bar, area);
 }
 
+/// Header label for a sort column
+fn sort_column_label(column: SortColumn) -> &'static str {
+    match column {
+        SortColumn::Port => "Port",
+        SortColumn::Protocol => "Protocol",
+        SortColumn::Pid => "PID",
+        SortColumn::ProcessName => "Name",
+        SortColumn::CpuUsage => "CPU%",
+        SortColumn::MemoryUsage => "Memory",
+        SortColumn::Uptime => "Uptime",
+    }
+}
+
+/// Arrow shown next to the sort column
+fn sort_order_arrow(order: SortOrder) -> &'static str {
+    match order {
+        SortOrder::Ascending => "↑",
+        SortOrder::Descending => "↓",
+    }
+}
+
 /// Render the main process table
 fn render_table(frame: &mut Frame, app: &App, area: Rect) {
-    // Define table headers with sort indicators and shortcut keys
-    // Format: (display_name, sort_column, shortcut_key)
-    let headers = [
-        ("PORT", SortColumn::Port, "P/1"),
-        ("PROTO", SortColumn::Protocol, "O/2"),
-        ("PID", SortColumn::Pid, "I/3"),
-        ("NAME", SortColumn::ProcessName, "N/4"),
-        ("CPU%", SortColumn::CpuUsage, "C/5"),
-        ("MEM", SortColumn::MemoryUsage, "M/6"),
-    ];
-
-    let header_cells = headers.iter().map(|(name, col, key)| {
+    let theme = &app.theme;
+    // Define table headers with sort indicators and shortcut keys from the keymap
+    let header_cells = HEADERS.iter().map(|(name, col)| {
+        let key = app.keymap.label(KeyContext::Table, Action::SortBy(*col));
         let is_sorted = app.sort_column == *col;
         let indicator = if is_sorted {
             match app.sort_order {
@@ -171,76 +454,111 @@ fn render_table(frame: &mut Frame, app: &App, area: Rect) {
 
         let style = if is_sorted {
             Style::default()
-                .fg(COLOR_ACCENT)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD)
         } else {
             Style::default()
-                .fg(COLOR_TEXT_DIM)
+                .fg(theme.text_dim)
                 .add_modifier(Modifier::BOLD)
         };
 
-        // Show: "NAME[N/4]" or "NAME[N/4]▲" when sorted
-        let text = if is_sorted {
-            format!("{}[{}]{}", name, key, indicator)
+        // Show: "NAME[N/4]" or "NAME[N/4]▲" when sorted (no brackets when unbound)
+        let key = if key.is_empty() {
+            key
         } else {
-            format!("{}[{}]", name, key)
+            format!("[{}]", key)
         };
+        let text = format!("{}{}{}", name, key, indicator);
 
         Cell::from(text).style(style)
     });
 
+    // Health badges aren't sortable, so the header has no shortcut
+    let header_cells = header_cells.chain(std::iter::once(
+        Cell::from("HEALTH").style(
+            Style::default()
+                .fg(theme.text_dim)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ));
+
+    // The fleet view says which host each row is on
+    let header_cells: Vec<Cell> = app
+        .is_fleet
+        .then(|| {
+            Cell::from("HOST").style(
+                Style::default()
+                    .fg(theme.text_dim)
+                    .add_modifier(Modifier::BOLD),
+            )
+        })
+        .into_iter()
+        .chain(header_cells)
+        .collect();
+
     let header = Row::new(header_cells)
-        .style(Style::default().bg(COLOR_HEADER_BG))
+        .style(Style::default().bg(theme.header_bg))
         .height(1);
 
-    // Create rows from entries with alternating colors
-    let rows: Vec<Row> = app
-        .entries
+    // Hosts that failed to scan and pinned ports nobody listens on come first, as placeholder rows
+    let mut rows: Vec<Row> = app
+        .host_errors
         .iter()
-        .enumerate()
-        .map(|(idx, entry)| {
-            let is_selected = idx == app.selected_index;
-            create_row(entry, idx, is_selected)
-        })
+        .map(|(host, error)| host_error_row(theme, host, error))
         .collect();
+    rows.extend(
+        app.pinned_down()
+            .iter()
+            .map(|(host, port)| pinned_down_row(app, host.as_deref(), *port)),
+    );
+    let placeholders = rows.len();
 
-    // Define column widths (accounting for [key] indicators in headers)
-    let widths = [
-        Constraint::Length(12), // PORT[P/1]▲
-        Constraint::Length(12), // PROTO[O/2]
-        Constraint::Length(11), // PID[I/3]
-        Constraint::Min(15),    // NAME[N/4] + process name
-        Constraint::Length(12), // CPU%[C/5]
-        Constraint::Length(12), // MEM[M/6]
-    ];
+    // Create rows from entries (and group headers while grouped) with alternating colors
+    match app.group_by {
+        None => rows.extend(
+            app.entries
+                .iter()
+                .enumerate()
+                .map(|(idx, entry)| create_row(app, entry, idx, 0)),
+        ),
+        Some(_) => rows.extend(app.rows.iter().zip(&app.row_depths).enumerate().map(
+            |(idx, (row, &depth))| match *row {
+                TableRow::Entry(index) => create_row(app, &app.entries[index], idx, depth),
+                TableRow::Group(index) => group_row(app, &app.groups[index], idx, depth),
+            },
+        )),
+    }
 
     // Create the table
-    let table = Table::new(rows, widths)
+    let table = Table::new(rows, table_widths(app))
         .header(header)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(COLOR_BORDER))
-                .style(Style::default().bg(COLOR_BG)),
+                .border_style(Style::default().fg(theme.border))
+                .style(Style::default().bg(theme.bg)),
         )
         .row_highlight_style(
             Style::default()
-                .bg(COLOR_SELECTED_BG)
-                .fg(COLOR_TEXT)
+                .bg(theme.selected_bg)
+                .fg(theme.text)
                 .add_modifier(Modifier::BOLD),
         )
-        .highlight_symbol("▶ ");
+        .highlight_symbol(HIGHLIGHT_SYMBOL)
+        // Always reserve the symbol's column so header positions don't shift
+        .highlight_spacing(HighlightSpacing::Always);
 
     // Create table state for selection
     let mut state = TableState::default();
-    if !app.entries.is_empty() {
-        state.select(Some(app.selected_index));
+    if app.row_count() > 0 {
+        // Placeholder rows aren't selectable, so skip past them
+        state.select(Some(placeholders + app.selected_index));
     }
 
     frame.render_stateful_widget(table, area, &mut state);
 
     // Show empty state message if no entries
-    if app.entries.is_empty() {
+    if app.entries.is_empty() && placeholders == 0 {
         let msg = if !app.filter.is_empty() {
             format!("No ports matching \"{}\"", app.filter)
         } else {
@@ -249,11 +567,11 @@ fn render_table(frame: &mut Frame, app: &App, area: Rect) {
 
         let empty_msg = Paragraph::new(vec![
             Line::from(""),
-            Line::from(Span::styled("⚠", Style::default().fg(COLOR_WARNING))),
-            Line::from(Span::styled(msg, Style::default().fg(COLOR_TEXT_DIM))),
+            Line::from(Span::styled("⚠", Style::default().fg(theme.warning))),
+            Line::from(Span::styled(msg, Style::default().fg(theme.text_dim))),
         ])
         .alignment(Alignment::Center)
-        .style(Style::default().bg(COLOR_BG));
+        .style(Style::default().bg(theme.bg));
 
         let inner_area = Rect {
             x: area.x + 2,
@@ -265,296 +583,1093 @@ fn render_table(frame: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+/// Column widths of the table, led by the host in the fleet view
+fn table_widths(app: &App) -> Vec<Constraint> {
+    let host = app.is_fleet.then_some(Constraint::Length(HOST_WIDTH));
+    host.into_iter().chain(COLUMN_WIDTHS).collect()
+}
+
+/// Put the host cell in front of a row's cells in the fleet view
+fn with_host(app: &App, host: &str, cells: Vec<Cell<'static>>) -> Vec<Cell<'static>> {
+    if !app.is_fleet {
+        return cells;
+    }
+    let host = Cell::from(host.to_string()).style(Style::default().fg(app.theme.accent2));
+    std::iter::once(host).chain(cells).collect()
+}
+
+/// Process name with health tags appended (e.g. "node [defunct]")
+fn process_name_label(entry: &PortEntry) -> String {
+    let mut label = entry.process_name.clone();
+    for (flag, tag) in [
+        (entry.is_zombie, " [defunct]"),
+        (entry.is_orphan, " [orphan]"),
+    ] {
+        if flag {
+            label.push_str(tag);
+        }
+    }
+    label
+}
+
+/// Indentation of a row's name nested `depth` levels deep in a group or the process tree
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
 /// Create a table row from a PortEntry
-fn create_row(entry: &PortEntry, idx: usize, is_selected: bool) -> Row<'static> {
+fn create_row(app: &App, entry: &PortEntry, idx: usize, depth: usize) -> Row<'static> {
+    let theme = &app.theme;
+    let is_selected = idx == app.selected_index;
+    let is_pinned = app.is_pinned(entry);
+    let is_marked = app.is_marked(entry);
+    let memory_trend = app
+        .history
+        .get(entry.host.as_deref(), entry.pid)
+        .and_then(PidHistory::memory_trend);
+
     // Alternating row background
     let row_bg = if is_selected {
-        COLOR_SELECTED_BG
+        theme.selected_bg
     } else if idx.is_multiple_of(2) {
-        COLOR_BG
+        theme.bg
     } else {
-        COLOR_ROW_ALT
+        theme.row_alt
     };
 
     // Determine text color based on status
     let text_color = if entry.is_zombie {
-        COLOR_ERROR
+        theme.error
     } else if is_selected {
-        COLOR_TEXT
+        theme.text
     } else {
-        COLOR_TEXT_DIM
+        theme.text_dim
     };
 
     // CPU color coding
     let cpu_color = if entry.cpu_usage > 80.0 {
-        COLOR_ERROR
+        theme.error
     } else if entry.cpu_usage > 40.0 {
-        COLOR_WARNING
+        theme.warning
     } else if entry.cpu_usage > 10.0 {
-        COLOR_ACCENT2
+        theme.accent2
+    } else {
+        text_color
+    };
+
+    // Highlight long-running processes (likely stale dev servers)
+    let uptime_color = match entry.uptime_secs() {
+        Some(secs) if secs >= STALE_UPTIME_SECS => theme.warning,
+        _ => text_color,
+    };
+
+    // Process name color: defunct > runaway > orphaned
+    let name_color = if entry.is_zombie {
+        theme.error
+    } else if entry.is_runaway() {
+        theme.runaway
+    } else if entry.is_orphan {
+        theme.orphan
     } else {
         text_color
     };
 
     // Protocol badge color
     let proto_color = match entry.protocol {
-        crate::app::Protocol::Tcp => COLOR_ACCENT,
-        crate::app::Protocol::Udp => COLOR_ACCENT2,
+        crate::app::Protocol::Tcp => theme.accent,
+        crate::app::Protocol::Udp => theme.accent2,
     };
 
     let cells = vec![
-        Cell::from(format!("{:>5}", entry.port)).style(Style::default().fg(if is_selected {
-            COLOR_ACCENT
-        } else {
-            text_color
-        })),
+        Cell::from(Line::from(vec![
+            mark_span(theme, is_marked),
+            Span::styled(
+                port_label(entry.port, is_pinned),
+                Style::default().fg(if is_selected {
+                    theme.accent
+                } else {
+                    text_color
+                }),
+            ),
+        ])),
         Cell::from(entry.protocol.to_string()).style(Style::default().fg(proto_color)),
         Cell::from(format!("{:>6}", entry.pid)).style(Style::default().fg(text_color)),
-        Cell::from(entry.process_name.clone()).style(Style::default().fg(if entry.is_zombie {
-            COLOR_ERROR
-        } else {
-            text_color
-        })),
+        Cell::from(format!("{}{}", indent(depth), process_name_label(entry)))
+            .style(Style::default().fg(name_color)),
         Cell::from(format!("{:>5.1}%", entry.cpu_usage)).style(Style::default().fg(cpu_color)),
-        Cell::from(entry.memory_display.clone()).style(Style::default().fg(text_color)),
+        Cell::from(memory_with_trend(theme, entry, memory_trend, text_color)),
+        Cell::from(entry.uptime_display()).style(Style::default().fg(uptime_color)),
+        Cell::from(health_badges(theme, entry)),
+    ];
+    let cells = with_host(app, entry.host.as_deref().unwrap_or("-"), cells);
+
+    Row::new(cells).style(Style::default().bg(row_bg)).height(1)
+}
+
+/// Create the header row of a group: port count, summed CPU and memory, port list.
+/// Process tree nodes only show the process and the ports below it.
+fn group_row(app: &App, group: &Group, idx: usize, depth: usize) -> Row<'static> {
+    let theme = &app.theme;
+    let is_selected = idx == app.selected_index;
+    let row_bg = if is_selected {
+        theme.selected_bg
+    } else if idx.is_multiple_of(2) {
+        theme.bg
+    } else {
+        theme.row_alt
+    };
+    let text_color = if is_selected {
+        theme.text
+    } else {
+        theme.text_dim
+    };
+
+    let arrow = if app.is_expanded(&group.key) {
+        "▾"
+    } else {
+        "▸"
+    };
+    let is_marked = group
+        .entries
+        .iter()
+        .all(|&index| app.is_marked(&app.entries[index]));
+    let pids = match group.pids.as_slice() {
+        [pid] => pid.to_string(),
+        pids => format!("{} pids", pids.len()),
+    };
+    let ports: Vec<String> = group
+        .ports
+        .iter()
+        .map(|port| format!(":{}", port))
+        .collect();
+    let uptime = group
+        .uptime_secs
+        .map(format_uptime)
+        .unwrap_or_else(|| "-".to_string());
+
+    let ports = Span::styled(
+        format!(" {}", ports.join(" ")),
+        Style::default().fg(theme.text_dim),
+    );
+    let mut hosts: Vec<&str> = group
+        .entries
+        .iter()
+        .filter_map(|&index| app.entries[index].host.as_deref())
+        .collect();
+    hosts.sort_unstable();
+    hosts.dedup();
+    let host = match hosts.as_slice() {
+        [] => "-".to_string(),
+        [host] => host.to_string(),
+        hosts => format!("{} hosts", hosts.len()),
+    };
+
+    if let GroupKey::Process(_, pid) = group.key {
+        let cells = vec![
+            Cell::from(Line::from(mark_span(theme, is_marked))),
+            Cell::from(""),
+            Cell::from(format!("{:>6}", pid)).style(Style::default().fg(text_color)),
+            Cell::from(Line::from(vec![
+                Span::styled(
+                    format!("{}{} ", indent(depth), arrow),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::styled(group.name.clone(), Style::default().fg(theme.text).bold()),
+                ports,
+            ])),
+        ];
+        let cells = with_host(app, &host, cells);
+        return Row::new(cells).style(Style::default().bg(row_bg)).height(1);
+    }
+
+    let cells = vec![
+        Cell::from(Line::from(vec![
+            mark_span(theme, is_marked),
+            Span::styled(
+                format!("{} {} ports", arrow, group.ports.len()),
+                Style::default().fg(theme.accent).bold(),
+            ),
+        ])),
+        Cell::from(group.protocol_label()).style(Style::default().fg(theme.accent)),
+        Cell::from(format!("{:>6}", pids)).style(Style::default().fg(text_color)),
+        Cell::from(Line::from(vec![
+            Span::styled(
+                format!("{}{}", indent(depth), group.name),
+                Style::default().fg(theme.text).bold(),
+            ),
+            ports,
+        ])),
+        Cell::from(format!("{:>5.1}%", group.cpu_usage)).style(Style::default().fg(text_color)),
+        Cell::from(format_memory(group.memory_usage)).style(Style::default().fg(text_color)),
+        Cell::from(uptime).style(Style::default().fg(text_color)),
+        Cell::from(""),
     ];
+    let cells = with_host(app, &host, cells);
 
     Row::new(cells).style(Style::default().bg(row_bg)).height(1)
 }
 
+/// Port number, marked with a pin when pinned
+fn port_label(port: u16, is_pinned: bool) -> String {
+    if is_pinned {
+        format!("📌{:>5}", port)
+    } else {
+        format!("  {:>5}", port)
+    }
+}
+
+/// Check mark in front of a marked row's port, or a blank of the same width
+fn mark_span(theme: &Theme, is_marked: bool) -> Span<'static> {
+    if is_marked {
+        Span::styled("✓", Style::default().fg(theme.accent2).bold())
+    } else {
+        Span::raw(" ")
+    }
+}
+
+/// Placeholder row for a pinned port that nothing is listening on
+fn pinned_down_row(app: &App, host: Option<&str>, port: u16) -> Row<'static> {
+    let theme = &app.theme;
+    let dim = Style::default().fg(theme.text_dim);
+    let cells = vec![
+        Cell::from(Line::from(vec![
+            mark_span(theme, false),
+            Span::styled(port_label(port, true), Style::default().fg(theme.error)),
+        ])),
+        Cell::from("-").style(dim),
+        Cell::from(format!("{:>6}", "-")).style(dim),
+        Cell::from("not listening").style(Style::default().fg(theme.error).italic()),
+        Cell::from(""),
+        Cell::from(""),
+        Cell::from(""),
+        Cell::from(""),
+    ];
+    let cells = with_host(app, host.unwrap_or(LOCAL_LABEL), cells);
+
+    Row::new(cells)
+        .style(Style::default().bg(theme.bg))
+        .height(1)
+}
+
+/// Placeholder row in the fleet view for a host whose scan failed
+fn host_error_row(theme: &Theme, host: &str, error: &str) -> Row<'static> {
+    let error_style = Style::default().fg(theme.error);
+    let cells = vec![
+        Cell::from(host.to_string()).style(error_style),
+        Cell::from(Line::from(vec![
+            mark_span(theme, false),
+            Span::styled(format!("{:>7}", "⚠"), error_style),
+        ])),
+        Cell::from(""),
+        Cell::from(""),
+        // The error spans the columns after NAME, which are empty anyway
+        Cell::from(error.to_string()).style(error_style.italic()),
+    ];
+
+    Row::new(cells)
+        .style(Style::default().bg(theme.bg))
+        .height(1)
+}
+
+/// Memory value followed by a trend arrow (rising memory is highlighted)
+fn memory_with_trend(
+    theme: &Theme,
+    entry: &PortEntry,
+    trend: Option<Trend>,
+    text_color: Color,
+) -> Line<'static> {
+    let mut spans = vec![Span::styled(
+        entry.memory_display.clone(),
+        Style::default().fg(text_color),
+    )];
+    if let Some(trend) = trend {
+        let color = match trend {
+            Trend::Rising => theme.warning,
+            Trend::Falling => theme.accent2,
+            Trend::Steady => theme.text_dim,
+        };
+        spans.push(Span::styled(
+            format!(" {}", trend.arrow()),
+            Style::default().fg(color),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Color for a health alert severity
+fn severity_color(theme: &Theme, severity: Severity) -> Color {
+    match severity {
+        Severity::Info => theme.accent,
+        Severity::Warning => theme.alert,
+        Severity::Critical => theme.error,
+    }
+}
+
+/// One colored badge per health alert, most severe first
+fn health_badges(theme: &Theme, entry: &PortEntry) -> Line<'static> {
+    let mut alerts: Vec<_> = entry.alerts.iter().collect();
+    alerts.sort_by_key(|alert| std::cmp::Reverse(alert.severity));
+
+    let spans = alerts
+        .into_iter()
+        .enumerate()
+        .map(|(i, alert)| {
+            let text = if i == 0 {
+                alert.rule.clone()
+            } else {
+                format!(" {}", alert.rule)
+            };
+            Span::styled(
+                text,
+                Style::default()
+                    .fg(severity_color(theme, alert.severity))
+                    .add_modifier(Modifier::BOLD),
+            )
+        })
+        .collect::<Vec<_>>();
+    Line::from(spans)
+}
+
+/// Render the detail panel with everything known about the selected entry's process
+fn render_detail_panel(frame: &mut Frame, app: &App, area: Rect, beside: bool) {
+    let theme = &app.theme;
+    let label_style = Style::default().fg(theme.text_dim);
+    let value_style = Style::default().fg(theme.text);
+
+    let (title, lines) = match app.selected_entry() {
+        Some(entry) => (
+            format!(" {} (PID {}) ", entry.process_name, entry.pid),
+            detail_lines(app, entry),
+        ),
+        None => (
+            " Details ".to_string(),
+            vec![Line::from(Span::styled(
+                " No process selected",
+                label_style,
+            ))],
+        ),
+    };
+    let text = Paragraph::new(lines)
+        .style(value_style)
+        .wrap(Wrap { trim: false });
+
+    let block = Block::default()
+        .title(Span::styled(
+            title,
+            Style::default().fg(theme.accent).bold(),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.bg));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // CPU/memory sparklines below the text when beside the table, otherwise to its right
+    let history = app
+        .selected_entry()
+        .and_then(|entry| app.history.get(entry.host.as_deref(), entry.pid));
+    let Some(history) = history else {
+        frame.render_widget(text, inner);
+        return;
+    };
+
+    let split = if beside {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(6)])
+            .split(inner)
+    } else {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(30), Constraint::Percentage(40)])
+            .split(inner)
+    };
+    frame.render_widget(text, split[0]);
+    render_history_sparklines(frame, theme, history, split[1]);
+}
+
+/// Everything known about the selected entry's process, one field per line
+fn detail_lines<'a>(app: &'a App, entry: &'a PortEntry) -> Vec<Line<'a>> {
+    let theme = &app.theme;
+    let label_style = Style::default().fg(theme.text_dim);
+    let field = |label: &'static str, value: Option<String>| {
+        Line::from(vec![
+            Span::styled(label, label_style),
+            value.map_or_else(|| Span::styled("-", label_style), Span::raw),
+        ])
+    };
+
+    let sockets = app.sockets_of(entry);
+    let listening: Vec<Span> = sockets
+        .iter()
+        .enumerate()
+        .flat_map(|(i, socket)| {
+            let style = if socket.key() == entry.key() {
+                Style::default().fg(theme.accent).bold()
+            } else {
+                Style::default()
+            };
+            let separator = (i > 0).then(|| Span::raw(", "));
+            separator.into_iter().chain(std::iter::once(Span::styled(
+                socket.socket_display(),
+                style,
+            )))
+        })
+        .collect();
+
+    // Established connections across the process's TCP ports, each port counted once
+    let mut counted: Vec<(u16, usize)> = sockets
+        .iter()
+        .filter_map(|socket| Some((socket.port, socket.connections?)))
+        .collect();
+    counted.dedup_by_key(|(port, _)| *port);
+    let connections = (!counted.is_empty()).then(|| {
+        let total: usize = counted.iter().map(|(_, count)| count).sum();
+        format!("{} established", total)
+    });
+
+    let uptime = entry.start_time.map(|_| {
+        format!(
+            "{} (up {})",
+            entry.start_time_display(),
+            entry.uptime_display()
+        )
+    });
+    let user = format!("{} ({})", entry.user.as_deref().unwrap_or("-"), entry.state);
+
+    vec![
+        field(" Command: ", entry.command.clone()),
+        field(" Exe:     ", entry.exe_path.clone()),
+        field(" Cwd:     ", entry.cwd.clone()),
+        field(" User:    ", Some(user)),
+        Line::from(
+            std::iter::once(Span::styled(" Health:  ", label_style))
+                .chain(if entry.alerts.is_empty() {
+                    vec![Span::styled("OK", Style::default().fg(theme.accent2))]
+                } else {
+                    health_badges(theme, entry).spans
+                })
+                .collect::<Vec<_>>(),
+        ),
+        field(" Started: ", uptime),
+        field(
+            " Parents: ",
+            (!entry.ancestors.is_empty()).then(|| entry.ancestry_display()),
+        ),
+        Line::from(
+            std::iter::once(Span::styled(" Listen:  ", label_style))
+                .chain(listening)
+                .collect::<Vec<_>>(),
+        ),
+        field(" Conns:   ", connections),
+    ]
+}
+
+/// Render CPU and memory sparklines for a process's recent samples
+fn render_history_sparklines(frame: &mut Frame, theme: &Theme, history: &PidHistory, area: Rect) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(3)])
+        .split(area);
+
+    // Only the most recent samples that fit in the available width
+    let width = area.width as usize;
+    let skip = |len: usize| len.saturating_sub(width);
+
+    let cpu: Vec<u64> = history
+        .cpu
+        .iter()
+        .skip(skip(history.cpu.len()))
+        .map(|&c| (c * 10.0) as u64)
+        .collect();
+    let cpu_now = history.cpu.back().copied().unwrap_or(0.0);
+    let cpu_peak = history.cpu.iter().copied().fold(0.0, f32::max);
+
+    let memory: Vec<u64> = history
+        .memory
+        .iter()
+        .skip(skip(history.memory.len()))
+        .map(|&m| m / 1024)
+        .collect();
+    let memory_now = history.memory.back().copied().unwrap_or(0);
+    let memory_peak = history.memory.iter().copied().max().unwrap_or(0);
+    let arrow = history
+        .memory_trend()
+        .map_or(String::new(), |trend| format!(" {}", trend.arrow()));
+
+    // CPU is scaled to at least 100% so an idle process doesn't look busy
+    let cpu_max = cpu.iter().copied().max().unwrap_or(0).max(1000);
+
+    for (row, label, data, max, color) in [
+        (
+            rows[0],
+            format!(" CPU {:.1}% (peak {:.1}%)", cpu_now, cpu_peak),
+            cpu,
+            Some(cpu_max),
+            theme.accent,
+        ),
+        (
+            rows[1],
+            format!(
+                " MEM {}{} (peak {})",
+                format_memory(memory_now),
+                arrow,
+                format_memory(memory_peak)
+            ),
+            memory,
+            None,
+            theme.accent2,
+        ),
+    ] {
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .split(row);
+        frame.render_widget(
+            Paragraph::new(Span::styled(label, Style::default().fg(theme.text_dim))),
+            parts[0],
+        );
+        let mut sparkline = Sparkline::default()
+            .data(data)
+            .style(Style::default().fg(color).bg(theme.bg));
+        if let Some(max) = max {
+            sparkline = sparkline.max(max);
+        }
+        frame.render_widget(sparkline, parts[1]);
+    }
+}
+
+/// Render the port open/close event log, newest first
+fn render_event_log(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let visible = area.height.saturating_sub(2) as usize;
+
+    let lines: Vec<Line> = if app.events.is_empty() {
+        vec![Line::from(Span::styled(
+            " No port changes yet",
+            Style::default().fg(theme.text_dim),
+        ))]
+    } else {
+        app.events
+            .newest_first()
+            .skip(app.events_scroll)
+            .take(visible)
+            .map(|event| {
+                let color = match event.kind {
+                    PortEventKind::Opened => theme.accent2,
+                    PortEventKind::Closed => theme.error,
+                    PortEventKind::OwnerChanged { .. } => theme.warning,
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {} ", format_clock(event.timestamp)),
+                        Style::default().fg(theme.text_dim),
+                    ),
+                    Span::styled(event.to_string(), Style::default().fg(color)),
+                ])
+            })
+            .collect()
+    };
+
+    let title = if app.events_scroll > 0 {
+        format!(
+            " Events ({}, UTC, {} newer hidden) <[/]> scroll ",
+            app.events.len(),
+            app.events_scroll
+        )
+    } else {
+        format!(" Events ({}, UTC) ", app.events.len())
+    };
+
+    let panel = Paragraph::new(lines).block(
+        Block::default()
+            .title(Span::styled(
+                title,
+                Style::default().fg(theme.accent).bold(),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.bg)),
+    );
+
+    frame.render_widget(panel, area);
+}
+
 /// Render the command bar at the bottom
 fn render_command_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let content = if app.connect_mode {
         // Connect input mode
         if app.connect_key_mode {
             Line::from(vec![
-                Span::styled("SSH Key: ", Style::default().fg(COLOR_ACCENT).bold()),
-                Span::styled(&app.connect_key_input, Style::default().fg(COLOR_TEXT)),
-                Span::styled("█", Style::default().fg(COLOR_ACCENT)), // Cursor
+                Span::styled("SSH Key: ", Style::default().fg(theme.accent).bold()),
+                Span::styled(&app.connect_key_input, Style::default().fg(theme.text)),
+                Span::styled("█", Style::default().fg(theme.accent)), // Cursor
                 Span::styled(
                     " (Enter to connect, Tab/Esc to skip)",
-                    Style::default().fg(COLOR_TEXT_DIM),
+                    Style::default().fg(theme.text_dim),
                 ),
             ])
         } else {
             Line::from(vec![
-                Span::styled("Connect: ", Style::default().fg(COLOR_ACCENT).bold()),
-                Span::styled(&app.connect_input, Style::default().fg(COLOR_TEXT)),
-                Span::styled("█", Style::default().fg(COLOR_ACCENT)), // Cursor
+                Span::styled("Connect: ", Style::default().fg(theme.accent).bold()),
+                Span::styled(&app.connect_input, Style::default().fg(theme.text)),
+                Span::styled("█", Style::default().fg(theme.accent)), // Cursor
                 Span::styled(
-                    " (Enter for SSH key, Tab to skip, Esc to cancel)",
-                    Style::default().fg(COLOR_TEXT_DIM),
+                    " (↑/↓ to choose, Enter to connect, Tab to skip the SSH key, Esc to cancel)",
+                    Style::default().fg(theme.text_dim),
                 ),
             ])
         }
-    } else if app.filter_mode {
-        // Filter input mode (like vim command mode)
+    } else if app.view_name_mode {
         Line::from(vec![
-            Span::styled("/", Style::default().fg(COLOR_ACCENT).bold()),
-            Span::styled(&app.filter, Style::default().fg(COLOR_TEXT)),
-            Span::styled("█", Style::default().fg(COLOR_ACCENT)), // Cursor
+            Span::styled("Save view as: ", Style::default().fg(theme.accent).bold()),
+            Span::styled(&app.view_name_input, Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.accent)), // Cursor
+            Span::styled(
+                " (Enter to save, Esc to cancel)",
+                Style::default().fg(theme.text_dim),
+            ),
         ])
+    } else if app.command_mode {
+        command_palette_line(app)
+    } else if app.filter_mode {
+        // Filter input mode (like vim command mode)
+        let mut spans = vec![
+            Span::styled("/", Style::default().fg(theme.accent).bold()),
+            Span::styled(&app.filter, Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.accent)), // Cursor
+        ];
+        if let Some(err) = &app.filter_error {
+            spans.push(Span::styled(
+                format!("  ✗ {}", err),
+                Style::default().fg(theme.error),
+            ));
+        }
+        Line::from(spans)
     } else {
         // Show keybindings or status
         match &app.status_message {
             StatusMessage::Info(msg) => {
                 // Show info message if it's actionable, otherwise show quick help
                 if msg == "Ready" || msg.is_empty() {
-                    Line::from(vec![
-                        Span::styled(" <j/k>", Style::default().fg(COLOR_ACCENT)),
-                        Span::styled(" Navigate ", Style::default().fg(COLOR_TEXT_DIM)),
-                        Span::styled("<Enter>", Style::default().fg(COLOR_ACCENT)),
-                        Span::styled(" Kill ", Style::default().fg(COLOR_TEXT_DIM)),
-                        Span::styled("<s>", Style::default().fg(COLOR_ACCENT)),
-                        Span::styled(" Sort ", Style::default().fg(COLOR_TEXT_DIM)),
-                        Span::styled("<1-6>", Style::default().fg(COLOR_ACCENT)),
-                        Span::styled(" Column ", Style::default().fg(COLOR_TEXT_DIM)),
-                        Span::styled("</>", Style::default().fg(COLOR_ACCENT)),
-                        Span::styled(" Filter ", Style::default().fg(COLOR_TEXT_DIM)),
-                        Span::styled("<c>", Style::default().fg(COLOR_ACCENT)),
-                        Span::styled(" Connect ", Style::default().fg(COLOR_TEXT_DIM)),
-                        Span::styled("<?>", Style::default().fg(COLOR_ACCENT)),
-                        Span::styled(" Help", Style::default().fg(COLOR_TEXT_DIM)),
-                    ])
+                    quick_help(app)
                 } else {
                     Line::from(vec![
-                        Span::styled(" ℹ ", Style::default().fg(COLOR_ACCENT)),
-                        Span::styled(msg.clone(), Style::default().fg(COLOR_TEXT_DIM)),
+                        Span::styled(" ℹ ", Style::default().fg(theme.accent)),
+                        Span::styled(msg.clone(), Style::default().fg(theme.text_dim)),
                     ])
                 }
             }
             StatusMessage::Success(msg) => Line::from(vec![
-                Span::styled(" ✓ ", Style::default().fg(COLOR_ACCENT2).bold()),
-                Span::styled(msg.clone(), Style::default().fg(COLOR_ACCENT2)),
+                Span::styled(" ✓ ", Style::default().fg(theme.accent2).bold()),
+                Span::styled(msg.clone(), Style::default().fg(theme.accent2)),
             ]),
             StatusMessage::Error(msg) => Line::from(vec![
-                Span::styled(" ✗ ", Style::default().fg(COLOR_ERROR).bold()),
-                Span::styled(msg.clone(), Style::default().fg(COLOR_ERROR)),
+                Span::styled(" ✗ ", Style::default().fg(theme.error).bold()),
+                Span::styled(msg.clone(), Style::default().fg(theme.error)),
             ]),
         }
     };
 
-    let bar = Paragraph::new(content).style(Style::default().bg(COLOR_HEADER_BG));
+    let bar = Paragraph::new(content).style(Style::default().bg(theme.header_bg));
 
     frame.render_widget(bar, area);
 }
 
-/// Render help popup
-fn render_help_popup(frame: &mut Frame) {
+/// Render the saved view picker popup
+fn render_view_picker(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(50, 40, frame.area());
+    frame.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = app
+        .views
+        .iter()
+        .enumerate()
+        .map(|(idx, view)| {
+            let is_selected = idx == app.view_picker_index;
+            let is_active = app.active_view.as_deref() == Some(view.name.as_str());
+            let filter = if view.filter.is_empty() {
+                "(no filter)".to_string()
+            } else {
+                view.filter.clone()
+            };
+            let sort = format!(
+                "{}{}",
+                sort_column_label(view.sort),
+                sort_order_arrow(view.order)
+            );
+            let name_style = if is_selected {
+                Style::default().fg(theme.bg).bg(theme.accent).bold()
+            } else {
+                Style::default().fg(theme.text).bold()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    if is_active { " ● " } else { "   " },
+                    Style::default().fg(theme.accent2),
+                ),
+                Span::styled(format!("{:<16}", view.name), name_style),
+                Span::styled(format!("  {}", filter), Style::default().fg(theme.accent)),
+                Span::styled(format!("  {}", sort), Style::default().fg(theme.text_dim)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .title(Span::styled(
+                " Views ",
+                Style::default().fg(theme.accent).bold(),
+            ))
+            .title_bottom(Span::styled(
+                " <Enter> apply  <d> delete  <Esc> close ",
+                Style::default().fg(theme.text_dim),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .style(Style::default().bg(theme.bg)),
+    );
+
+    frame.render_widget(list, area);
+}
+
+/// Render the connect picker: recent and inventory hosts matching the typed text
+fn render_host_picker(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(50, 40, frame.area());
+    frame.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = app
+        .connect_choices()
+        .into_iter()
+        .map(|choice| {
+            let (marker, detail) = if choice.is_new() {
+                (" + ", "new host".to_string())
+            } else {
+                let marker = if choice.recent { " ↺ " } else { "   " };
+                (marker, choice.address.clone())
+            };
+            let groups: Vec<String> = choice.groups.iter().map(|g| format!("@{}", g)).collect();
+            ListItem::new(Line::from(vec![
+                Span::styled(marker, Style::default().fg(theme.accent2)),
+                Span::styled(
+                    format!("{:<16}", choice.name),
+                    Style::default().fg(theme.text).bold(),
+                ),
+                Span::styled(format!("  {}", detail), Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    format!("  {}", groups.join(" ")),
+                    Style::default().fg(theme.accent),
+                ),
+            ]))
+        })
+        .collect();
+    let empty = items.is_empty();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    " Connect ",
+                    Style::default().fg(theme.accent).bold(),
+                ))
+                .title_bottom(Span::styled(
+                    " ↺ recent  <Enter> connect  <Esc> cancel ",
+                    Style::default().fg(theme.text_dim),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent))
+                .style(Style::default().bg(theme.bg)),
+        )
+        .highlight_style(Style::default().fg(theme.bg).bg(theme.accent).bold());
+    let mut state = ListState::default().with_selected(Some(app.connect_picker_index));
+    frame.render_stateful_widget(list, area, &mut state);
+
+    if empty {
+        let hint = Paragraph::new(Span::styled(
+            "Type user@host:port, or list hosts in hosts.toml",
+            Style::default().fg(theme.text_dim),
+        ))
+        .alignment(Alignment::Center);
+        let line = Rect {
+            x: area.x + 1,
+            y: area.y + area.height / 2,
+            width: area.width.saturating_sub(2),
+            height: 1,
+        };
+        frame.render_widget(hint, line);
+    }
+}
+
+/// The command palette prompt, followed by completions or a usage hint
+fn command_palette_line(app: &App) -> Line<'_> {
+    let theme = &app.theme;
+    let mut spans = vec![
+        Span::styled(":", Style::default().fg(theme.accent).bold()),
+        Span::styled(&app.command_input, Style::default().fg(theme.text)),
+        Span::styled("█", Style::default().fg(theme.accent)), // Cursor
+        Span::raw("  "),
+    ];
+
+    if app.command_input.is_empty() {
+        let key = app
+            .keymap
+            .first_label(KeyContext::Prompt, Action::Skip)
+            .unwrap_or_default();
+        spans.push(Span::styled(
+            format!("(<{}> to complete, Enter to run, Esc to cancel)", key),
+            Style::default().fg(theme.text_dim),
+        ));
+        return Line::from(spans);
+    }
+
+    // Candidates being cycled through with Tab, or else what Tab would offer
+    let cycling = !app.command_completions.is_empty();
+    let candidates = if cycling {
+        app.command_completions.clone()
+    } else {
+        palette::complete(&app.command_input, app)
+    };
+
+    // Once a word is finished, say what the command expects next
+    let word_finished = app.command_input.ends_with(char::is_whitespace)
+        || candidates.len() == 1 && candidates[0] == app.command_input;
+    if let Some(usage) = palette::usage(&app.command_input).filter(|_| word_finished && !cycling) {
+        spans.push(Span::styled(usage, Style::default().fg(theme.text_dim)));
+        return Line::from(spans);
+    }
+
+    for (i, candidate) in candidates.iter().take(MAX_COMPLETIONS_SHOWN).enumerate() {
+        // Show only the word being completed
+        let word = candidate
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+        let style = if cycling && i == app.command_completion_index {
+            Style::default().fg(theme.accent).bold()
+        } else {
+            Style::default().fg(theme.text_dim)
+        };
+        spans.push(Span::styled(format!("{} ", word), style));
+    }
+    if candidates.len() > MAX_COMPLETIONS_SHOWN {
+        spans.push(Span::styled("…", Style::default().fg(theme.text_dim)));
+    }
+    Line::from(spans)
+}
+
+/// Render the confirmation popup listing the processes a bulk action will hit
+fn render_confirm_popup(frame: &mut Frame, app: &App, bulk: &PendingBulk) {
+    let theme = &app.theme;
+    let area = centered_rect(50, 40, frame.area());
+    frame.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = bulk
+        .targets
+        .iter()
+        .map(|target| {
+            let ports: Vec<String> = target.ports.iter().map(|p| format!(":{}", p)).collect();
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(" {}", target.name),
+                    Style::default().fg(theme.text).bold(),
+                ),
+                Span::styled(
+                    match &target.host {
+                        Some(host) => format!(" (PID {} on {})", target.pid, host),
+                        None => format!(" (PID {})", target.pid),
+                    },
+                    Style::default().fg(theme.text_dim),
+                ),
+                Span::styled(
+                    format!("  {}", ports.join(", ")),
+                    Style::default().fg(theme.accent),
+                ),
+            ]))
+        })
+        .collect();
+
+    let confirm = app.keymap.label(KeyContext::Confirm, Action::Confirm);
+    let cancel = app.keymap.label(KeyContext::Confirm, Action::Cancel);
+    let list = List::new(items).block(
+        Block::default()
+            .title(Span::styled(
+                format!(" {} ", bulk.question()),
+                Style::default().fg(theme.warning).bold(),
+            ))
+            .title_bottom(Span::styled(
+                format!(" <{}> confirm  <{}> cancel ", confirm, cancel),
+                Style::default().fg(theme.text_dim),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.warning))
+            .style(Style::default().bg(theme.bg)),
+    );
+
+    frame.render_widget(list, area);
+}
+
+/// Key hints shown in the command bar when there's no status message
+fn quick_help(app: &App) -> Line<'static> {
+    let theme = &app.theme;
+    let key = |action| app.keymap.first_label(KeyContext::Table, action);
+    let navigate = match (key(Action::Down), key(Action::Up)) {
+        (Some(down), Some(up)) => Some(format!("{}/{}", down, up)),
+        _ => None,
+    };
+    let hints = [
+        (navigate, "Navigate"),
+        (key(Action::Kill), "Kill"),
+        (key(Action::CycleSort), "Sort"),
+        (key(Action::Filter), "Filter"),
+        (key(Action::ViewPicker), "Views"),
+        (key(Action::Connect), "Connect"),
+        (key(Action::Command), "Command"),
+        (key(Action::Help), "Help"),
+    ];
+
+    let mut spans = vec![Span::raw(" ")];
+    for (keys, label) in hints {
+        let Some(keys) = keys else { continue };
+        spans.push(Span::styled(
+            format!("<{}>", keys),
+            Style::default().fg(theme.accent),
+        ));
+        spans.push(Span::styled(
+            format!(" {} ", label),
+            Style::default().fg(theme.text_dim),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Render help popup (key columns come from the active keymap)
+fn render_help_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let area = centered_rect(60, 70, frame.area());
 
     // Clear the background
     frame.render_widget(Clear, area);
 
-    let help_text = vec![
-        Line::from(""),
-        Line::from(vec![Span::styled(
-            "  NAVIGATION",
-            Style::default().fg(COLOR_ACCENT).bold(),
-        )]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("    ↑/k      ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Move up", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    ↓/j      ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Move down", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    PgUp     ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Page up (10 rows)", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    PgDn     ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Page down (10 rows)", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    Home     ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Go to first", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    End      ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Go to last", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(""),
-        Line::from(vec![Span::styled(
-            "  ACTIONS",
-            Style::default().fg(COLOR_ACCENT).bold(),
-        )]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("    Enter    ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Kill selected process", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    /        ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Filter (supports regex)", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    c        ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Connect to remote host", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    d        ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Disconnect from remote", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    Esc      ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Clear filter / Close help", Style::default().fg(COLOR_TEXT)),
-        ]),
+    let actions: Vec<(Action, String)> = app
+        .keymap
+        .table_actions()
+        .map(|action| (action, app.keymap.label(KeyContext::Table, action)))
+        .collect();
+    let key_width = actions
+        .iter()
+        .map(|(_, keys)| keys.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(7)
+        + 2;
+
+    let mut help_text = Vec::new();
+    for (section, title) in [
+        (Section::Navigation, "  NAVIGATION"),
+        (Section::Actions, "  ACTIONS"),
+        (Section::Sorting, "  SORTING (k9s-style)"),
+    ] {
+        help_text.push(Line::from(""));
+        help_text.push(Line::from(vec![Span::styled(
+            title,
+            Style::default().fg(theme.accent).bold(),
+        )]));
+        help_text.push(Line::from(""));
+        for (action, keys) in actions.iter().filter(|(a, _)| a.section() == section) {
+            help_text.push(Line::from(vec![
+                Span::styled(
+                    format!("    {:<width$}", keys, width = key_width),
+                    Style::default().fg(theme.warning),
+                ),
+                Span::styled(action.description(), Style::default().fg(theme.text)),
+            ]));
+        }
+    }
+    help_text.extend(vec![
         Line::from(vec![
-            Span::styled("    q        ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Quit", Style::default().fg(COLOR_TEXT)),
+            Span::styled("    ", Style::default()),
+            Span::styled(
+                "(Press same key to toggle ↑/↓)",
+                Style::default().fg(theme.text_dim),
+            ),
         ]),
         Line::from(""),
         Line::from(vec![Span::styled(
-            "  SORTING (k9s-style)",
-            Style::default().fg(COLOR_ACCENT).bold(),
+            "  LEGEND",
+            Style::default().fg(theme.accent).bold(),
         )]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("    Shift+P  ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Sort by Port", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    Shift+O  ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Sort by Protocol", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    Shift+I  ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Sort by PID", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    Shift+N  ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Sort by Name", Style::default().fg(COLOR_TEXT)),
-        ]),
-        Line::from(vec![
-            Span::styled("    Shift+C  ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Sort by CPU %", Style::default().fg(COLOR_TEXT)),
+            Span::styled("    ", Style::default()),
+            Span::styled("TCP", Style::default().fg(theme.accent)),
+            Span::styled("  TCP connections", Style::default().fg(theme.text)),
         ]),
         Line::from(vec![
-            Span::styled("    Shift+M  ", Style::default().fg(COLOR_WARNING)),
-            Span::styled("Sort by Memory", Style::default().fg(COLOR_TEXT)),
+            Span::styled("    ", Style::default()),
+            Span::styled("UDP", Style::default().fg(theme.accent2)),
+            Span::styled("  UDP connections", Style::default().fg(theme.text)),
         ]),
         Line::from(vec![
-            Span::styled("    1-6      ", Style::default().fg(COLOR_WARNING)),
+            Span::styled("    ", Style::default()),
+            Span::styled("YELLOW", Style::default().fg(theme.warning)),
             Span::styled(
-                "Quick sort (same as above)",
-                Style::default().fg(COLOR_TEXT),
+                "  Uptime over 7 days (possibly stale)",
+                Style::default().fg(theme.text),
             ),
         ]),
         Line::from(vec![
             Span::styled("    ", Style::default()),
+            Span::styled("RED", Style::default().fg(theme.error)),
             Span::styled(
-                "(Press same key to toggle ↑/↓)",
-                Style::default().fg(COLOR_TEXT_DIM),
+                "  Defunct (zombie) process, not yet reaped",
+                Style::default().fg(theme.text),
             ),
         ]),
-        Line::from(""),
-        Line::from(vec![Span::styled(
-            "  LEGEND",
-            Style::default().fg(COLOR_ACCENT).bold(),
-        )]),
-        Line::from(""),
         Line::from(vec![
             Span::styled("    ", Style::default()),
-            Span::styled("TCP", Style::default().fg(COLOR_ACCENT)),
-            Span::styled("  TCP connections", Style::default().fg(COLOR_TEXT)),
+            Span::styled("HEALTH", Style::default().fg(theme.alert)),
+            Span::styled("  Rule badges: ", Style::default().fg(theme.text)),
+            Span::styled("info ", Style::default().fg(theme.accent)),
+            Span::styled("warning ", Style::default().fg(theme.alert)),
+            Span::styled("critical", Style::default().fg(theme.error)),
         ]),
         Line::from(vec![
             Span::styled("    ", Style::default()),
-            Span::styled("UDP", Style::default().fg(COLOR_ACCENT2)),
-            Span::styled("  UDP connections", Style::default().fg(COLOR_TEXT)),
+            Span::styled("MAROON", Style::default().fg(theme.runaway)),
+            Span::styled(
+                "  Runaway CPU (built-in runaway rule)",
+                Style::default().fg(theme.text),
+            ),
         ]),
         Line::from(vec![
             Span::styled("    ", Style::default()),
-            Span::styled("RED", Style::default().fg(COLOR_ERROR)),
+            Span::styled("MAUVE", Style::default().fg(theme.orphan)),
             Span::styled(
-                "  Zombie process (high CPU + orphaned)",
-                Style::default().fg(COLOR_TEXT),
+                "  Orphaned process (outlived its session, adopted by init)",
+                Style::default().fg(theme.text),
             ),
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("           Press ", Style::default().fg(COLOR_TEXT_DIM)),
-            Span::styled("?", Style::default().fg(COLOR_WARNING)),
-            Span::styled(" or ", Style::default().fg(COLOR_TEXT_DIM)),
-            Span::styled("Esc", Style::default().fg(COLOR_WARNING)),
-            Span::styled(" to close", Style::default().fg(COLOR_TEXT_DIM)),
+            Span::styled("           Press ", Style::default().fg(theme.text_dim)),
+            Span::styled("any key", Style::default().fg(theme.warning)),
+            Span::styled(" to close", Style::default().fg(theme.text_dim)),
         ]),
-    ];
+    ]);
 
     let help = Paragraph::new(help_text)
         .block(
             Block::default()
                 .title(Span::styled(
                     " ⚓ Lsport Help ",
-                    Style::default().fg(COLOR_ACCENT).bold(),
+                    Style::default().fg(theme.accent).bold(),
                 ))
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(COLOR_ACCENT))
-                .style(Style::default().bg(COLOR_BG)),
+                .border_style(Style::default().fg(theme.accent))
+                .style(Style::default().bg(theme.bg)),
         )
         .wrap(Wrap { trim: false });
 
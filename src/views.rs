@@ -0,0 +1,219 @@
+//! Saved views
+//!
+//! A view is a named filter plus sort column and order ("databases",
+//! "dev stack"), saved from the TUI with `V`, switched with `v`/`Tab` and
+//! selected at startup with `lsport --view NAME`. Views are stored in
+//! `$XDG_CONFIG_HOME/lsport/views.toml`, next to the config file:
+//!
+//! ```toml
+//! [[views]]
+//! name = "databases"
+//! filter = "port:3306,5432,6379,27017"
+//! sort = "memory"
+//! order = "desc"
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{SortColumn, SortOrder};
+use crate::config::config_path;
+
+/// A named filter and sort
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct View {
+    pub name: String,
+    /// Filter query (empty shows everything)
+    #[serde(default)]
+    pub filter: String,
+    /// port, protocol, pid, name, cpu, memory or uptime
+    #[serde(default)]
+    pub sort: SortColumn,
+    /// asc or desc
+    #[serde(default)]
+    pub order: SortOrder,
+}
+
+/// On-disk format of the views file
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ViewsFile {
+    views: Vec<View>,
+}
+
+/// Saved views in the order they were created, saved to disk on every change
+#[derive(Debug, Clone, Default)]
+pub struct Views {
+    views: Vec<View>,
+    /// Where to persist the views (None keeps them in memory only)
+    path: Option<PathBuf>,
+}
+
+impl Views {
+    /// Load views from the default file (empty if it doesn't exist)
+    pub fn load() -> Result<Self> {
+        match views_path() {
+            Some(path) => Self::load_from(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load views from `path`, which is also where changes are saved
+    pub fn load_from(path: PathBuf) -> Result<Self> {
+        let mut views = Vec::new();
+        if path.exists() {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let file: ViewsFile = toml::from_str(&text)
+                .with_context(|| format!("Invalid views file {}", path.display()))?;
+            views = file.views;
+        }
+        Ok(Self {
+            views,
+            path: Some(path),
+        })
+    }
+
+    /// Look up a view by name
+    pub fn get(&self, name: &str) -> Option<&View> {
+        self.views.iter().find(|view| view.name == name)
+    }
+
+    /// Look up a view by name, with an error listing the available ones
+    pub fn find(&self, name: &str) -> Result<&View> {
+        if let Some(view) = self.get(name) {
+            return Ok(view);
+        }
+        if self.views.is_empty() {
+            bail!("No view named '{}' (no views saved yet)", name);
+        }
+        let names: Vec<&str> = self.views.iter().map(|v| v.name.as_str()).collect();
+        bail!("No view named '{}' (available: {})", name, names.join(", "))
+    }
+
+    /// The view at `index` in creation order
+    pub fn at(&self, index: usize) -> Option<&View> {
+        self.views.get(index)
+    }
+
+    /// Views in creation order
+    pub fn iter(&self) -> impl Iterator<Item = &View> {
+        self.views.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+
+    /// Add a view, replacing any existing view with the same name, and save
+    pub fn save_view(&mut self, view: View) -> Result<()> {
+        match self.views.iter_mut().find(|v| v.name == view.name) {
+            Some(existing) => *existing = view,
+            None => self.views.push(view),
+        }
+        self.save()
+    }
+
+    /// Delete a view by name and save
+    pub fn remove(&mut self, name: &str) -> Result<()> {
+        self.views.retain(|view| view.name != name);
+        self.save()
+    }
+
+    /// Write the views file (creating its directory if needed)
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let file = ViewsFile {
+            views: self.views.clone(),
+        };
+        let text = toml::to_string(&file)?;
+        fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Path of the views file (`views.toml` next to `config.toml`)
+pub fn views_path() -> Option<PathBuf> {
+    Some(config_path()?.with_file_name("views.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(name: &str, filter: &str) -> View {
+        View {
+            name: name.to_string(),
+            filter: filter.to_string(),
+            sort: SortColumn::MemoryUsage,
+            order: SortOrder::Descending,
+        }
+    }
+
+    #[test]
+    fn test_save_replaces_by_name() {
+        let mut views = Views::default();
+        views.save_view(view("db", "port:5432")).unwrap();
+        views.save_view(view("dev", "port:3000-3999")).unwrap();
+        views.save_view(view("db", "port:5432,6379")).unwrap();
+
+        assert_eq!(views.len(), 2);
+        assert_eq!(views.get("db").unwrap().filter, "port:5432,6379");
+
+        views.remove("db").unwrap();
+        assert!(views.get("db").is_none());
+    }
+
+    #[test]
+    fn test_find_lists_available_views() {
+        let mut views = Views::default();
+        let err = views.find("db").unwrap_err();
+        assert!(err.to_string().contains("no views saved yet"));
+
+        views.save_view(view("dev", "")).unwrap();
+        let err = views.find("db").unwrap_err();
+        assert_eq!(err.to_string(), "No view named 'db' (available: dev)");
+    }
+
+    #[test]
+    fn test_views_persist() {
+        let dir = std::env::temp_dir().join(format!("lsport-views-{}", std::process::id()));
+        let path = dir.join("views.toml");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut views = Views::load_from(path.clone()).unwrap();
+        views.save_view(view("db", "port:5432")).unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        let reloaded = Views::load_from(path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(text.contains("sort = \"memory\""));
+        assert!(text.contains("order = \"desc\""));
+        assert_eq!(reloaded.get("db"), Some(&view("db", "port:5432")));
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let file: ViewsFile = toml::from_str("[[views]]\nname = \"all\"").unwrap();
+        assert_eq!(file.views[0].filter, "");
+        assert_eq!(file.views[0].sort, SortColumn::Port);
+        assert_eq!(file.views[0].order, SortOrder::Ascending);
+
+        assert!(toml::from_str::<ViewsFile>("[[views]]\nname = \"x\"\nsort = \"size\"").is_err());
+    }
+}